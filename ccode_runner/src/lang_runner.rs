@@ -2,14 +2,34 @@
 //!
 //! # Modules
 //!
+//! * `checker` - Public module providing output checkers, deciding whether an output is acceptable
+//! * `interactive` - Public module for running interactive problems, where a solution talks to an interactor program
+//! * `compile_cache` - Public module sharing compiled programs between runs, keyed by a hash of the code and its toolchain
+//! * `determinism` - Public module running a program several times on the same input, flagging non-deterministic output
+//! * `diagnostics` - Public module capturing compiler failures, with their output parsed into diagnostics
 //! * `file_store` - Internal interface and module for managing source code files
 //! * `language_name` - Public module containing language name definitions and utilities
+//! * `multi_program_store` - Public module comparing any number of programs, against an oracle or by majority vote
 //! * `program_store` - Public module for storing and managing program source code with caching support, built on top of file_store.
+//! * `project` - Internal module detecting how to build and run solutions which are directories
 //! * `runner` - Internal module implementing core code execution functionality
 //! * `runner_error_types` - Public module defining error types that can occur during code execution
+//! * `sanitizer` - Public module recognizing the reports of sanitizers stopping a program, in the debug build profile
+//! * `toolchain` - Public module describing how each language is compiled and run, configurable with a TOML file
+//! * `worker_pool` - Internal module keeping interpreters and JVMs running between runs of a program
 
+pub mod checker;
+pub mod compile_cache;
+pub mod determinism;
+pub mod diagnostics;
 pub(crate) mod file_store;
+pub mod interactive;
 pub mod language_name;
+pub mod multi_program_store;
 pub mod program_store;
+pub(crate) mod project;
 pub mod runner;
 pub mod runner_error_types;
+pub mod sanitizer;
+pub mod toolchain;
+pub(crate) mod worker_pool;
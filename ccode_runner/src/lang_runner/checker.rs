@@ -0,0 +1,499 @@
+//! Provides output checkers, which decide whether the output of a test program is acceptable
+//! given the output of the correct program.
+//!
+//! # Types
+//!
+//! - [`Checker`]: Trait implemented by every checker, usable with `ProgramStore::with_checker`
+//! - [`CheckerVerdict`]: Outcome of a check
+//! - [`CheckerKind`]: Serializable selection of a checker, for command line and API use
+//!
+//! # Built-in Checkers
+//!
+//! - [`TextChecker`]: Line by line text comparison, ignoring BOM, EOL style and trailing
+//!   spaces (default)
+//! - [`TokenChecker`]: Whitespace-insensitive token comparison
+//! - [`FloatChecker`]: Token comparison accepting numbers within an absolute or relative tolerance
+//! - [`YesNoChecker`]: Case-insensitive token comparison, for YES/NO style answers
+//! - [`UnorderedLinesChecker`]: Compares the lines of the outputs regardless of their order
+//! - [`SpecialJudgeChecker`]: Runs an external checker program, testlib-style
+//!
+
+use std::fmt;
+use std::io::Write;
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+
+#[cfg(feature = "api")]
+use utoipa::ToSchema;
+
+use super::language_name::LanguageName;
+use super::runner::Language;
+use super::runner_error_types::RunnerErrorType;
+use crate::utils::file_utils;
+use crate::utils::program_utils::{ExecutionLimits, ExecutionResult, ExecutionStatus};
+
+/// Outcome of checking the output of a test program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckerVerdict {
+    /// The output is acceptable
+    Accepted,
+    /// The output is wrong, with a message describing why
+    WrongAnswer(String),
+}
+
+impl CheckerVerdict {
+    /// Returns true if the output was accepted
+    pub fn is_accepted(&self) -> bool {
+        *self == CheckerVerdict::Accepted
+    }
+}
+
+/// Decides whether the output of a test program is acceptable.
+///
+/// Checkers are shared between threads by `ProgramStore`, hence the `Send + Sync` bound.
+pub trait Checker: fmt::Debug + Send + Sync {
+    /// Checks the `actual` output of the test program, given the `input` both programs ran with and
+    /// the `expected` output of the correct program.
+    ///
+    /// Errors are reserved for failures of the checker itself, a wrong output is reported as
+    /// `CheckerVerdict::WrongAnswer`.
+    fn check(
+        &self,
+        input: &str,
+        expected: &str,
+        actual: &str,
+    ) -> Result<CheckerVerdict, Box<RunnerErrorType>>;
+
+    /// Same as `check`, without blocking the async runtime.
+    ///
+    /// Checkers which run programs override it, others check on the calling task.
+    fn check_async<'a>(
+        &'a self,
+        input: &'a str,
+        expected: &'a str,
+        actual: &'a str,
+    ) -> BoxFuture<'a, Result<CheckerVerdict, Box<RunnerErrorType>>> {
+        Box::pin(std::future::ready(self.check(input, expected, actual)))
+    }
+}
+
+/// Checks the output with `check_async`, or with `check` when `blocking`, for code shared by the
+/// blocking and async variants of a run
+pub(crate) async fn check_output(
+    checker: &dyn Checker,
+    blocking: bool,
+    input: &str,
+    expected: &str,
+    actual: &str,
+) -> Result<CheckerVerdict, Box<RunnerErrorType>> {
+    match blocking {
+        true => checker.check(input, expected, actual),
+        false => checker.check_async(input, expected, actual).await,
+    }
+}
+
+/// Compares the outputs line by line, ignoring BOM, EOL differences and trailing spaces/tabs.
+///
+/// This is the default checker of `ProgramStore`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextChecker;
+
+impl Checker for TextChecker {
+    fn check(
+        &self,
+        _input: &str,
+        expected: &str,
+        actual: &str,
+    ) -> Result<CheckerVerdict, Box<RunnerErrorType>> {
+        if file_utils::string_diff(expected, actual) {
+            Ok(CheckerVerdict::WrongAnswer(String::from(
+                "Output differs from the expected output",
+            )))
+        } else {
+            Ok(CheckerVerdict::Accepted)
+        }
+    }
+}
+
+/// Compares the whitespace separated tokens of the outputs, ignoring how they are laid out
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenChecker;
+
+impl Checker for TokenChecker {
+    fn check(
+        &self,
+        _input: &str,
+        expected: &str,
+        actual: &str,
+    ) -> Result<CheckerVerdict, Box<RunnerErrorType>> {
+        Ok(compare_tokens(expected, actual, |expected, actual| {
+            expected == actual
+        }))
+    }
+}
+
+/// Compares the tokens of the outputs, accepting numbers within either tolerance of the expected
+/// number. Tokens which are not numbers must match exactly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloatChecker {
+    /// Largest accepted absolute difference
+    pub absolute_tolerance: f64,
+    /// Largest accepted difference, relative to the expected number
+    pub relative_tolerance: f64,
+}
+
+impl FloatChecker {
+    /// Default tolerance, as commonly used by problems with floating point answers
+    pub const DEFAULT_TOLERANCE: f64 = 1e-6;
+
+    pub fn new(absolute_tolerance: f64, relative_tolerance: f64) -> Self {
+        Self {
+            absolute_tolerance,
+            relative_tolerance,
+        }
+    }
+
+    fn is_close(&self, expected: f64, actual: f64) -> bool {
+        if expected.is_nan() || actual.is_nan() {
+            return expected.is_nan() && actual.is_nan();
+        }
+        if expected.is_infinite() || actual.is_infinite() {
+            return expected == actual;
+        }
+
+        let difference = (expected - actual).abs();
+        difference <= self.absolute_tolerance
+            || difference <= self.relative_tolerance * expected.abs()
+    }
+}
+
+impl Default for FloatChecker {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TOLERANCE, Self::DEFAULT_TOLERANCE)
+    }
+}
+
+impl Checker for FloatChecker {
+    fn check(
+        &self,
+        _input: &str,
+        expected: &str,
+        actual: &str,
+    ) -> Result<CheckerVerdict, Box<RunnerErrorType>> {
+        Ok(compare_tokens(expected, actual, |expected, actual| match (
+            expected.parse::<f64>(),
+            actual.parse::<f64>(),
+        ) {
+            (Ok(expected), Ok(actual)) => self.is_close(expected, actual),
+            _ => expected == actual,
+        }))
+    }
+}
+
+/// Compares the tokens of the outputs case-insensitively, so `YES`, `Yes` and `yes` are the same
+/// answer
+#[derive(Debug, Clone, Copy, Default)]
+pub struct YesNoChecker;
+
+impl Checker for YesNoChecker {
+    fn check(
+        &self,
+        _input: &str,
+        expected: &str,
+        actual: &str,
+    ) -> Result<CheckerVerdict, Box<RunnerErrorType>> {
+        Ok(compare_tokens(expected, actual, |expected, actual| {
+            expected.eq_ignore_ascii_case(actual)
+        }))
+    }
+}
+
+/// Compares the lines of the outputs regardless of their order, for problems accepting the
+/// answers in any order. Trailing whitespace and empty lines are ignored.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnorderedLinesChecker;
+
+impl Checker for UnorderedLinesChecker {
+    fn check(
+        &self,
+        _input: &str,
+        expected: &str,
+        actual: &str,
+    ) -> Result<CheckerVerdict, Box<RunnerErrorType>> {
+        fn sorted_lines(output: &str) -> Vec<&str> {
+            let mut lines: Vec<&str> = output
+                .lines()
+                .map(str::trim_end)
+                .filter(|line| !line.is_empty())
+                .collect();
+            lines.sort_unstable();
+            lines
+        }
+
+        let expected_lines = sorted_lines(expected);
+        let actual_lines = sorted_lines(actual);
+
+        if expected_lines == actual_lines {
+            return Ok(CheckerVerdict::Accepted);
+        }
+
+        let message = match expected_lines
+            .iter()
+            .find(|line| !actual_lines.contains(line))
+        {
+            Some(line) => format!("Expected line `{line}` is missing"),
+            None => format!(
+                "Expected {} lines, found {}",
+                expected_lines.len(),
+                actual_lines.len()
+            ),
+        };
+        Ok(CheckerVerdict::WrongAnswer(message))
+    }
+}
+
+/// Compares the tokens of both outputs pairwise, reporting the first mismatch
+fn compare_tokens(
+    expected: &str,
+    actual: &str,
+    tokens_match: impl Fn(&str, &str) -> bool,
+) -> CheckerVerdict {
+    let mut expected_tokens = expected.split_whitespace();
+    let mut actual_tokens = actual.split_whitespace();
+
+    for position in 1.. {
+        match (expected_tokens.next(), actual_tokens.next()) {
+            (None, None) => return CheckerVerdict::Accepted,
+            (Some(expected), Some(actual)) if tokens_match(expected, actual) => {}
+            (Some(expected), Some(actual)) => {
+                return CheckerVerdict::WrongAnswer(format!(
+                    "Token {position} differs, expected `{expected}`, found `{actual}`"
+                ));
+            }
+            (Some(expected), None) => {
+                return CheckerVerdict::WrongAnswer(format!(
+                    "Output ended early, expected `{expected}` as token {position}"
+                ));
+            }
+            (None, Some(actual)) => {
+                return CheckerVerdict::WrongAnswer(format!(
+                    "Unexpected extra token `{actual}` at token {position}"
+                ));
+            }
+        }
+    }
+
+    unreachable!("Token positions are unbounded")
+}
+
+/// Runs an external checker program (special judge), testlib-style.
+///
+/// The checker is run as `checker <input_file> <output_file> <answer_file>`, with the test
+/// program's output as the output file and the correct program's output as the answer file.
+/// Its exit code is the verdict, following testlib:
+/// - `0`: Accepted
+/// - `1` (wrong answer), `2` (presentation error) or `7` (partially correct): Wrong answer, with
+///   the checker's message from stderr (or stdout)
+/// - Anything else (including `3`, a checker failure): Error, as the checker itself failed
+#[derive(Debug)]
+pub struct SpecialJudgeChecker {
+    checker: Language,
+}
+
+impl SpecialJudgeChecker {
+    /// Wraps an already compiled checker program, which runs with its own execution limits
+    pub fn new(checker: Language) -> Self {
+        Self { checker }
+    }
+}
+
+impl Checker for SpecialJudgeChecker {
+    fn check(
+        &self,
+        input: &str,
+        expected: &str,
+        actual: &str,
+    ) -> Result<CheckerVerdict, Box<RunnerErrorType>> {
+        let files = JudgeFiles::new(input, expected, actual)?;
+        let result = self.checker.run_program_code_with_args("", &files.args())?;
+        judge_verdict(result)
+    }
+
+    fn check_async<'a>(
+        &'a self,
+        input: &'a str,
+        expected: &'a str,
+        actual: &'a str,
+    ) -> BoxFuture<'a, Result<CheckerVerdict, Box<RunnerErrorType>>> {
+        Box::pin(async move {
+            let files = JudgeFiles::new(input, expected, actual)?;
+            let result = self
+                .checker
+                .run_program_code_with_args_async("", &files.args())
+                .await?;
+            judge_verdict(result)
+        })
+    }
+}
+
+/// Files given to the special judge, removed once dropped
+struct JudgeFiles {
+    input: NamedTempFile,
+    output: NamedTempFile,
+    answer: NamedTempFile,
+}
+
+impl JudgeFiles {
+    fn new(input: &str, expected: &str, actual: &str) -> Result<Self, Box<RunnerErrorType>> {
+        Ok(JudgeFiles {
+            input: write_temp_file(input)?,
+            output: write_temp_file(actual)?,
+            answer: write_temp_file(expected)?,
+        })
+    }
+
+    /// `<input_file> <output_file> <answer_file>`
+    fn args(&self) -> [&str; 3] {
+        [&self.input, &self.output, &self.answer]
+            .map(|file| file.path().to_str().unwrap_or_default())
+    }
+}
+
+/// Verdict of the special judge from how it exited
+fn judge_verdict(result: ExecutionResult) -> Result<CheckerVerdict, Box<RunnerErrorType>> {
+    let message = match result.stderr_lossy().trim() {
+        "" => result.stdout_lossy().trim().to_string(),
+        stderr => stderr.to_string(),
+    };
+
+    match result.status {
+        ExecutionStatus::Success => Ok(CheckerVerdict::Accepted),
+        ExecutionStatus::NonZeroExit(1 | 2 | 7) => Ok(CheckerVerdict::WrongAnswer(message)),
+        status => Err(Box::new(RunnerErrorType::CheckerFailed(format!(
+            "{status:?}: {message}"
+        )))),
+    }
+}
+
+pub(crate) fn write_temp_file(content: &str) -> Result<NamedTempFile, Box<RunnerErrorType>> {
+    let mut file = NamedTempFile::new()
+        .map_err(|err| Box::new(RunnerErrorType::FileCreationError(Box::new(err))))?;
+    file.write_all(content.as_bytes())
+        .map_err(|err| Box::new(RunnerErrorType::FileCreationError(Box::new(err))))?;
+    Ok(file)
+}
+
+/// Serializable selection of a checker, for choosing one from the command line or an API request
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "api", derive(ToSchema))]
+pub enum CheckerKind {
+    /// See [`TextChecker`]
+    #[default]
+    Text,
+    /// See [`TokenChecker`]
+    Token,
+    /// See [`FloatChecker`]
+    Float {
+        absolute_tolerance: f64,
+        relative_tolerance: f64,
+    },
+    /// See [`YesNoChecker`]
+    YesNo,
+    /// See [`UnorderedLinesChecker`]
+    UnorderedLines,
+    /// See [`SpecialJudgeChecker`], with the source code of the checker program
+    SpecialJudge {
+        code: String,
+        language: LanguageName,
+    },
+}
+
+impl CheckerKind {
+    /// Creates the selected checker, compiling the special judge if needed, which runs with the
+    /// `limits` (e.g. a sandbox, for a judge from an untrusted source)
+    pub fn build(self, limits: ExecutionLimits) -> Result<Box<dyn Checker>, Box<RunnerErrorType>> {
+        Ok(match self {
+            CheckerKind::Text => Box::new(TextChecker),
+            CheckerKind::Token => Box::new(TokenChecker),
+            CheckerKind::Float {
+                absolute_tolerance,
+                relative_tolerance,
+            } => Box::new(FloatChecker::new(absolute_tolerance, relative_tolerance)),
+            CheckerKind::YesNo => Box::new(YesNoChecker),
+            CheckerKind::UnorderedLines => Box::new(UnorderedLinesChecker),
+            CheckerKind::SpecialJudge { code, language } => Box::new(SpecialJudgeChecker::new(
+                Language::new_from_text_with_limits(&code, language, false, limits)?,
+            )),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Checker, CheckerVerdict, FloatChecker, TextChecker, TokenChecker, UnorderedLinesChecker,
+        YesNoChecker,
+    };
+
+    fn is_accepted(checker: &dyn Checker, expected: &str, actual: &str) -> bool {
+        checker.check("", expected, actual).unwrap().is_accepted()
+    }
+
+    #[test]
+    fn text_checker_ignores_trailing_whitespace_only() {
+        assert!(is_accepted(&TextChecker, "1 2\n3\n", "1 2  \r\n3\n"));
+        assert!(!is_accepted(&TextChecker, "1 2\n3\n", "1  2\n3\n"));
+    }
+
+    #[test]
+    fn token_checker_ignores_layout() {
+        assert!(is_accepted(&TokenChecker, "1 2\n3\n", "1\n2   3"));
+        assert!(!is_accepted(&TokenChecker, "1 2 3", "1 2"));
+        assert_eq!(
+            TokenChecker.check("", "1 2 3", "1 5 3").unwrap(),
+            CheckerVerdict::WrongAnswer(String::from("Token 2 differs, expected `2`, found `5`"))
+        );
+    }
+
+    #[test]
+    fn float_checker_accepts_within_tolerance() {
+        let checker = FloatChecker::default();
+
+        assert!(is_accepted(&checker, "0.3333333", "0.33333334"));
+        assert!(is_accepted(&checker, "1000000000.0", "1000000100.0"));
+        assert!(!is_accepted(&checker, "0.5", "0.51"));
+        assert!(is_accepted(&checker, "answer 1.5", "answer 1.5000001"));
+        assert!(!is_accepted(&checker, "answer 1.5", "Answer 1.5"));
+    }
+
+    #[test]
+    fn float_checker_uses_the_larger_tolerance() {
+        let checker = FloatChecker::new(0.1, 0.0);
+
+        assert!(is_accepted(&checker, "10", "10.09"));
+        assert!(!is_accepted(&checker, "10", "10.2"));
+    }
+
+    #[test]
+    fn yes_no_checker_ignores_case() {
+        assert!(is_accepted(&YesNoChecker, "YES\nNO", "yes\nNo"));
+        assert!(!is_accepted(&YesNoChecker, "YES", "NO"));
+    }
+
+    #[test]
+    fn unordered_lines_checker_ignores_order() {
+        assert!(is_accepted(
+            &UnorderedLinesChecker,
+            "1 2\n3 4\n",
+            "3 4\n1 2"
+        ));
+        assert!(!is_accepted(
+            &UnorderedLinesChecker,
+            "1 2\n3 4\n",
+            "1 2\n1 2\n"
+        ));
+        assert!(!is_accepted(&UnorderedLinesChecker, "1 2\n3 4\n", "1 2\n"));
+    }
+}
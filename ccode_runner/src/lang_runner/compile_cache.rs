@@ -0,0 +1,442 @@
+//! Shares compiled programs between runs, so the same code is compiled only once.
+//!
+//! Entries are keyed by a hash of the source code, its language and file name, the compile
+//! commands of its toolchain (including their flags), and the installed compilers they run (their
+//! resolved path, size and modification time), so changing any of them compiles again. The
+//! cache lives in a directory shared by every process using it, e.g. `cpast test` and `cpast_api`,
+//! with one sub-directory per entry:
+//!
+//! ```text
+//! <cache dir>/
+//! └── 3f9a…c1/          # Hash of the source, language, file name and toolchain
+//!     ├── .cpast_entry  # Marks a complete entry, its modification time is the last use
+//!     └── source        # Compiled program, along with anything else the compiler wrote
+//! ```
+//!
+//! Programs are compiled in a staging directory which is renamed into place once complete, so
+//! concurrent processes never see a partial entry. A staging directory replacing an entry (e.g. on
+//! a forced compilation) is swapped with it in one step on Linux and macOS, so processes running
+//! the entry's program keep finding it. Once the cache grows over its size limit, the least
+//! recently used entries are evicted.
+
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use sha2::{Digest, Sha256};
+use tempfile::Builder;
+
+use super::language_name::LanguageName;
+use super::runner_error_types::RunnerErrorType;
+use super::toolchain::LanguageToolchain;
+
+/// Name of the file marking a complete entry
+const ENTRY_MARKER: &str = ".cpast_entry";
+
+/// Prefix of the staging directories, which are not entries
+const STAGING_PREFIX: &str = ".staging_";
+
+/// Default size limit of the cache, 512 MiB
+pub const DEFAULT_MAX_CACHE_SIZE: u64 = 512 * 1024 * 1024;
+
+/// A directory of compiled programs, keyed by everything that affects compilation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileCache {
+    dir: PathBuf,
+    max_size: u64,
+}
+
+impl CompileCache {
+    /// Cache in the given directory, created on first use
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        CompileCache {
+            dir: dir.into(),
+            max_size: DEFAULT_MAX_CACHE_SIZE,
+        }
+    }
+
+    /// Cache in the default directory, see `default_dir`
+    pub fn in_default_dir() -> Self {
+        Self::new(Self::default_dir())
+    }
+
+    /// Set the size limit in bytes, over which the least recently used entries are evicted
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// The directory holding the entries
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Directory shared by every user of the cache on this machine, in order:
+    ///
+    /// - `$CPAST_CACHE_DIR`
+    /// - `$XDG_CACHE_HOME/cpast/compile`
+    /// - `$HOME/.cache/cpast/compile` (`%LOCALAPPDATA%\cpast\compile` on Windows)
+    /// - `cpast/compile` in the temporary directory
+    pub fn default_dir() -> PathBuf {
+        let env_dir = |name: &str| {
+            std::env::var_os(name)
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+        };
+
+        if let Some(dir) = env_dir("CPAST_CACHE_DIR") {
+            return dir;
+        }
+        env_dir("XDG_CACHE_HOME")
+            .or_else(|| env_dir("HOME").map(|home| home.join(".cache")))
+            .or_else(|| env_dir("LOCALAPPDATA"))
+            .unwrap_or_else(std::env::temp_dir)
+            .join("cpast")
+            .join("compile")
+    }
+
+    /// Key of a program, changing whenever anything affecting its compilation does
+    pub(crate) fn key(
+        source: &[u8],
+        language: &LanguageName,
+        name: &str,
+        toolchain: &LanguageToolchain,
+    ) -> String {
+        let mut hasher = Sha256::new();
+        // Fields are length prefixed, so that they can't run into each other
+        let mut update = |field: &[u8]| {
+            hasher.update((field.len() as u64).to_le_bytes());
+            hasher.update(field);
+        };
+        update(language.to_string().as_bytes());
+        update(name.as_bytes());
+        update(
+            toolchain
+                .output_extension
+                .as_deref()
+                .unwrap_or_default()
+                .as_bytes(),
+        );
+        for template in toolchain.compile.iter().chain(&toolchain.project_compile) {
+            update(template.program.as_bytes());
+            update(compiler_identity(&template.program).as_bytes());
+            for arg in &template.args {
+                update(arg.as_bytes());
+            }
+        }
+        update(source);
+        hex::encode(hasher.finalize())
+    }
+
+    /// Directory of the entry, which may not exist
+    pub(crate) fn entry_dir(&self, key: &str) -> PathBuf {
+        self.dir.join(key)
+    }
+
+    /// Returns true if the entry is complete, marking it as recently used
+    pub(crate) fn lookup(&self, key: &str) -> bool {
+        let marker = self.entry_dir(key).join(ENTRY_MARKER);
+        match File::options().write(true).open(marker) {
+            Ok(marker) => {
+                let _ = marker.set_modified(SystemTime::now());
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Creates an empty directory to compile in, to be published with `insert`
+    pub(crate) fn staging_dir(&self) -> Result<PathBuf, Box<RunnerErrorType>> {
+        fs::create_dir_all(&self.dir)
+            .and_then(|_| Builder::new().prefix(STAGING_PREFIX).tempdir_in(&self.dir))
+            .map(|dir| dir.keep())
+            .map_err(|err| Box::new(RunnerErrorType::FileCreationError(Box::new(err))))
+    }
+
+    /// Publishes a staging directory as the entry, replacing any previous one, then evicts old
+    /// entries if the cache is over its size limit
+    pub(crate) fn insert(&self, key: &str, staging_dir: &Path) -> Result<(), Box<RunnerErrorType>> {
+        let entry_dir = self.entry_dir(key);
+        let publish = || -> io::Result<()> {
+            File::create(staging_dir.join(ENTRY_MARKER))?;
+            match fs::rename(staging_dir, &entry_dir) {
+                Ok(()) => Ok(()),
+                // The previous entry, which another process may be running, ends up in the
+                // staging directory
+                Err(_) if entry_dir.exists() => {
+                    exchange_dirs(staging_dir, &entry_dir)?;
+                    fs::remove_dir_all(staging_dir)
+                }
+                Err(err) => Err(err),
+            }
+        };
+
+        if let Err(err) = publish() {
+            let _ = fs::remove_dir_all(staging_dir);
+            // Another process published the same entry in the meantime
+            if !self.lookup(key) {
+                return Err(Box::new(RunnerErrorType::FileCreationError(Box::new(err))));
+            }
+        }
+
+        self.evict(key);
+        Ok(())
+    }
+
+    /// Removes the least recently used entries until the cache fits its size limit, always keeping
+    /// the given entry. Entries which can't be read are left alone.
+    fn evict(&self, keep: &str) {
+        let Ok(dir_entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+
+        let mut entries: Vec<(SystemTime, u64, PathBuf)> = dir_entries
+            .flatten()
+            .filter(|entry| entry.file_name() != keep)
+            .filter_map(|entry| {
+                let path = entry.path();
+                let last_used = path.join(ENTRY_MARKER).metadata().ok()?.modified().ok()?;
+                Some((last_used, dir_size(&path), path))
+            })
+            .collect();
+
+        let mut total_size =
+            dir_size(&self.entry_dir(keep)) + entries.iter().map(|(_, size, _)| size).sum::<u64>();
+        if total_size <= self.max_size {
+            return;
+        }
+
+        entries.sort_by_key(|(last_used, _, _)| *last_used);
+        for (_, size, path) in entries {
+            if total_size <= self.max_size {
+                break;
+            }
+            if fs::remove_dir_all(&path).is_ok() {
+                total_size = total_size.saturating_sub(size);
+            }
+        }
+    }
+}
+
+/// Identity of the installed compiler: the path it resolves to, along with its size and
+/// modification time, which change when it is upgraded. Empty if it can't be found.
+fn compiler_identity(program: &str) -> String {
+    let Some(path) = which::which(program)
+        .ok()
+        .and_then(|path| path.canonicalize().ok())
+    else {
+        return String::new();
+    };
+    let Ok(metadata) = path.metadata() else {
+        return String::new();
+    };
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .unwrap_or_default();
+
+    format!(
+        "{} {} {}",
+        path.display(),
+        metadata.len(),
+        modified.as_nanos()
+    )
+}
+
+/// Swaps two directories in one step
+#[cfg(target_os = "linux")]
+fn exchange_dirs(a: &Path, b: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let a = CString::new(a.as_os_str().as_bytes())?;
+    let b = CString::new(b.as_os_str().as_bytes())?;
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_renameat2,
+            libc::AT_FDCWD,
+            a.as_ptr(),
+            libc::AT_FDCWD,
+            b.as_ptr(),
+            libc::RENAME_EXCHANGE,
+        )
+    };
+    if ret == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Swaps two directories in one step
+#[cfg(target_os = "macos")]
+fn exchange_dirs(a: &Path, b: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let a = CString::new(a.as_os_str().as_bytes())?;
+    let b = CString::new(b.as_os_str().as_bytes())?;
+    if unsafe { libc::renamex_np(a.as_ptr(), b.as_ptr(), libc::RENAME_SWAP) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Swaps two directories through a third one, so `b` is briefly missing
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn exchange_dirs(a: &Path, b: &Path) -> io::Result<()> {
+    let mut aside = a.as_os_str().to_owned();
+    aside.push("_previous");
+
+    fs::rename(b, &aside)?;
+    fs::rename(a, b)?;
+    fs::rename(&aside, a)
+}
+
+/// Total size of the files in the directory, recursively
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => dir_size(&entry.path()),
+            _ => entry.metadata().map(|metadata| metadata.len()).unwrap_or(0),
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang_runner::toolchain::ToolchainConfig;
+
+    fn cpp_toolchain() -> LanguageToolchain {
+        ToolchainConfig::builtin()
+            .get(&LanguageName::Cpp)
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn key_depends_on_everything_affecting_compilation() {
+        let toolchain = cpp_toolchain();
+        let key = CompileCache::key(b"int main() {}", &LanguageName::Cpp, "main", &toolchain);
+
+        assert_eq!(
+            key,
+            CompileCache::key(b"int main() {}", &LanguageName::Cpp, "main", &toolchain)
+        );
+        assert_ne!(
+            key,
+            CompileCache::key(b"int main() { }", &LanguageName::Cpp, "main", &toolchain)
+        );
+        assert_ne!(
+            key,
+            CompileCache::key(b"int main() {}", &LanguageName::C, "main", &toolchain)
+        );
+        assert_ne!(
+            key,
+            CompileCache::key(b"int main() {}", &LanguageName::Cpp, "other", &toolchain)
+        );
+
+        let mut flagged = toolchain.clone();
+        flagged.compile[0].args.push(String::from("-DONLINE_JUDGE"));
+        assert_ne!(
+            key,
+            CompileCache::key(b"int main() {}", &LanguageName::Cpp, "main", &flagged)
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn key_depends_on_the_installed_compiler() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let compiler = dir.path().join("compiler");
+        fs::write(&compiler, "v1").unwrap();
+        fs::set_permissions(&compiler, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut toolchain = cpp_toolchain();
+        toolchain.compile[0].program = compiler.display().to_string();
+        let key = CompileCache::key(b"int main() {}", &LanguageName::Cpp, "main", &toolchain);
+
+        fs::write(&compiler, "v1.1").unwrap();
+        assert_ne!(
+            key,
+            CompileCache::key(b"int main() {}", &LanguageName::Cpp, "main", &toolchain)
+        );
+    }
+
+    #[test]
+    fn insert_replaces_entry_without_removing_it_first() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = CompileCache::new(dir.path());
+
+        let staging_dir = cache.staging_dir().unwrap();
+        fs::write(staging_dir.join("program"), "old").unwrap();
+        cache.insert("key", &staging_dir).unwrap();
+        let program = cache.entry_dir("key").join("program");
+        let mut running = File::open(&program).unwrap();
+
+        // Another process running the program all along
+        let replaced = AtomicBool::new(false);
+        std::thread::scope(|scope| {
+            let reader = scope.spawn(|| {
+                while !replaced.load(Ordering::Relaxed) {
+                    assert!(program.exists());
+                }
+            });
+
+            for _ in 0..100 {
+                let staging_dir = cache.staging_dir().unwrap();
+                fs::write(staging_dir.join("program"), "new").unwrap();
+                cache.insert("key", &staging_dir).unwrap();
+                assert!(!staging_dir.exists());
+            }
+            replaced.store(true, Ordering::Relaxed);
+            reader.join().unwrap();
+        });
+
+        assert!(cache.lookup("key"));
+        assert_eq!(fs::read_to_string(&program).unwrap(), "new");
+        let mut old = String::new();
+        io::Read::read_to_string(&mut running, &mut old).unwrap();
+        assert_eq!(old, "old");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn insert_publishes_entry_and_evicts_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = CompileCache::new(dir.path()).with_max_size(150);
+
+        for key in ["old", "recent", "new"] {
+            let staging_dir = cache.staging_dir().unwrap();
+            fs::write(staging_dir.join("program"), [0; 60]).unwrap();
+            cache.insert(key, &staging_dir).unwrap();
+            assert!(!staging_dir.exists());
+            if key == "old" {
+                // Make sure the entries are ordered, whatever the timestamp precision
+                let marker = File::options()
+                    .write(true)
+                    .open(cache.entry_dir(key).join(ENTRY_MARKER))
+                    .unwrap();
+                marker.set_modified(SystemTime::UNIX_EPOCH).unwrap();
+            }
+        }
+
+        assert!(!cache.lookup("old"));
+        assert!(cache.lookup("recent"));
+        assert!(cache.lookup("new"));
+        assert_eq!(
+            fs::read(cache.entry_dir("new").join("program")).unwrap(),
+            [0; 60]
+        );
+    }
+}
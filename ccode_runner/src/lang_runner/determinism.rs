@@ -0,0 +1,127 @@
+//! Runs a program several times on the same input, to tell whether its output can be trusted.
+//!
+//! Reading uninitialized memory, iterating over unordered maps, or seeding a random generator with
+//! the time all make a program's output change between runs on the same input. A mismatch against
+//! such a program is a ghost, so [`check_determinism`] reruns it and reports every distinct outcome.
+//!
+//! Runs always get a fresh process, never a warm worker, as some sources of non-determinism (e.g.
+//! Python's hash seed) are only drawn once per process.
+
+use futures::future::join_all;
+
+use super::checker::{Checker, check_output};
+use super::program_store::program_output;
+use super::runner::Language;
+use super::runner_error_types::RunnerErrorType;
+
+/// An outcome shared by some of the runs
+#[derive(Debug)]
+pub struct DistinctOutcome {
+    /// Output of the first of these runs, or how it failed
+    pub outcome: Result<String, Box<RunnerErrorType>>,
+    /// Number of runs with this outcome
+    pub runs: usize,
+}
+
+/// Every distinct outcome of a program run several times on the same input
+#[derive(Debug)]
+pub struct DeterminismReport {
+    /// Outcomes in the order they first appeared
+    pub outcomes: Vec<DistinctOutcome>,
+}
+
+impl DeterminismReport {
+    /// Returns true if every run had the same outcome
+    pub fn is_deterministic(&self) -> bool {
+        self.outcomes.len() <= 1
+    }
+
+    /// Number of runs
+    pub fn runs(&self) -> usize {
+        self.outcomes.iter().map(|outcome| outcome.runs).sum()
+    }
+}
+
+/// Runs the program `runs` times on the input, one after the other, and groups the runs by outcome.
+///
+/// Outputs are the same if the checker accepts one against the other, and failures are the same if
+/// they are the same kind of error (e.g. both `RuntimeError`, whatever the exit code).
+///
+/// # Returns
+///
+/// * `Ok(DeterminismReport)` - The distinct outcomes of the runs
+/// * `Err(Box<RunnerErrorType>)` - If the program couldn't be launched, or the checker itself failed
+pub fn check_determinism(
+    program: &Language,
+    stdin_content: &str,
+    runs: usize,
+    checker: &dyn Checker,
+) -> Result<DeterminismReport, Box<RunnerErrorType>> {
+    let outcomes = (0..runs)
+        .map(|_| {
+            program
+                .run_in_new_process(stdin_content, &[])
+                .map(|result| program_output(program, result))
+        })
+        .collect::<Result<_, _>>()?;
+
+    // The checker is blocking here, so grouping never waits on anything
+    futures::executor::block_on(group_outcomes(stdin_content, outcomes, checker, true))
+}
+
+/// Same as `check_determinism`, without blocking the async runtime. The runs happen at once.
+pub async fn check_determinism_async(
+    program: &Language,
+    stdin_content: &str,
+    runs: usize,
+    checker: &dyn Checker,
+) -> Result<DeterminismReport, Box<RunnerErrorType>> {
+    let outcomes = join_all((0..runs).map(|_| async {
+        program
+            .run_in_new_process_async(stdin_content, &[])
+            .await
+            .map(|result| program_output(program, result))
+    }))
+    .await
+    .into_iter()
+    .collect::<Result<_, _>>()?;
+
+    group_outcomes(stdin_content, outcomes, checker, false).await
+}
+
+/// Groups the outcomes, checking outputs with `Checker::check` when `blocking`
+async fn group_outcomes(
+    stdin_content: &str,
+    outcomes: Vec<Result<String, Box<RunnerErrorType>>>,
+    checker: &dyn Checker,
+    blocking: bool,
+) -> Result<DeterminismReport, Box<RunnerErrorType>> {
+    let mut distinct: Vec<DistinctOutcome> = Vec::new();
+    for outcome in outcomes {
+        let mut same = None;
+        for (index, seen) in distinct.iter().enumerate() {
+            let is_same = match (&seen.outcome, &outcome) {
+                (Ok(seen), Ok(output)) => {
+                    check_output(checker, blocking, stdin_content, seen, output)
+                        .await?
+                        .is_accepted()
+                }
+                (Err(seen), Err(err)) => {
+                    std::mem::discriminant(&**seen) == std::mem::discriminant(&**err)
+                }
+                _ => false,
+            };
+            if is_same {
+                same = Some(index);
+                break;
+            }
+        }
+
+        match same {
+            Some(index) => distinct[index].runs += 1,
+            None => distinct.push(DistinctOutcome { outcome, runs: 1 }),
+        }
+    }
+
+    Ok(DeterminismReport { outcomes: distinct })
+}
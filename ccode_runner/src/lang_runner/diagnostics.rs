@@ -0,0 +1,341 @@
+//! Captures why a program failed to compile, parsing the compiler output into diagnostics.
+//!
+//! Diagnostics are recognized in the formats of:
+//!
+//! - gcc and clang: `main.cpp:3:5: error: expected ';'`
+//! - rustc: `error[E0425]: cannot find value`, followed by `--> main.rs:3:5`
+//! - javac: `Main.java:3: error: ';' expected`, with the column taken from the caret below the
+//!   quoted source line
+//!
+//! The output of other compilers is still kept as is, in [`CompilerAttempt::stderr`].
+
+use std::fmt;
+use std::io;
+use std::path::{MAIN_SEPARATOR, Path};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Serialize;
+
+#[cfg(feature = "api")]
+use utoipa::ToSchema;
+
+use crate::utils::program_utils::ExecutionResult;
+
+/// `file:line[:column]: severity: message`, as printed by gcc, clang and javac
+static LOCATED_DIAGNOSTIC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?P<file>[^:\s][^:]*):(?P<line>\d+):(?:(?P<column>\d+):)?\s*(?P<severity>fatal error|error|warning|note):\s*(?P<message>.*)$",
+    )
+    .unwrap()
+});
+
+/// `severity[code]: message`, as printed by rustc before the location
+static RUSTC_HEADER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<severity>error|warning|note)(?:\[\w+\])?:\s*(?P<message>.*)$").unwrap()
+});
+
+/// `--> file:line:column`, the location of a rustc diagnostic
+static RUSTC_LOCATION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*--> (?P<file>.+):(?P<line>\d+):(?P<column>\d+)$").unwrap());
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "api", derive(ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl fmt::Display for DiagnosticSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticSeverity::Error => write!(f, "error"),
+            DiagnosticSeverity::Warning => write!(f, "warning"),
+            DiagnosticSeverity::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// A message of the compiler about a location in the source code
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "api", derive(ToSchema))]
+pub struct CompileDiagnostic {
+    /// File as named by the compiler
+    pub file: String,
+    /// Line, starting at 1
+    pub line: u32,
+    /// Column, starting at 1, if the compiler reported it
+    pub column: Option<u32>,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+}
+
+impl fmt::Display for CompileDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ":{column}")?;
+        }
+        write!(f, ": {}: {}", self.severity, self.message)
+    }
+}
+
+/// A compiler which failed to compile the program
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "api", derive(ToSchema))]
+pub struct CompilerAttempt {
+    /// The compiler program, e.g. `g++`
+    pub compiler: String,
+    /// False if the compiler could not be started at all, e.g. as it isn't installed
+    pub launched: bool,
+    /// What the compiler printed (its stdout if its stderr is empty, as some compilers report
+    /// errors there), or why it could not be started
+    pub stderr: String,
+    /// Diagnostics parsed from the output, empty if its format isn't recognized
+    pub diagnostics: Vec<CompileDiagnostic>,
+}
+
+impl CompilerAttempt {
+    pub(crate) fn failed(compiler: &str, result: &ExecutionResult) -> Self {
+        let stderr = match result.stderr_lossy().trim() {
+            "" => match result.stdout_lossy().trim() {
+                "" => format!("Compiler failed with {:?}", result.status),
+                stdout => stdout.to_string(),
+            },
+            stderr => stderr.to_string(),
+        };
+
+        CompilerAttempt {
+            compiler: compiler.to_string(),
+            launched: true,
+            diagnostics: parse_diagnostics(&stderr),
+            stderr,
+        }
+    }
+
+    pub(crate) fn not_launched(compiler: &str, err: &io::Error) -> Self {
+        CompilerAttempt {
+            compiler: compiler.to_string(),
+            launched: false,
+            stderr: err.to_string(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// The attempt with `dir` removed from the paths in the compiler output, e.g. to hide the
+    /// temporary directory a submitted program was compiled in
+    pub fn relative_to(&self, dir: &Path) -> Self {
+        let dir_prefix = format!("{}{MAIN_SEPARATOR}", dir.display());
+        CompilerAttempt {
+            stderr: self.stderr.replace(&dir_prefix, ""),
+            diagnostics: self
+                .diagnostics
+                .iter()
+                .map(|diagnostic| CompileDiagnostic {
+                    file: diagnostic.file.replace(&dir_prefix, ""),
+                    ..diagnostic.clone()
+                })
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Diagnostics which are errors
+    pub fn errors(&self) -> impl Iterator<Item = &CompileDiagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+    }
+}
+
+impl fmt::Display for CompilerAttempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.launched {
+            write!(f, "{}:\n{}", self.compiler, self.stderr)
+        } else {
+            write!(
+                f,
+                "{}: could not be started: {}",
+                self.compiler, self.stderr
+            )
+        }
+    }
+}
+
+/// Parses the diagnostics of gcc, clang, rustc and javac out of their output, skipping anything
+/// else
+pub fn parse_diagnostics(output: &str) -> Vec<CompileDiagnostic> {
+    let lines: Vec<&str> = output.lines().collect();
+    let mut diagnostics = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        if let Some(captures) = LOCATED_DIAGNOSTIC.captures(line) {
+            let column = captures
+                .name("column")
+                .and_then(|column| column.as_str().parse().ok())
+                .or_else(|| caret_column(&lines[index + 1..]));
+            diagnostics.push(CompileDiagnostic {
+                file: captures["file"].to_string(),
+                line: captures["line"].parse().unwrap_or_default(),
+                column,
+                severity: parse_severity(&captures["severity"]),
+                message: captures["message"].trim().to_string(),
+            });
+        } else if let Some(header) = RUSTC_HEADER.captures(line) {
+            // Diagnostics without a location, e.g. `error: aborting due to 1 previous error`, are
+            // summaries rather than diagnostics
+            let location = lines[index + 1..]
+                .iter()
+                .take_while(|line| !RUSTC_HEADER.is_match(line))
+                .find_map(|line| RUSTC_LOCATION.captures(line));
+            if let Some(location) = location {
+                diagnostics.push(CompileDiagnostic {
+                    file: location["file"].to_string(),
+                    line: location["line"].parse().unwrap_or_default(),
+                    column: location["column"].parse().ok(),
+                    severity: parse_severity(&header["severity"]),
+                    message: header["message"].trim().to_string(),
+                });
+            }
+        }
+    }
+
+    diagnostics
+}
+
+fn parse_severity(severity: &str) -> DiagnosticSeverity {
+    match severity {
+        "warning" => DiagnosticSeverity::Warning,
+        "note" => DiagnosticSeverity::Note,
+        _ => DiagnosticSeverity::Error,
+    }
+}
+
+/// Column of the caret javac prints below the quoted source line
+fn caret_column(following_lines: &[&str]) -> Option<u32> {
+    let caret_line = following_lines.get(1)?;
+    let column = caret_line.find('^')?;
+    caret_line[..column]
+        .chars()
+        .all(char::is_whitespace)
+        .then(|| column as u32 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(
+        file: &str,
+        line: u32,
+        column: Option<u32>,
+        severity: DiagnosticSeverity,
+        message: &str,
+    ) -> CompileDiagnostic {
+        CompileDiagnostic {
+            file: file.to_string(),
+            line,
+            column,
+            severity,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_gcc_and_clang_diagnostics() {
+        let output = "\
+main.cpp: In function 'int main()':
+main.cpp:4:17: error: expected ';' before '}' token
+    4 |     return 0
+      |             ^
+main.cpp:2:5: warning: unused variable 'x' [-Wunused-variable]
+";
+        assert_eq!(
+            parse_diagnostics(output),
+            vec![
+                diagnostic(
+                    "main.cpp",
+                    4,
+                    Some(17),
+                    DiagnosticSeverity::Error,
+                    "expected ';' before '}' token"
+                ),
+                diagnostic(
+                    "main.cpp",
+                    2,
+                    Some(5),
+                    DiagnosticSeverity::Warning,
+                    "unused variable 'x' [-Wunused-variable]"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_rustc_diagnostics() {
+        let output = "\
+error[E0425]: cannot find value `y` in this scope
+ --> main.rs:3:20
+  |
+3 |     println!(\"{}\", y);
+  |                    ^ not found in this scope
+
+error: aborting due to 1 previous error
+";
+        assert_eq!(
+            parse_diagnostics(output),
+            vec![diagnostic(
+                "main.rs",
+                3,
+                Some(20),
+                DiagnosticSeverity::Error,
+                "cannot find value `y` in this scope"
+            )]
+        );
+    }
+
+    #[test]
+    fn parses_javac_diagnostics_with_caret_column() {
+        let output = "\
+Main.java:3: error: ';' expected
+        int x = 1
+                 ^
+1 error
+";
+        assert_eq!(
+            parse_diagnostics(output),
+            vec![diagnostic(
+                "Main.java",
+                3,
+                Some(18),
+                DiagnosticSeverity::Error,
+                "';' expected"
+            )]
+        );
+    }
+
+    #[test]
+    fn relative_to_removes_the_directory() {
+        let dir = Path::new("/tmp/cpast_runner_abc");
+        let stderr = format!(
+            "{0}/source.cpp: In function 'int main()':\n{0}/source.cpp:2:12: error: 'x' was not declared in this scope",
+            dir.display()
+        );
+        let attempt = CompilerAttempt {
+            compiler: String::from("g++"),
+            launched: true,
+            diagnostics: parse_diagnostics(&stderr),
+            stderr,
+        };
+
+        let attempt = attempt.relative_to(dir);
+        assert_eq!(
+            attempt.stderr,
+            "source.cpp: In function 'int main()':\nsource.cpp:2:12: error: 'x' was not declared in this scope"
+        );
+        assert_eq!(attempt.diagnostics[0].file, "source.cpp");
+    }
+}
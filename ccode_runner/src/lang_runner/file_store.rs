@@ -5,16 +5,18 @@ use tempfile::Builder;
 
 use crate::utils::java_classname::get_java_public_classname_from_text;
 
-use super::language_name::get_file_extension;
 use super::{
-    language_name::{
-        CompilationType, LanguageName, get_language_compilation_type, get_programming_language_name,
-    },
+    language_name::{CompilationType, LanguageName, get_language_compilation_type},
+    project::Project,
     runner_error_types::RunnerErrorType,
+    toolchain::{LanguageToolchain, ToolchainConfig},
 };
 
 const DEFAULT_PROGRAM_NAME: &str = "program";
 
+/// Name of source files created from text, fixed so that compiled programs can be cached
+const DEFAULT_SOURCE_NAME: &str = "source";
+
 #[derive(Debug)]
 pub(crate) struct SourceCodeInfo {
     pub(crate) source_path: PathBuf,
@@ -23,24 +25,25 @@ pub(crate) struct SourceCodeInfo {
     pub(crate) language: LanguageName,
     pub(crate) compilation_type: CompilationType,
     pub(crate) temp_dir: Option<PathBuf>,
+    /// Set if the source path is a directory holding a project
+    pub(crate) project: Option<Project>,
 }
 
 impl SourceCodeInfo {
-    pub(crate) fn new(source_file: &Path) -> Result<Self, Box<RunnerErrorType>> {
+    pub(crate) fn new(
+        source_file: &Path,
+        toolchains: &ToolchainConfig,
+    ) -> Result<Self, Box<RunnerErrorType>> {
         if !Self::exists(source_file) {
             return Err(Box::new(RunnerErrorType::FileNotFound(
                 source_file.to_path_buf(),
             )));
         }
+        if source_file.is_dir() {
+            return Self::new_project(source_file, None, toolchains);
+        }
 
-        let lang = match get_programming_language_name(source_file) {
-            Some(lang) => lang,
-            None => {
-                return Err(Box::new(RunnerErrorType::UnsupportedLanguage(
-                    source_file.to_path_buf(),
-                )));
-            }
-        };
+        let lang = toolchains.language_of(source_file)?;
 
         let compilation_type = get_language_compilation_type(&lang);
         if compilation_type != CompilationType::Interpreted {
@@ -54,7 +57,8 @@ impl SourceCodeInfo {
                 .tempdir()
                 .map_err(|e| Box::new(RunnerErrorType::FileCreationError(Box::new(e))))?;
 
-            let dest_path = temp_dir.path().join(program_name_stem);
+            let dest_path =
+                compiled_file_path(temp_dir.path(), program_name_stem, toolchains.get(&lang)?);
 
             return Ok(SourceCodeInfo {
                 source_path: source_file.to_path_buf(),
@@ -62,6 +66,7 @@ impl SourceCodeInfo {
                 compilation_type,
                 language: lang,
                 temp_dir: Some(temp_dir.keep()),
+                project: None,
             });
         }
 
@@ -71,13 +76,10 @@ impl SourceCodeInfo {
             compilation_type,
             language: lang,
             temp_dir: None,
+            project: None,
         })
     }
 
-    pub(crate) fn get_dest_file_str(&self) -> Option<&str> {
-        self.dest_path.as_ref().and_then(|dest| dest.to_str())
-    }
-
     /// Creates a new `SourceCodeInfo` instance with an optional custom destination path.
     ///
     /// This method initializes source code information for both interpreted and compiled languages,
@@ -87,6 +89,7 @@ impl SourceCodeInfo {
     ///
     /// * `source_file` - Path to the source code file
     /// * `dest_file` - Optional custom destination path for the compiled output
+    /// * `toolchains` - Toolchains deciding the language from the file extension
     ///
     /// # Returns
     ///
@@ -105,21 +108,18 @@ impl SourceCodeInfo {
     pub(crate) fn new_from_custom_dest(
         source_file: &Path,
         dest_file: Option<&Path>,
+        toolchains: &ToolchainConfig,
     ) -> Result<Self, Box<RunnerErrorType>> {
         if !Self::exists(source_file) {
             return Err(Box::new(RunnerErrorType::FileNotFound(
                 source_file.to_path_buf(),
             )));
         }
+        if source_file.is_dir() {
+            return Self::new_project(source_file, dest_file, toolchains);
+        }
 
-        let lang = match get_programming_language_name(source_file) {
-            Some(lang) => lang,
-            None => {
-                return Err(Box::new(RunnerErrorType::UnsupportedLanguage(
-                    source_file.to_path_buf(),
-                )));
-            }
-        };
+        let lang = toolchains.language_of(source_file)?;
 
         let compilation_type = get_language_compilation_type(&lang);
 
@@ -130,6 +130,7 @@ impl SourceCodeInfo {
                 compilation_type,
                 language: lang,
                 temp_dir: None,
+                project: None,
             });
         }
 
@@ -167,7 +168,7 @@ impl SourceCodeInfo {
                     .tempdir()
                     .map_err(|e| Box::new(RunnerErrorType::FileCreationError(Box::new(e))))?;
 
-                temp_dir.path().join(program_name_stem)
+                compiled_file_path(temp_dir.path(), program_name_stem, toolchains.get(&lang)?)
             }
         };
 
@@ -177,28 +178,82 @@ impl SourceCodeInfo {
             compilation_type,
             language: lang,
             temp_dir: None,
+            project: None,
+        })
+    }
+
+    /// Source code information of the project in the directory, compiled to `dest_file`, or to a
+    /// temporary directory if None
+    fn new_project(
+        project_dir: &Path,
+        dest_file: Option<&Path>,
+        toolchains: &ToolchainConfig,
+    ) -> Result<Self, Box<RunnerErrorType>> {
+        let project = Project::detect(project_dir, toolchains)?;
+        let lang = project.language.clone();
+        let compilation_type = get_language_compilation_type(&lang);
+        let toolchain = toolchains.get(&lang)?;
+
+        let unsupported = |commands: &str| {
+            Box::new(RunnerErrorType::InvalidProject(
+                project_dir.to_path_buf(),
+                format!("The {lang} toolchain has no {commands} commands"),
+            ))
+        };
+        let (dest_path, temp_dir) = if compilation_type == CompilationType::Interpreted {
+            if toolchain.project_run.is_empty() {
+                return Err(unsupported("project_run"));
+            }
+            (None, None)
+        } else {
+            if toolchain.project_compile.is_empty() {
+                return Err(unsupported("project_compile"));
+            }
+            match dest_file {
+                Some(dest) => (Some(dest.to_path_buf()), None),
+                None => {
+                    let temp_dir = Builder::new()
+                        .prefix("cpast_runner_")
+                        .tempdir()
+                        .map_err(|e| Box::new(RunnerErrorType::FileCreationError(Box::new(e))))?;
+                    let dest_path = compiled_file_path(temp_dir.path(), &project.entry, toolchain);
+                    (Some(dest_path), Some(temp_dir.keep()))
+                }
+            }
+        };
+
+        Ok(SourceCodeInfo {
+            source_path: project_dir.to_path_buf(),
+            dest_path,
+            compilation_type,
+            language: lang,
+            temp_dir,
+            project: Some(project),
         })
     }
 
     pub(crate) fn new_from_text(
         source_text: &str,
         lang: LanguageName,
+        toolchains: &ToolchainConfig,
     ) -> Result<Self, Box<RunnerErrorType>> {
         let compilation_type = get_language_compilation_type(&lang);
+        let toolchain = toolchains.get(&lang)?;
 
         let temp_dir = Builder::new()
             .prefix("cpast_runner_")
             .tempdir()
             .map_err(|e| Box::new(RunnerErrorType::FileCreationError(Box::new(e))))?;
 
-        let file_extension = get_file_extension(&lang);
+        // The toolchain is validated to have at least one extension
+        let file_extension = &toolchain.extensions[0];
         let source_file_stem = if lang == LanguageName::Java {
             // Java has some strict requirements around filenaming
             get_java_public_classname_from_text(source_text).ok_or(Box::new(
                 RunnerErrorType::JavaNoPublicClassFound(source_text.to_owned()),
             ))?
         } else {
-            format!("source_{}", rand::random::<u16>())
+            DEFAULT_SOURCE_NAME.to_string()
         };
 
         let source_file_path = temp_dir
@@ -223,7 +278,7 @@ impl SourceCodeInfo {
                 .file_stem()
                 .and_then(|stem| stem.to_str())
                 .unwrap_or(DEFAULT_PROGRAM_NAME);
-            let dest_path = temp_dir.path().join(program_name_stem);
+            let dest_path = compiled_file_path(temp_dir.path(), program_name_stem, toolchain);
             Some(dest_path)
         } else {
             None
@@ -235,14 +290,36 @@ impl SourceCodeInfo {
             compilation_type,
             language: lang,
             temp_dir: Some(temp_dir.keep()),
+            project: None,
         })
     }
 
+    /// Name of the program: the entry point of a project, or the source file name without its
+    /// extension
+    pub(crate) fn program_name(&self) -> Option<&str> {
+        match &self.project {
+            Some(project) => Some(&project.entry),
+            None => self.source_path.file_stem().and_then(|stem| stem.to_str()),
+        }
+    }
+
     fn exists(file: &Path) -> bool {
         file.exists()
     }
 }
 
+/// Path of the compiled program inside `dir`, with the extension its compiler requires
+pub(crate) fn compiled_file_path(
+    dir: &Path,
+    program_name_stem: &str,
+    toolchain: &LanguageToolchain,
+) -> PathBuf {
+    match &toolchain.output_extension {
+        Some(extension) => dir.join(format!("{program_name_stem}.{extension}")),
+        None => dir.join(program_name_stem),
+    }
+}
+
 impl Drop for SourceCodeInfo {
     fn drop(&mut self) {
         if let Some(temp_dir) = &self.temp_dir {
@@ -0,0 +1,336 @@
+//! Runs interactive problems, where a solution talks to an interactor program instead of reading a
+//! fixed input.
+//!
+//! The stdout of the solution is piped to the stdin of the interactor and the other way around.
+//! The interactor is run testlib-style as `interactor <input> <output> [answer]`, where `input` is
+//! the test (e.g. generated from a clex pattern), `output` is a file the interactor may write to,
+//! and `answer` is the output of an optional reference solution run on the test. The exit code of
+//! the interactor decides the verdict:
+//!
+//! - `0`: Accepted
+//! - `1`, `2` or `7`: Wrong answer, explained by the stderr (or stdout) of the interactor
+//! - Anything else: The interactor failed
+//!
+//! # Types
+//!
+//! - [`InteractiveStore`]: Holds the solution, the interactor and the optional reference solution
+//! - [`InteractionResult`]: Verdict, execution results of both programs and transcript of a run
+//! - [`InteractionTranscript`]: Everything the programs sent each other, in order
+
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::checker::write_temp_file;
+use super::language_name::LanguageName;
+use super::runner::Language;
+use super::runner_error_types::RunnerErrorType;
+use crate::utils::program_utils::{self, ExecutionLimits, ExecutionResult, ExecutionStatus};
+
+/// Outcome of an interaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InteractionVerdict {
+    /// The interactor accepted the solution
+    Accepted,
+    /// The interactor rejected the solution, with its message
+    WrongAnswer(String),
+    /// The solution crashed or exceeded a limit
+    SolutionFailed(ExecutionStatus),
+    /// The interactor crashed, exceeded a limit or exited with an unknown code
+    InteractorFailed(String),
+}
+
+impl InteractionVerdict {
+    /// Returns true if the interactor accepted the solution
+    pub fn is_accepted(&self) -> bool {
+        *self == InteractionVerdict::Accepted
+    }
+}
+
+/// Which program sent a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptDirection {
+    SolutionToInteractor,
+    InteractorToSolution,
+}
+
+/// Data sent by one of the programs, as read from its stdout, split at line ends where possible
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptEntry {
+    pub direction: TranscriptDirection,
+    /// Time since the programs were started
+    pub elapsed: Duration,
+    /// The data, with invalid UTF-8 replaced by `U+FFFD`
+    pub data: String,
+}
+
+/// Everything the solution and the interactor sent each other, in the order it was read.
+///
+/// Displayed as a log, with lines sent by the solution prefixed by `>` and lines sent by the
+/// interactor prefixed by `<`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InteractionTranscript {
+    pub entries: Vec<TranscriptEntry>,
+}
+
+impl fmt::Display for InteractionTranscript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            let prefix = match entry.direction {
+                TranscriptDirection::SolutionToInteractor => '>',
+                TranscriptDirection::InteractorToSolution => '<',
+            };
+            for line in entry.data.lines() {
+                writeln!(f, "[{:>6}ms] {prefix} {line}", entry.elapsed.as_millis())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Result of running the solution against the interactor
+#[derive(Debug, Clone)]
+pub struct InteractionResult {
+    pub verdict: InteractionVerdict,
+    pub solution: ExecutionResult,
+    pub interactor: ExecutionResult,
+    pub transcript: InteractionTranscript,
+}
+
+/// A solution to an interactive problem, along with the interactor judging it.
+///
+/// Each program runs with its own execution limits, which are set when creating its `Language`.
+#[derive(Debug)]
+pub struct InteractiveStore {
+    solution: Language,
+    interactor: Language,
+    reference: Option<Language>,
+}
+
+impl InteractiveStore {
+    pub fn new(
+        solution_file: &Path,
+        interactor_file: &Path,
+        do_force_compile: bool,
+    ) -> Result<Self, Box<RunnerErrorType>> {
+        Self::new_with_limits(
+            solution_file,
+            interactor_file,
+            do_force_compile,
+            ExecutionLimits::default(),
+            ExecutionLimits::default(),
+        )
+    }
+
+    pub fn new_with_limits(
+        solution_file: &Path,
+        interactor_file: &Path,
+        do_force_compile: bool,
+        solution_limits: ExecutionLimits,
+        interactor_limits: ExecutionLimits,
+    ) -> Result<Self, Box<RunnerErrorType>> {
+        Ok(InteractiveStore {
+            solution: Language::new_with_limits(solution_file, do_force_compile, solution_limits)?,
+            interactor: Language::new_with_limits(
+                interactor_file,
+                do_force_compile,
+                interactor_limits,
+            )?,
+            reference: None,
+        })
+    }
+
+    pub fn new_from_text(
+        solution_text: &str,
+        interactor_text: &str,
+        solution_lang: LanguageName,
+        interactor_lang: LanguageName,
+        do_force_compile: bool,
+    ) -> Result<Self, Box<RunnerErrorType>> {
+        Self::new_from_text_with_limits(
+            solution_text,
+            interactor_text,
+            solution_lang,
+            interactor_lang,
+            do_force_compile,
+            ExecutionLimits::default(),
+            ExecutionLimits::default(),
+        )
+    }
+
+    pub fn new_from_text_with_limits(
+        solution_text: &str,
+        interactor_text: &str,
+        solution_lang: LanguageName,
+        interactor_lang: LanguageName,
+        do_force_compile: bool,
+        solution_limits: ExecutionLimits,
+        interactor_limits: ExecutionLimits,
+    ) -> Result<Self, Box<RunnerErrorType>> {
+        Ok(InteractiveStore {
+            solution: Language::new_from_text_with_limits(
+                solution_text,
+                solution_lang,
+                do_force_compile,
+                solution_limits,
+            )?,
+            interactor: Language::new_from_text_with_limits(
+                interactor_text,
+                interactor_lang,
+                do_force_compile,
+                interactor_limits,
+            )?,
+            reference: None,
+        })
+    }
+
+    pub fn new_from_language(solution: Language, interactor: Language) -> Self {
+        InteractiveStore {
+            solution,
+            interactor,
+            reference: None,
+        }
+    }
+
+    /// Set a reference solution, which runs non-interactively on the input before each
+    /// interaction. Its output is passed to the interactor as the answer file.
+    pub fn with_reference(mut self, reference: Language) -> Self {
+        self.reference = Some(reference);
+        self
+    }
+
+    /// Run the solution against the interactor on the given test
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The test, passed to the interactor (and the reference solution) only
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(InteractionResult)` - The verdict, how both programs ended and the transcript
+    /// * `Err(Box<RunnerErrorType>)` - If either program could not be started, or the reference
+    ///   solution failed
+    pub fn run_interaction(&self, input: &str) -> Result<InteractionResult, Box<RunnerErrorType>> {
+        let answer = self
+            .reference
+            .as_ref()
+            .map(|reference| run_reference(reference, input))
+            .transpose()?;
+
+        let input_file = write_temp_file(input)?;
+        let output_file = write_temp_file("")?;
+        let answer_file = answer.as_deref().map(write_temp_file).transpose()?;
+
+        let (solution_program, solution_args) = self.solution.run_command()?;
+        let (interactor_program, mut interactor_args) = self.interactor.run_command()?;
+        interactor_args.extend(
+            [Some(&input_file), Some(&output_file), answer_file.as_ref()]
+                .into_iter()
+                .flatten()
+                .map(|file| file.path().to_str().unwrap_or_default().to_string()),
+        );
+        let solution_args: Vec<&str> = solution_args.iter().map(String::as_str).collect();
+        let interactor_args: Vec<&str> = interactor_args.iter().map(String::as_str).collect();
+
+        let entries = Arc::new(Mutex::new(Vec::new()));
+        let start_time = Instant::now();
+        let (solution, interactor) = program_utils::run_interactive(
+            (
+                &solution_program,
+                &solution_args,
+                &self.solution.execution_limits,
+            ),
+            (
+                &interactor_program,
+                &interactor_args,
+                &self.interactor.execution_limits,
+            ),
+            record_transcript(
+                &entries,
+                start_time,
+                TranscriptDirection::SolutionToInteractor,
+            ),
+            record_transcript(
+                &entries,
+                start_time,
+                TranscriptDirection::InteractorToSolution,
+            ),
+        )
+        .map_err(|err| Box::new(RunnerErrorType::ProgramRunError(Box::new(err))))?;
+
+        let entries = std::mem::take(&mut *entries.lock().unwrap_or_else(|err| err.into_inner()));
+
+        Ok(InteractionResult {
+            verdict: interaction_verdict(&solution, &interactor),
+            solution,
+            interactor,
+            transcript: InteractionTranscript { entries },
+        })
+    }
+}
+
+fn run_reference(reference: &Language, input: &str) -> Result<String, Box<RunnerErrorType>> {
+    let result = reference.run_program_code(input)?;
+    if result.is_success() {
+        Ok(result.stdout_lossy().into_owned())
+    } else {
+        Err(Box::new(RunnerErrorType::RuntimeError(Box::new(result))))
+    }
+}
+
+/// Data read in the middle of a line is appended to the previous entry of the same program, so
+/// that entries do not depend on how the pipe happened to be read
+fn record_transcript(
+    entries: &Arc<Mutex<Vec<TranscriptEntry>>>,
+    start_time: Instant,
+    direction: TranscriptDirection,
+) -> impl FnMut(&[u8]) + Send + 'static {
+    let entries = entries.clone();
+    move |data| {
+        let data = String::from_utf8_lossy(data);
+        let mut entries = entries.lock().unwrap_or_else(|err| err.into_inner());
+
+        match entries
+            .iter_mut()
+            .rev()
+            .find(|entry| entry.direction == direction)
+        {
+            Some(entry) if !entry.data.ends_with('\n') => entry.data.push_str(&data),
+            _ => entries.push(TranscriptEntry {
+                direction,
+                elapsed: start_time.elapsed(),
+                data: data.into_owned(),
+            }),
+        }
+    }
+}
+
+/// A solution over its limits is reported as such, since the interactor usually fails too once the
+/// solution stops talking to it. Otherwise a wrong answer takes precedence over the solution
+/// crashing, as the solution often crashes on the EOF left by an interactor which gave up on it.
+fn interaction_verdict(
+    solution: &ExecutionResult,
+    interactor: &ExecutionResult,
+) -> InteractionVerdict {
+    let message = match interactor.stderr_lossy().trim() {
+        "" => interactor.stdout_lossy().trim().to_string(),
+        stderr => stderr.to_string(),
+    };
+
+    match (solution.status, interactor.status) {
+        (
+            status @ (ExecutionStatus::TimeLimitExceeded
+            | ExecutionStatus::CpuTimeLimitExceeded
+            | ExecutionStatus::MemoryLimitExceeded
+            | ExecutionStatus::OutputLimitExceeded),
+            _,
+        ) => InteractionVerdict::SolutionFailed(status),
+        (_, ExecutionStatus::NonZeroExit(1 | 2 | 7)) => InteractionVerdict::WrongAnswer(message),
+        (ExecutionStatus::Success, ExecutionStatus::Success) => InteractionVerdict::Accepted,
+        (ExecutionStatus::Success, status) => {
+            InteractionVerdict::InteractorFailed(format!("{status:?}: {message}"))
+        }
+        (status, _) => InteractionVerdict::SolutionFailed(status),
+    }
+}
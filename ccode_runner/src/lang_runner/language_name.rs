@@ -24,6 +24,11 @@
 //! - Ruby (.rb)
 //! - JavaScript (.js)
 //! - Java (.java)
+//! - Go (.go)
+//! - Kotlin (.kt)
+//! - C# (.cs)
+//! - Haskell (.hs)
+//! - PHP (.php)
 //!
 //! This module includes utilities for:
 //! - Converting between file extensions and language names
@@ -41,7 +46,7 @@ use utoipa::ToSchema;
 use super::runner_error_types::RunnerErrorType;
 
 /// Enumeration of supported programming languages.
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "api", derive(ToSchema))]
 pub enum LanguageName {
     Python,
@@ -51,6 +56,11 @@ pub enum LanguageName {
     Ruby,
     Javascript,
     Java,
+    Go,
+    Kotlin,
+    CSharp,
+    Haskell,
+    Php,
 }
 
 impl fmt::Display for LanguageName {
@@ -63,6 +73,11 @@ impl fmt::Display for LanguageName {
             LanguageName::Ruby => write!(f, "ruby"),
             LanguageName::Javascript => write!(f, "javascript"),
             LanguageName::Java => write!(f, "java"),
+            LanguageName::Go => write!(f, "go"),
+            LanguageName::Kotlin => write!(f, "kotlin"),
+            LanguageName::CSharp => write!(f, "csharp"),
+            LanguageName::Haskell => write!(f, "haskell"),
+            LanguageName::Php => write!(f, "php"),
         }
     }
 }
@@ -79,8 +94,13 @@ impl TryFrom<String> for LanguageName {
             "ruby" => Ok(LanguageName::Ruby),
             "javascript" => Ok(LanguageName::Javascript),
             "java" => Ok(LanguageName::Java),
+            "go" => Ok(LanguageName::Go),
+            "kotlin" => Ok(LanguageName::Kotlin),
+            "csharp" => Ok(LanguageName::CSharp),
+            "haskell" => Ok(LanguageName::Haskell),
+            "php" => Ok(LanguageName::Php),
             other => Err(format!(
-                "{other} is not a supported language. Use either `python`, `cpp`, `c`, `rust`, `ruby`, `javascript` or `java`."
+                "{other} is not a supported language. Use either `python`, `cpp`, `c`, `rust`, `ruby`, `javascript`, `java`, `go`, `kotlin`, `csharp`, `haskell` or `php`."
             )),
         }
     }
@@ -93,7 +113,7 @@ impl TryFrom<String> for LanguageName {
 pub enum CompilationType {
     Compiled,         // Compiled language like C, C++, Rust, Go, etc.
     Interpreted,      // Interpreted language like Python, etc.
-    BytecodeCompiled, // Java, compiled to bytecode, executed by JVM
+    BytecodeCompiled, // Java, Kotlin, C#, compiled to bytecode, executed by a VM
 }
 
 impl TryFrom<&Path> for LanguageName {
@@ -110,6 +130,11 @@ impl TryFrom<&Path> for LanguageName {
             Some("java") => Ok(LanguageName::Java),
             Some("js") => Ok(LanguageName::Javascript),
             Some("rb") => Ok(LanguageName::Ruby),
+            Some("go") => Ok(LanguageName::Go),
+            Some("kt") => Ok(LanguageName::Kotlin),
+            Some("cs") => Ok(LanguageName::CSharp),
+            Some("hs") => Ok(LanguageName::Haskell),
+            Some("php") => Ok(LanguageName::Php),
             Some(_) => Err(RunnerErrorType::UnsupportedLanguage(
                 file_path.to_path_buf(),
             )),
@@ -120,35 +145,19 @@ impl TryFrom<&Path> for LanguageName {
     }
 }
 
-pub(super) fn get_programming_language_name(file_path: &Path) -> Option<LanguageName> {
-    LanguageName::try_from(file_path).ok()
-}
-
-impl LanguageName {
-    /// Gets the default file extension for this programming language.
-    pub(crate) fn file_extension(&self) -> &'static str {
-        match self {
-            LanguageName::Rust => "rs",
-            LanguageName::Python => "py",
-            LanguageName::C => "c",
-            LanguageName::Cpp => "cpp",
-            LanguageName::Java => "java",
-            LanguageName::Javascript => "js",
-            LanguageName::Ruby => "rb",
-        }
-    }
-}
-
-pub(super) fn get_file_extension(lang_name: &LanguageName) -> &'static str {
-    lang_name.file_extension()
-}
-
 pub(super) fn get_language_compilation_type(lang_name: &LanguageName) -> CompilationType {
     match lang_name {
-        LanguageName::Rust | LanguageName::Cpp | LanguageName::C => CompilationType::Compiled,
-        LanguageName::Python | LanguageName::Ruby | LanguageName::Javascript => {
-            CompilationType::Interpreted
+        LanguageName::Rust
+        | LanguageName::Cpp
+        | LanguageName::C
+        | LanguageName::Go
+        | LanguageName::Haskell => CompilationType::Compiled,
+        LanguageName::Python
+        | LanguageName::Ruby
+        | LanguageName::Javascript
+        | LanguageName::Php => CompilationType::Interpreted,
+        LanguageName::Java | LanguageName::Kotlin | LanguageName::CSharp => {
+            CompilationType::BytecodeCompiled
         }
-        LanguageName::Java => CompilationType::BytecodeCompiled,
     }
 }
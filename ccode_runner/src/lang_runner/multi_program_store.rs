@@ -0,0 +1,344 @@
+//! Compares any number of programs on the same input, e.g. a brute force, the intended solution and
+//! several candidate submissions.
+//!
+//! The expected output of each input comes from either:
+//!
+//! - [`ReferenceStrategy::Oracle`]: A designated program, trusted to be correct
+//! - [`ReferenceStrategy::Majority`]: The output most programs agree on, as decided by the checker
+//!
+//! Every other program is then checked against it, and [`ComparisonReport`] tells which programs
+//! disagree with the reference on the input, and how.
+
+use std::path::Path;
+
+use futures::future::join_all;
+
+use super::checker::{Checker, CheckerVerdict, TextChecker, check_output};
+use super::program_store::program_output;
+use super::runner::Language;
+use super::runner_error_types::RunnerErrorType;
+use super::toolchain::ToolchainConfig;
+use crate::utils::program_utils::ExecutionLimits;
+
+/// How the expected output of an input is decided
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceStrategy {
+    /// The output of the program at this index is the expected output
+    Oracle(usize),
+    /// The output of the largest group of programs agreeing with each other is the expected
+    /// output. Ties go to the group of the program listed first.
+    Majority,
+}
+
+/// How a program did compared to the reference output
+#[derive(Debug)]
+pub enum ProgramVerdict {
+    /// The output agrees with the reference output (or is the reference output)
+    Accepted,
+    /// The output was rejected by the checker, with a message describing why
+    WrongAnswer(String),
+    /// The program didn't produce an output, e.g. it crashed or exceeded a limit
+    Failed(Box<RunnerErrorType>),
+}
+
+impl ProgramVerdict {
+    /// Returns true if the output agrees with the reference output
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, ProgramVerdict::Accepted)
+    }
+}
+
+/// Outcome of one program on an input
+#[derive(Debug)]
+pub struct ProgramResult {
+    /// Name of the program, its path for programs read from files
+    pub name: String,
+    /// What the program printed, `None` if it failed
+    pub output: Option<String>,
+    pub verdict: ProgramVerdict,
+}
+
+/// Outcome of every program on an input, in the order the programs were given
+#[derive(Debug)]
+pub struct ComparisonReport {
+    /// Index of the program whose output is the reference, `None` if every program failed
+    pub reference: Option<usize>,
+    pub results: Vec<ProgramResult>,
+}
+
+impl ComparisonReport {
+    /// The reference output, `None` if every program failed
+    pub fn reference_output(&self) -> Option<&str> {
+        self.reference
+            .and_then(|index| self.results[index].output.as_deref())
+    }
+
+    /// Returns true if every program agrees with the reference output
+    pub fn all_agree(&self) -> bool {
+        self.results
+            .iter()
+            .all(|result| result.verdict.is_accepted())
+    }
+
+    /// Programs which failed or disagree with the reference output
+    pub fn disagreeing(&self) -> impl Iterator<Item = &ProgramResult> {
+        self.results
+            .iter()
+            .filter(|result| !result.verdict.is_accepted())
+    }
+}
+
+/// A structure that holds any number of named programs for comparison.
+///
+/// Unlike `ProgramStore`, a program which fails is reported along with the others rather than as
+/// an error, except for the oracle, whose failure leaves nothing to compare with. Outputs are
+/// compared with a `Checker`, `TextChecker` unless set with `with_checker`.
+#[derive(Debug)]
+pub struct MultiProgramStore {
+    programs: Vec<(String, Language)>,
+    reference: ReferenceStrategy,
+    checker: Box<dyn Checker>,
+}
+
+impl MultiProgramStore {
+    pub fn new(
+        files: &[&Path],
+        reference: ReferenceStrategy,
+        do_force_compile: bool,
+    ) -> Result<Self, Box<RunnerErrorType>> {
+        Self::new_with_toolchain(
+            files,
+            reference,
+            do_force_compile,
+            ExecutionLimits::default(),
+            ToolchainConfig::builtin(),
+        )
+    }
+
+    /// Same as `new`, compiling and running every file with the given limits and toolchains
+    pub fn new_with_toolchain(
+        files: &[&Path],
+        reference: ReferenceStrategy,
+        do_force_compile: bool,
+        execution_limits: ExecutionLimits,
+        toolchains: &ToolchainConfig,
+    ) -> Result<Self, Box<RunnerErrorType>> {
+        let programs = files
+            .iter()
+            .map(|file| {
+                Language::new_with_toolchain(file, do_force_compile, execution_limits, toolchains)
+                    .map(|lang| (file.display().to_string(), lang))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Self::new_from_languages(programs, reference)
+    }
+
+    /// Compares the given programs, named for the report
+    pub fn new_from_languages(
+        programs: Vec<(String, Language)>,
+        reference: ReferenceStrategy,
+    ) -> Result<Self, Box<RunnerErrorType>> {
+        if programs.len() < 2 {
+            return Err(Box::new(RunnerErrorType::InvalidProgramSet(format!(
+                "At least 2 programs are needed for a comparison, got {}",
+                programs.len()
+            ))));
+        }
+        if let ReferenceStrategy::Oracle(index) = reference
+            && index >= programs.len()
+        {
+            return Err(Box::new(RunnerErrorType::InvalidProgramSet(format!(
+                "Oracle {index} is out of range for {} programs",
+                programs.len()
+            ))));
+        }
+
+        Ok(MultiProgramStore {
+            programs,
+            reference,
+            checker: Box::new(TextChecker),
+        })
+    }
+
+    /// Set the checker comparing the outputs of the programs
+    pub fn with_checker(mut self, checker: Box<dyn Checker>) -> Self {
+        self.checker = checker;
+        self
+    }
+
+    /// Names of the programs, in the order they were given
+    pub fn program_names(&self) -> impl Iterator<Item = &str> {
+        self.programs.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// The program at the index, in the order they were given
+    pub fn program(&self, index: usize) -> Option<&Language> {
+        self.programs.get(index).map(|(_, language)| language)
+    }
+
+    /// How the expected output is decided
+    pub fn reference(&self) -> ReferenceStrategy {
+        self.reference
+    }
+
+    /// The checker comparing the outputs
+    pub fn checker(&self) -> &dyn Checker {
+        self.checker.as_ref()
+    }
+
+    /// Run every program with the given input, one after the other, and compare their outputs
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ComparisonReport)` - The output and verdict of every program
+    /// * `Err(Box<RunnerErrorType>)` - If the oracle failed, as reported by
+    ///   `ProgramStore::run_codes_and_compare_output` for the correct file, or if the checker
+    ///   itself failed (`CheckerFailed`)
+    pub fn run_codes_and_compare_outputs(
+        &self,
+        stdin_content: &str,
+    ) -> Result<ComparisonReport, Box<RunnerErrorType>> {
+        let outputs = self
+            .programs
+            .iter()
+            .map(|(_, language)| {
+                language
+                    .run_program_code(stdin_content)
+                    .and_then(|result| program_output(language, result))
+            })
+            .collect();
+
+        // The checker is blocking here, so the comparison never waits on anything
+        futures::executor::block_on(self.compare_outputs(stdin_content, outputs, true))
+    }
+
+    /// Same as `run_codes_and_compare_outputs`, without blocking the async runtime
+    ///
+    /// Every program runs at once. Dropping the returned future cancels the run, killing the
+    /// programs along with every process they started. The outputs are checked with
+    /// `Checker::check_async`, so a special judge doesn't block the runtime either.
+    pub async fn run_codes_and_compare_outputs_async(
+        &self,
+        stdin_content: &str,
+    ) -> Result<ComparisonReport, Box<RunnerErrorType>> {
+        let outputs = join_all(self.programs.iter().map(|(_, language)| async move {
+            language
+                .run_program_code_async(stdin_content)
+                .await
+                .and_then(|result| program_output(language, result))
+        }))
+        .await;
+
+        self.compare_outputs(stdin_content, outputs, false).await
+    }
+
+    /// Checks the outputs, with `Checker::check` when `blocking`
+    async fn compare_outputs(
+        &self,
+        stdin_content: &str,
+        mut outputs: Vec<Result<String, Box<RunnerErrorType>>>,
+        blocking: bool,
+    ) -> Result<ComparisonReport, Box<RunnerErrorType>> {
+        let (reference, majority) = match self.reference {
+            ReferenceStrategy::Oracle(index) => {
+                if outputs[index].is_err() {
+                    return Err(outputs.swap_remove(index).unwrap_err());
+                }
+                (Some(index), Vec::new())
+            }
+            ReferenceStrategy::Majority => {
+                let majority = self
+                    .majority_group(stdin_content, &outputs, blocking)
+                    .await?;
+                (majority.first().copied(), majority)
+            }
+        };
+        let reference_output = reference
+            .and_then(|index| outputs[index].as_ref().ok())
+            .cloned();
+
+        let mut results = Vec::with_capacity(outputs.len());
+        for (index, ((name, _), output)) in self.programs.iter().zip(outputs).enumerate() {
+            let (output, verdict) = match output {
+                Err(err) => (None, ProgramVerdict::Failed(err)),
+                Ok(output) => {
+                    let verdict = match &reference_output {
+                        Some(expected)
+                            if Some(index) != reference && !majority.contains(&index) =>
+                        {
+                            let verdict = check_output(
+                                self.checker.as_ref(),
+                                blocking,
+                                stdin_content,
+                                expected,
+                                &output,
+                            )
+                            .await?;
+                            match verdict {
+                                CheckerVerdict::Accepted => ProgramVerdict::Accepted,
+                                CheckerVerdict::WrongAnswer(message) => {
+                                    ProgramVerdict::WrongAnswer(message)
+                                }
+                            }
+                        }
+                        _ => ProgramVerdict::Accepted,
+                    };
+                    (Some(output), verdict)
+                }
+            };
+            results.push(ProgramResult {
+                name: name.clone(),
+                output,
+                verdict,
+            });
+        }
+
+        Ok(ComparisonReport { reference, results })
+    }
+
+    /// Indices of the largest group of programs whose outputs the checker accepts against the
+    /// output of the group's first program, empty if every program failed
+    async fn majority_group(
+        &self,
+        stdin_content: &str,
+        outputs: &[Result<String, Box<RunnerErrorType>>],
+        blocking: bool,
+    ) -> Result<Vec<usize>, Box<RunnerErrorType>> {
+        let mut groups: Vec<(&str, Vec<usize>)> = Vec::new();
+        for (index, output) in outputs.iter().enumerate() {
+            let Ok(output) = output else {
+                continue;
+            };
+
+            let mut group_index = None;
+            for (position, (representative, _)) in groups.iter().enumerate() {
+                if check_output(
+                    self.checker.as_ref(),
+                    blocking,
+                    stdin_content,
+                    representative,
+                    output,
+                )
+                .await?
+                .is_accepted()
+                {
+                    group_index = Some(position);
+                    break;
+                }
+            }
+            match group_index {
+                Some(position) => groups[position].1.push(index),
+                None => groups.push((output, vec![index])),
+            }
+        }
+
+        // `max_by_key` returns the last maximum, so ties go to the group of the earliest program
+        Ok(groups
+            .into_iter()
+            .rev()
+            .map(|(_, members)| members)
+            .max_by_key(|members| members.len())
+            .unwrap_or_default())
+    }
+}
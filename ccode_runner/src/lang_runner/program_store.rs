@@ -1,9 +1,15 @@
 //! This module provides functionalities for managing and interacting with stored programs.
 //! It includes utilities for running program files.
+use crate::lang_runner::checker::{Checker, CheckerVerdict, TextChecker};
 use crate::lang_runner::runner::Language;
-use crate::utils::file_utils;
-use crate::utils::program_utils::ExecutionLimits;
+use crate::lang_runner::sanitizer::parse_sanitizer_report;
+use crate::lang_runner::toolchain::ToolchainConfig;
+use crate::utils::program_utils::{ExecutionLimits, ExecutionResult, ExecutionStatus};
+use serde::Serialize;
+use std::fmt;
 use std::path::Path;
+#[cfg(feature = "api")]
+use utoipa::ToSchema;
 
 use super::language_name::LanguageName;
 use super::runner_error_types::RunnerErrorType;
@@ -14,19 +20,35 @@ use super::runner_error_types::RunnerErrorType;
 /// * A correct implementation file
 /// * A test implementation file
 ///
-/// Both files are represented using the `Language` type.
+/// Both files are represented using the `Language` type. Their outputs are compared with a
+/// `Checker`, `TextChecker` unless set with `with_checker`.
 #[derive(Debug)]
 pub struct ProgramStore {
     correct_file: Language,
     test_file: Language,
+    checker: Box<dyn Checker>,
 }
 
-#[derive(Debug)]
-enum FileType {
+/// One of the two programs of a `ProgramStore`, which its errors are tagged with (see
+/// `RunnerErrorType::ProgramFailed`)
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "api", derive(ToSchema))]
+pub enum ProgramRole {
+    /// The program giving the expected output
     Correct,
+    /// The program being tested
     Test,
 }
 
+impl fmt::Display for ProgramRole {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProgramRole::Correct => write!(f, "correct"),
+            ProgramRole::Test => write!(f, "test"),
+        }
+    }
+}
+
 impl ProgramStore {
     pub fn new(
         correct_file: &Path,
@@ -36,6 +58,7 @@ impl ProgramStore {
         Ok(ProgramStore {
             correct_file: Language::new(correct_file, do_force_compile)?,
             test_file: Language::new(test_file, do_force_compile)?,
+            checker: Box::new(TextChecker),
         })
     }
 
@@ -52,6 +75,32 @@ impl ProgramStore {
                 execution_limits,
             )?,
             test_file: Language::new_with_limits(test_file, do_force_compile, execution_limits)?,
+            checker: Box::new(TextChecker),
+        })
+    }
+
+    /// Same as `new_with_limits`, compiling and running both files with the given toolchains
+    pub fn new_with_toolchain(
+        correct_file: &Path,
+        test_file: &Path,
+        do_force_compile: bool,
+        execution_limits: ExecutionLimits,
+        toolchains: &ToolchainConfig,
+    ) -> Result<Self, Box<RunnerErrorType>> {
+        Ok(ProgramStore {
+            correct_file: Language::new_with_toolchain(
+                correct_file,
+                do_force_compile,
+                execution_limits,
+                toolchains,
+            )?,
+            test_file: Language::new_with_toolchain(
+                test_file,
+                do_force_compile,
+                execution_limits,
+                toolchains,
+            )?,
+            checker: Box::new(TextChecker),
         })
     }
 
@@ -69,6 +118,7 @@ impl ProgramStore {
                 do_force_compile,
             )?,
             test_file: Language::new_from_custom_dest(test_file, test_dest, do_force_compile)?,
+            checker: Box::new(TextChecker),
         })
     }
 
@@ -93,6 +143,7 @@ impl ProgramStore {
                 do_force_compile,
                 execution_limits,
             )?,
+            checker: Box::new(TextChecker),
         })
     }
 
@@ -106,6 +157,7 @@ impl ProgramStore {
         Ok(ProgramStore {
             correct_file: Language::new_from_text(correct_text, correct_lang, do_force_compile)?,
             test_file: Language::new_from_text(test_text, test_lang, do_force_compile)?,
+            checker: Box::new(TextChecker),
         })
     }
 
@@ -130,6 +182,37 @@ impl ProgramStore {
                 do_force_compile,
                 execution_limits,
             )?,
+            checker: Box::new(TextChecker),
+        })
+    }
+
+    /// Same as `new_from_text_with_limits`, compiling and running both programs with the given
+    /// toolchains
+    pub fn new_from_text_with_toolchain(
+        correct_text: &str,
+        test_text: &str,
+        correct_lang: LanguageName,
+        test_lang: LanguageName,
+        do_force_compile: bool,
+        execution_limits: ExecutionLimits,
+        toolchains: &ToolchainConfig,
+    ) -> Result<Self, Box<RunnerErrorType>> {
+        Ok(ProgramStore {
+            correct_file: Language::new_from_text_with_toolchain(
+                correct_text,
+                correct_lang,
+                do_force_compile,
+                execution_limits,
+                toolchains,
+            )?,
+            test_file: Language::new_from_text_with_toolchain(
+                test_text,
+                test_lang,
+                do_force_compile,
+                execution_limits,
+                toolchains,
+            )?,
+            checker: Box::new(TextChecker),
         })
     }
 
@@ -140,9 +223,31 @@ impl ProgramStore {
         Ok(ProgramStore {
             correct_file: correct_lang,
             test_file: test_lang,
+            checker: Box::new(TextChecker),
         })
     }
 
+    /// Set the checker comparing the outputs of the correct and test files
+    pub fn with_checker(mut self, checker: Box<dyn Checker>) -> Self {
+        self.checker = checker;
+        self
+    }
+
+    /// The correct program
+    pub fn correct_program(&self) -> &Language {
+        &self.correct_file
+    }
+
+    /// The program tested against the correct one
+    pub fn test_program(&self) -> &Language {
+        &self.test_file
+    }
+
+    /// The checker comparing the outputs
+    pub fn checker(&self) -> &dyn Checker {
+        self.checker.as_ref()
+    }
+
     /// Run both correct and test files with the given input and compare their outputs
     ///
     /// # Arguments
@@ -152,37 +257,144 @@ impl ProgramStore {
     /// # Returns
     ///
     /// * `Ok((bool, String, String))` - A tuple containing:
-    ///   * A boolean indicating if outputs differ (true if different)
+    ///   * A boolean indicating if the checker accepted the output of the test file
     ///   * The output string from the correct file
     ///   * The output string from the test file
-    /// * `Err(Box<RunnerErrorType>)` - If there was an error running either program
+    /// * `Err(Box<RunnerErrorType>)` - If there was an error running either program, as
+    ///   `ProgramFailed` with the role of that program. A program which fails is reported as
+    ///   `TimeLimitExceeded`, `CpuTimeLimitExceeded`, `MemoryLimitExceeded`, `OutputLimitExceeded`
+    ///   or `RuntimeError` (terminated by a signal or non-zero exit code), or `SanitizerError` if a
+    ///   sanitizer of the debug build profile stopped it
     pub fn run_codes_and_compare_output(
         &self,
         stdin_content: &str,
     ) -> Result<(bool, String, String), Box<RunnerErrorType>> {
-        let correct_output =
-            self.run_program_code_interface(&self.correct_file, stdin_content, FileType::Correct)?;
+        let (verdict, correct_output, test_output) =
+            self.run_codes_and_check_output(stdin_content)?;
+
+        Ok((verdict.is_accepted(), correct_output, test_output))
+    }
+
+    /// Run both correct and test files with the given input and check the output of the test file
+    ///
+    /// Same as `run_codes_and_compare_output`, but returns the checker's verdict, which explains
+    /// why an output was rejected. A failure of the checker itself is reported as `CheckerFailed`.
+    pub fn run_codes_and_check_output(
+        &self,
+        stdin_content: &str,
+    ) -> Result<(CheckerVerdict, String, String), Box<RunnerErrorType>> {
+        let correct_output = self.run_program_code_interface(
+            &self.correct_file,
+            stdin_content,
+            ProgramRole::Correct,
+        )?;
         let test_output =
-            self.run_program_code_interface(&self.test_file, stdin_content, FileType::Test)?;
+            self.run_program_code_interface(&self.test_file, stdin_content, ProgramRole::Test)?;
+
+        let verdict = self
+            .checker
+            .check(stdin_content, &correct_output, &test_output)?;
+
+        Ok((verdict, correct_output, test_output))
+    }
+
+    /// Same as `run_codes_and_compare_output`, without blocking the async runtime
+    ///
+    /// Both programs run at once. Dropping the returned future cancels the run, killing the
+    /// programs along with every process they started. The output is checked with
+    /// `Checker::check_async`, so a special judge doesn't block the runtime either.
+    pub async fn run_codes_and_compare_output_async(
+        &self,
+        stdin_content: &str,
+    ) -> Result<(bool, String, String), Box<RunnerErrorType>> {
+        let (verdict, correct_output, test_output) =
+            self.run_codes_and_check_output_async(stdin_content).await?;
+
+        Ok((verdict.is_accepted(), correct_output, test_output))
+    }
+
+    /// Same as `run_codes_and_check_output`, without blocking the async runtime, see
+    /// `run_codes_and_compare_output_async`
+    pub async fn run_codes_and_check_output_async(
+        &self,
+        stdin_content: &str,
+    ) -> Result<(CheckerVerdict, String, String), Box<RunnerErrorType>> {
+        // Once either program fails, the other one is cancelled
+        let (correct_output, test_output) = tokio::try_join!(
+            self.run_program_code_interface_async(
+                &self.correct_file,
+                stdin_content,
+                ProgramRole::Correct
+            ),
+            self.run_program_code_interface_async(
+                &self.test_file,
+                stdin_content,
+                ProgramRole::Test
+            ),
+        )?;
+
+        let verdict = self
+            .checker
+            .check_async(stdin_content, &correct_output, &test_output)
+            .await?;
 
-        Ok((
-            !file_utils::string_diff(&correct_output, &test_output),
-            correct_output,
-            test_output,
-        ))
+        Ok((verdict, correct_output, test_output))
     }
 
     fn run_program_code_interface(
         &self,
         language: &Language,
         stdin_content: &str,
-        file_type: FileType,
+        role: ProgramRole,
     ) -> Result<String, Box<RunnerErrorType>> {
         language
             .run_program_code(stdin_content)
-            .map_err(move |err| {
-                eprintln!("[PROGRAM STORE ERROR] Failed to run {file_type:?}!\n{err}");
-                err
-            })
+            .and_then(|result| program_output(language, result))
+            .map_err(|err| Box::new(RunnerErrorType::ProgramFailed(role, err)))
+    }
+
+    async fn run_program_code_interface_async(
+        &self,
+        language: &Language,
+        stdin_content: &str,
+        role: ProgramRole,
+    ) -> Result<String, Box<RunnerErrorType>> {
+        language
+            .run_program_code_async(stdin_content)
+            .await
+            .and_then(|result| program_output(language, result))
+            .map_err(|err| Box::new(RunnerErrorType::ProgramFailed(role, err)))
+    }
+}
+
+/// Output of a program which succeeded, or the error describing how it failed
+pub(crate) fn program_output(
+    language: &Language,
+    result: ExecutionResult,
+) -> Result<String, Box<RunnerErrorType>> {
+    let limits = &language.execution_limits;
+    match result.status {
+        ExecutionStatus::Success => Ok(result.stdout_lossy().into_owned()),
+        ExecutionStatus::TimeLimitExceeded => Err(Box::new(RunnerErrorType::TimeLimitExceeded(
+            limits.time_limit_ms.unwrap_or_default(),
+        ))),
+        ExecutionStatus::CpuTimeLimitExceeded => Err(Box::new(
+            RunnerErrorType::CpuTimeLimitExceeded(limits.cpu_time_limit_ms.unwrap_or_default()),
+        )),
+        ExecutionStatus::MemoryLimitExceeded => Err(Box::new(
+            RunnerErrorType::MemoryLimitExceeded(limits.memory_limit_bytes.unwrap_or_default()),
+        )),
+        ExecutionStatus::OutputLimitExceeded => Err(Box::new(
+            RunnerErrorType::OutputLimitExceeded(limits.output_limit_bytes.unwrap_or_default()),
+        )),
+        ExecutionStatus::Signaled(_) | ExecutionStatus::NonZeroExit(_) => {
+            match parse_sanitizer_report(&result.stderr_lossy()) {
+                Some(report) => Err(Box::new(RunnerErrorType::SanitizerError(
+                    Box::new(result),
+                    Box::new(report),
+                ))),
+                None => Err(Box::new(RunnerErrorType::RuntimeError(Box::new(result)))),
+            }
+        }
     }
 }
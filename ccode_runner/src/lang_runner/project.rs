@@ -0,0 +1,331 @@
+//! Detects how to build and run solutions which are directories rather than single files.
+//!
+//! Supported layouts are:
+//!
+//! - Cargo projects: a `Cargo.toml`, running its binary (`default-run`, the only `[[bin]]`, or the
+//!   package name)
+//! - Python packages: a `__main__.py`, run as the directory
+//! - C and C++ sources with local headers: every source file compiled together, with the project
+//!   on the include path, and exactly one defining `main`
+//! - Java packages: every source file compiled together, running the one class with a `main`
+//!   method by its fully qualified name
+//!
+//! How a project is compiled and run is set by the `project_compile` and `project_run` commands of
+//! its language's toolchain.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use super::language_name::LanguageName;
+use super::runner_error_types::RunnerErrorType;
+use super::toolchain::ToolchainConfig;
+use crate::utils::java_classname::get_java_public_classname_from_text;
+
+/// Directories skipped when collecting the files of a project, holding build outputs
+const SKIPPED_DIRS: [&str; 4] = ["target", "build", "node_modules", "__pycache__"];
+
+/// `main` of a C or C++ program
+static C_MAIN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*(?:int|signed|auto|void)\s+main\s*\(").unwrap());
+
+/// `main` method of a Java class
+static JAVA_MAIN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\bstatic\s+(?:final\s+)?void\s+main\s*\(").unwrap());
+
+/// `package` declaration of a Java file
+static JAVA_PACKAGE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*package\s+([\w.]+)\s*;").unwrap());
+
+/// A solution spread over the files of a directory
+#[derive(Debug, Clone)]
+pub(crate) struct Project {
+    pub(crate) root: PathBuf,
+    pub(crate) language: LanguageName,
+    /// What is run: the Cargo binary, the Java main class, or the file stem defining `main`
+    pub(crate) entry: String,
+    /// Source files given to the compiler
+    pub(crate) sources: Vec<PathBuf>,
+    /// Every file of the project, including headers and manifests, sorted
+    pub(crate) files: Vec<PathBuf>,
+}
+
+impl Project {
+    /// Detects the language and entry point of the project in the directory
+    pub(crate) fn detect(
+        root: &Path,
+        toolchains: &ToolchainConfig,
+    ) -> Result<Self, Box<RunnerErrorType>> {
+        let invalid =
+            |reason: String| Box::new(RunnerErrorType::InvalidProject(root.to_path_buf(), reason));
+
+        let mut files = Vec::new();
+        collect_files(root, &mut files).map_err(|err| invalid(err.to_string()))?;
+        files.sort();
+
+        let sources_of = |language: &LanguageName| -> Vec<PathBuf> {
+            files
+                .iter()
+                .filter(|file| toolchains.language_of(file).ok().as_ref() == Some(language))
+                .cloned()
+                .collect()
+        };
+
+        if root.join("Cargo.toml").is_file() {
+            let entry = cargo_binary(&root.join("Cargo.toml")).map_err(invalid)?;
+            return Ok(Project {
+                root: root.to_path_buf(),
+                language: LanguageName::Rust,
+                entry,
+                sources: sources_of(&LanguageName::Rust),
+                files,
+            });
+        }
+
+        if root.join("__main__.py").is_file() {
+            return Ok(Project {
+                root: root.to_path_buf(),
+                language: LanguageName::Python,
+                entry: String::from("__main__"),
+                sources: sources_of(&LanguageName::Python),
+                files,
+            });
+        }
+
+        let mut languages = Vec::new();
+        for file in &files {
+            if let Ok(language) = toolchains.language_of(file)
+                && !languages.contains(&language)
+            {
+                languages.push(language);
+            }
+        }
+        let language = match languages.as_slice() {
+            [language] => language.clone(),
+            [] => return Err(invalid(String::from("No source files found"))),
+            _ => {
+                let names: Vec<String> = languages.iter().map(ToString::to_string).collect();
+                return Err(invalid(format!(
+                    "Found sources in several languages: {}",
+                    names.join(", ")
+                )));
+            }
+        };
+
+        let sources = sources_of(&language);
+        let entry = match language {
+            LanguageName::C | LanguageName::Cpp => {
+                let entry_file =
+                    single_match(&sources, &C_MAIN, "main function").map_err(invalid)?;
+                file_stem(&entry_file).map_err(invalid)?
+            }
+            LanguageName::Java => {
+                let entry_file =
+                    single_match(&sources, &JAVA_MAIN, "main method").map_err(invalid)?;
+                java_main_class(&entry_file).map_err(invalid)?
+            }
+            LanguageName::Rust => {
+                return Err(invalid(String::from("Rust projects need a Cargo.toml")));
+            }
+            LanguageName::Python => {
+                return Err(invalid(String::from("Python projects need a __main__.py")));
+            }
+            language => {
+                return Err(invalid(format!("Projects in {language} are not supported")));
+            }
+        };
+
+        Ok(Project {
+            root: root.to_path_buf(),
+            language,
+            entry,
+            sources,
+            files,
+        })
+    }
+
+    /// Every file of the project along with its path, for keying compiled programs
+    pub(crate) fn contents(&self) -> io::Result<Vec<u8>> {
+        let mut contents = Vec::new();
+        for file in &self.files {
+            let relative_path = file.strip_prefix(&self.root).unwrap_or(file);
+            let relative_path = relative_path.to_string_lossy();
+            let file_contents = fs::read(file)?;
+            // Length prefixed, so that files can't run into each other
+            contents.extend((relative_path.len() as u64).to_le_bytes());
+            contents.extend(relative_path.as_bytes());
+            contents.extend((file_contents.len() as u64).to_le_bytes());
+            contents.extend(file_contents);
+        }
+        Ok(contents)
+    }
+}
+
+/// Collects the files in the directory recursively, skipping hidden entries and build outputs
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        if file_name.starts_with('.') {
+            continue;
+        }
+
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if !SKIPPED_DIRS.contains(&file_name.as_ref()) {
+                collect_files(&entry.path(), files)?;
+            }
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// The only source file matching the pattern
+fn single_match(sources: &[PathBuf], pattern: &Regex, what: &str) -> Result<PathBuf, String> {
+    let mut matches = sources.iter().filter(|source| {
+        fs::read_to_string(source).is_ok_and(|contents| pattern.is_match(&contents))
+    });
+
+    match (matches.next(), matches.next()) {
+        (Some(entry), None) => Ok(entry.clone()),
+        (None, _) => Err(format!("No source file has a {what}")),
+        (Some(first), Some(second)) => Err(format!(
+            "Several source files have a {what}, e.g. {} and {}",
+            first.display(),
+            second.display()
+        )),
+    }
+}
+
+fn file_stem(file: &Path) -> Result<String, String> {
+    file.file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(str::to_string)
+        .ok_or_else(|| format!("Invalid file name {}", file.display()))
+}
+
+/// Fully qualified name of the class with the `main` method, e.g. `com.example.Main`
+fn java_main_class(file: &Path) -> Result<String, String> {
+    let contents = fs::read_to_string(file).map_err(|err| err.to_string())?;
+    let class_name = match get_java_public_classname_from_text(&contents) {
+        Some(class_name) => class_name,
+        None => file_stem(file)?,
+    };
+
+    Ok(match JAVA_PACKAGE.captures(&contents) {
+        Some(package) => format!("{}.{class_name}", &package[1]),
+        None => class_name,
+    })
+}
+
+/// Name of the binary `cargo run` would run
+fn cargo_binary(manifest_path: &Path) -> Result<String, String> {
+    let manifest = fs::read_to_string(manifest_path).map_err(|err| err.to_string())?;
+    let manifest: toml::Table = toml::from_str(&manifest).map_err(|err| err.to_string())?;
+    let package = manifest
+        .get("package")
+        .and_then(|package| package.as_table());
+
+    if let Some(default_run) = package
+        .and_then(|package| package.get("default-run"))
+        .and_then(|default_run| default_run.as_str())
+    {
+        return Ok(default_run.to_string());
+    }
+
+    let binaries: Vec<&str> = manifest
+        .get("bin")
+        .and_then(|binaries| binaries.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|binary| binary.get("name")?.as_str())
+        .collect();
+    match binaries.as_slice() {
+        [binary] => Ok(binary.to_string()),
+        [] => package
+            .and_then(|package| package.get("name"))
+            .and_then(|name| name.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| String::from("Cargo.toml has no package name")),
+        _ => Err(String::from(
+            "Cargo.toml has several binaries, set `default-run` to pick one",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_files(root: &Path, files: &[(&str, &str)]) {
+        for (path, contents) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+    }
+
+    #[test]
+    fn detects_java_main_class_in_package() {
+        let dir = tempfile::tempdir().unwrap();
+        write_files(
+            dir.path(),
+            &[
+                (
+                    "com/example/Main.java",
+                    "package com.example;\npublic class Main {\n    public static void main(String[] args) {}\n}",
+                ),
+                (
+                    "com/example/util/Reader.java",
+                    "package com.example.util;\npublic class Reader {}",
+                ),
+            ],
+        );
+
+        let project = Project::detect(dir.path(), ToolchainConfig::builtin()).unwrap();
+        assert_eq!(project.language, LanguageName::Java);
+        assert_eq!(project.entry, "com.example.Main");
+        assert_eq!(project.sources.len(), 2);
+    }
+
+    #[test]
+    fn detects_cargo_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        write_files(
+            dir.path(),
+            &[
+                ("Cargo.toml", "[package]\nname = \"solution\"\n"),
+                ("src/main.rs", "fn main() {}"),
+                ("target/debug/build.rs", "fn main() {}"),
+            ],
+        );
+
+        let project = Project::detect(dir.path(), ToolchainConfig::builtin()).unwrap();
+        assert_eq!(project.language, LanguageName::Rust);
+        assert_eq!(project.entry, "solution");
+        assert_eq!(project.sources, [dir.path().join("src/main.rs")]);
+    }
+
+    #[test]
+    fn rejects_ambiguous_projects() {
+        let dir = tempfile::tempdir().unwrap();
+        write_files(
+            dir.path(),
+            &[("a.cpp", "int main() {}"), ("b.cpp", "int main() {}")],
+        );
+        assert!(Project::detect(dir.path(), ToolchainConfig::builtin()).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        write_files(
+            dir.path(),
+            &[("main.cpp", "int main() {}"), ("tool.py", "print(1)")],
+        );
+        assert!(Project::detect(dir.path(), ToolchainConfig::builtin()).is_err());
+    }
+}
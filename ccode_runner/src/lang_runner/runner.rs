@@ -1,17 +1,28 @@
 use crate::utils::program_utils;
-use crate::utils::program_utils::{ExecutionLimits, remake};
+use crate::utils::program_utils::{ExecutionLimits, ExecutionResult, remake};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
-use super::file_store::SourceCodeInfo;
+use super::compile_cache::CompileCache;
+use super::diagnostics::CompilerAttempt;
+use super::file_store::{SourceCodeInfo, compiled_file_path};
 use super::language_name::{CompilationType, LanguageName};
 use super::runner_error_types::RunnerErrorType;
+use super::toolchain::{CommandTemplate, LanguageToolchain, TemplateValues, ToolchainConfig};
+use super::worker_pool::{self, WorkerPool};
 
 #[derive(Debug)]
 pub struct Language {
     pub(crate) code: SourceCodeInfo,
     is_compiled: bool, // For program optimization
     do_force_compile: bool,
-    execution_limits: ExecutionLimits,
+    pub(crate) execution_limits: ExecutionLimits,
+    toolchain: LanguageToolchain,
+    /// Only used for programs compiled to a temporary destination
+    compile_cache: Option<CompileCache>,
+    /// Warm workers running the program, if enabled by the toolchain configuration
+    worker_pool: Option<Arc<WorkerPool>>,
 }
 
 impl Language {
@@ -24,19 +35,38 @@ impl Language {
         do_force_compile: bool,
         execution_limits: ExecutionLimits,
     ) -> Result<Self, Box<RunnerErrorType>> {
-        let code = SourceCodeInfo::new(file_path)?;
+        Self::new_with_toolchain(
+            file_path,
+            do_force_compile,
+            execution_limits,
+            ToolchainConfig::builtin(),
+        )
+    }
+
+    /// Same as `new_with_limits`, compiling and running the program with the given toolchains
+    pub fn new_with_toolchain(
+        file_path: &Path,
+        do_force_compile: bool,
+        execution_limits: ExecutionLimits,
+        toolchains: &ToolchainConfig,
+    ) -> Result<Self, Box<RunnerErrorType>> {
+        let code = SourceCodeInfo::new(file_path, toolchains)?;
 
         let mut lang = Self {
+            toolchain: toolchains.resolve(&code.language)?,
             code,
             is_compiled: false,
             do_force_compile,
             execution_limits,
+            compile_cache: toolchains.compile_cache().cloned(),
+            worker_pool: None,
         };
 
         // One time compilation/intermediate generation before code is actually run for the first time
         // For intreperted languages, no need to compile
         // For bytecode compiled languages, compile to bytecode as it might require intermediate compilation (eg Java)
         lang.compile_language()?;
+        lang.worker_pool = lang.start_worker_pool(toolchains.workers());
 
         Ok(lang)
     }
@@ -60,19 +90,44 @@ impl Language {
         do_force_compile: bool,
         execution_limits: ExecutionLimits,
     ) -> Result<Self, Box<RunnerErrorType>> {
-        let code = SourceCodeInfo::new_from_custom_dest(file_path, dest_path)?;
+        Self::new_from_custom_dest_with_toolchain(
+            file_path,
+            dest_path,
+            do_force_compile,
+            execution_limits,
+            ToolchainConfig::builtin(),
+        )
+    }
+
+    /// Same as `new_from_custom_dest_with_limits`, compiling and running the program with the
+    /// given toolchains
+    pub fn new_from_custom_dest_with_toolchain(
+        file_path: &Path,
+        dest_path: Option<&Path>,
+        do_force_compile: bool,
+        execution_limits: ExecutionLimits,
+        toolchains: &ToolchainConfig,
+    ) -> Result<Self, Box<RunnerErrorType>> {
+        let code = SourceCodeInfo::new_from_custom_dest(file_path, dest_path, toolchains)?;
 
         let mut lang = Self {
+            toolchain: toolchains.resolve(&code.language)?,
             code,
             is_compiled: false,
             do_force_compile,
             execution_limits,
+            compile_cache: toolchains
+                .compile_cache()
+                .filter(|_| dest_path.is_none())
+                .cloned(),
+            worker_pool: None,
         };
 
         // One time compilation/intermediate generation before code is actually run for the first time
         // For intreperted languages, no need to compile
         // For bytecode compiled languages, compile to bytecode as it might require intermediate compilation (eg Java)
         lang.compile_language()?;
+        lang.worker_pool = lang.start_worker_pool(toolchains.workers());
 
         Ok(lang)
     }
@@ -96,92 +151,221 @@ impl Language {
         do_force_compile: bool,
         execution_limits: ExecutionLimits,
     ) -> Result<Self, Box<RunnerErrorType>> {
-        let code = SourceCodeInfo::new_from_text(source_text, lang)?;
+        Self::new_from_text_with_toolchain(
+            source_text,
+            lang,
+            do_force_compile,
+            execution_limits,
+            ToolchainConfig::builtin(),
+        )
+    }
+
+    /// Same as `new_from_text_with_limits`, compiling and running the program with the given
+    /// toolchains
+    pub fn new_from_text_with_toolchain(
+        source_text: &str,
+        lang: LanguageName,
+        do_force_compile: bool,
+        execution_limits: ExecutionLimits,
+        toolchains: &ToolchainConfig,
+    ) -> Result<Self, Box<RunnerErrorType>> {
+        let code = SourceCodeInfo::new_from_text(source_text, lang, toolchains)?;
 
         let mut lang = Self {
+            toolchain: toolchains.resolve(&code.language)?,
             code,
             is_compiled: false,
             do_force_compile,
             execution_limits,
+            compile_cache: toolchains.compile_cache().cloned(),
+            worker_pool: None,
         };
 
         // One time compilation/intermediate generation before code is actually run for the first time
         // For intreperted languages, no need to compile
         // For bytecode compiled languages, compile to bytecode as it might require intermediate compilation (eg Java)
         lang.compile_language()?;
+        lang.worker_pool = lang.start_worker_pool(toolchains.workers());
         Ok(lang)
     }
 
     /// Running single filed self executable program
-    pub(crate) fn run_program_code(
+    ///
+    /// A program which runs but fails (non-zero exit, signal, timeout) is still returned as `Ok`,
+    /// with the `ExecutionResult` describing how it ended.
+    pub fn run_program_code(
+        &self,
+        stdin_content: &str,
+    ) -> Result<ExecutionResult, Box<RunnerErrorType>> {
+        self.run_program_code_with_args(stdin_content, &[])
+    }
+
+    /// Running single filed self executable program, passing it the command line arguments
+    ///
+    /// The run commands of the toolchain are tried in order, falling back to the next one only if
+    /// the program couldn't be launched. Without arguments, the program runs in a warm worker if
+    /// the toolchain configuration enables them.
+    pub fn run_program_code_with_args(
+        &self,
+        stdin_content: &str,
+        program_args: &[&str],
+    ) -> Result<ExecutionResult, Box<RunnerErrorType>> {
+        if program_args.is_empty()
+            && let Some(worker_pool) = &self.worker_pool
+            && let Some(result) = worker_pool.run(stdin_content, self.time_limit(), None)
+        {
+            return Ok(result);
+        }
+
+        self.run_in_new_process(stdin_content, program_args)
+    }
+
+    /// Same as `run_program_code_with_args`, never running the program in a warm worker
+    pub(crate) fn run_in_new_process(
         &self,
         stdin_content: &str,
-    ) -> Result<String, Box<RunnerErrorType>> {
-        match self.code.compilation_type {
-            CompilationType::Compiled => {
-                if !self.is_compiled {
-                    return Err(Box::new(RunnerErrorType::WarmupCompileFatal));
+        program_args: &[&str],
+    ) -> Result<ExecutionResult, Box<RunnerErrorType>> {
+        for (program, args) in self.run_commands(program_args)? {
+            let execution_result = program_utils::run_program_with_input(
+                &program,
+                &args.iter().map(String::as_str).collect(),
+                stdin_content,
+                &self.execution_limits,
+            );
+            match execution_result {
+                Ok(result) => {
+                    return Ok(result);
                 }
-                Ok(program_utils::run_program_with_input(
-                    self.code
-                        .get_dest_file_str()
-                        .ok_or(RunnerErrorType::EmptyDestinationPath(
-                            self.code.source_path.to_path_buf(),
-                            self.code.language.clone(),
-                            self.code.compilation_type.clone(),
-                        ))?,
-                    &vec![],
-                    stdin_content,
-                    &self.execution_limits,
-                )
-                .map_err(|err| Box::new(RunnerErrorType::ProgramRunError(Box::new(err))))?)
-            }
-            CompilationType::Interpreted => {
-                // Need to Just Run
-                Ok(self.run_interpreted_language(stdin_content)?)
+                Err(err) => self.warn_run_failure(&program, &err),
             }
-            CompilationType::BytecodeCompiled => {
-                if !self.is_compiled {
-                    return Err(Box::new(RunnerErrorType::WarmupCompileFatal));
-                }
+        }
+
+        Err(Box::new(RunnerErrorType::CodeRunFailed(
+            self.code.source_path.to_path_buf(),
+        )))
+    }
+
+    /// Same as `run_program_code`, without blocking the async runtime
+    ///
+    /// Dropping the returned future (e.g. in `tokio::select!` or `tokio::time::timeout`) cancels
+    /// the run, killing the program along with every process it started.
+    pub async fn run_program_code_async(
+        &self,
+        stdin_content: &str,
+    ) -> Result<ExecutionResult, Box<RunnerErrorType>> {
+        self.run_program_code_with_args_async(stdin_content, &[])
+            .await
+    }
+
+    /// Same as `run_program_code_with_args`, without blocking the async runtime, see
+    /// `run_program_code_async`
+    pub async fn run_program_code_with_args_async(
+        &self,
+        stdin_content: &str,
+        program_args: &[&str],
+    ) -> Result<ExecutionResult, Box<RunnerErrorType>> {
+        if program_args.is_empty()
+            && let Some(worker_pool) = &self.worker_pool
+            && let Some(result) = Arc::clone(worker_pool)
+                .run_async(stdin_content.to_string(), self.time_limit())
+                .await
+        {
+            return Ok(result);
+        }
+
+        self.run_in_new_process_async(stdin_content, program_args)
+            .await
+    }
 
-                match self.code.language {
-                    LanguageName::Java => Ok(program_utils::run_program_with_input(
-                        "java",
-                        &vec![
-                            "-cp",
-                            self.code
-                                .temp_dir
-                                .as_ref()
-                                .ok_or(RunnerErrorType::EmptyTempDir(
-                                    self.code.source_path.to_path_buf(),
-                                    self.code.language.clone(),
-                                    self.code.compilation_type.clone(),
-                                ))?
-                                .to_str()
-                                .unwrap_or_default(),
-                            self.code
-                                .source_path
-                                .file_stem()
-                                .ok_or(RunnerErrorType::FileStemExtractionError(
-                                    self.code.source_path.to_path_buf(),
-                                ))?
-                                .to_str()
-                                .ok_or(RunnerErrorType::InvalidFileName(
-                                    self.code.source_path.to_path_buf(),
-                                ))?,
-                        ],
-                        stdin_content,
-                        &self.execution_limits,
-                    )
-                    .map_err(|err| Box::new(RunnerErrorType::ProgramRunError(Box::new(err))))?),
-                    _ => Err(Box::new(RunnerErrorType::InvalidLanguageMapping(
-                        self.code.language.clone(),
-                        self.code.compilation_type.clone(),
-                    ))),
+    /// Same as `run_program_code_with_args_async`, never running the program in a warm worker
+    pub(crate) async fn run_in_new_process_async(
+        &self,
+        stdin_content: &str,
+        program_args: &[&str],
+    ) -> Result<ExecutionResult, Box<RunnerErrorType>> {
+        for (program, args) in self.run_commands(program_args)? {
+            let execution_result = program_utils::run_program_with_input_async(
+                &program,
+                &args.iter().map(String::as_str).collect::<Vec<_>>(),
+                stdin_content,
+                &self.execution_limits,
+            )
+            .await;
+            match execution_result {
+                Ok(result) => {
+                    return Ok(result);
                 }
+                Err(err) => self.warn_run_failure(&program, &err),
+            }
+        }
+
+        Err(Box::new(RunnerErrorType::CodeRunFailed(
+            self.code.source_path.to_path_buf(),
+        )))
+    }
+
+    /// Commands running the program with the given arguments, in the order they are tried
+    fn run_commands(
+        &self,
+        program_args: &[&str],
+    ) -> Result<Vec<(String, Vec<String>)>, Box<RunnerErrorType>> {
+        if self.code.compilation_type != CompilationType::Interpreted && !self.is_compiled {
+            return Err(Box::new(RunnerErrorType::WarmupCompileFatal));
+        }
+
+        self.run_templates()
+            .iter()
+            .map(|template| {
+                let (program, mut args) = self.expand(template)?;
+                args.extend(program_args.iter().map(|arg| arg.to_string()));
+                Ok((program, args))
+            })
+            .collect()
+    }
+
+    fn time_limit(&self) -> Option<Duration> {
+        self.execution_limits
+            .time_limit_ms
+            .map(Duration::from_millis)
+    }
+
+    /// Starts warm workers running the program, if its language has a worker harness which can be
+    /// launched, and its limits can be enforced by workers
+    fn start_worker_pool(&self, size: usize) -> Option<Arc<WorkerPool>> {
+        if size == 0 || !worker_pool::supports_limits(&self.execution_limits) {
+            return None;
+        }
+        let (harness_dir, harness) = worker_pool::write_harness(&self.code.language).ok()??;
+
+        for template in &self.toolchain.worker {
+            let (program, args) = self
+                .expand_for(template, self.code.dest_path.as_deref(), &harness)
+                .ok()?;
+            if program_utils::program_exists(&program).is_ok() {
+                return WorkerPool::start(program, args, size, harness_dir)
+                    .ok()
+                    .map(Arc::new);
             }
         }
+        None
+    }
+
+    /// Commands running the program, tried in order until one can be launched
+    fn run_templates(&self) -> &[CommandTemplate] {
+        match self.code.project {
+            Some(_) if !self.toolchain.project_run.is_empty() => &self.toolchain.project_run,
+            _ => &self.toolchain.run,
+        }
+    }
+
+    fn warn_run_failure(&self, program: &str, err: &std::io::Error) {
+        eprintln!(
+            "[RUNNER WARNING] Failed to run {} code with {} with reason {}",
+            self.code.source_path.display(),
+            program,
+            err
+        );
     }
 
     fn compile_language(&mut self) -> Result<(), RunnerErrorType> {
@@ -191,6 +375,14 @@ impl Language {
             return Ok(()); // No compilation needed
         }
 
+        if self.is_compiled {
+            return Ok(());
+        }
+
+        if let Some(compile_cache) = self.compile_cache.clone() {
+            return self.compile_cached(&compile_cache);
+        }
+
         let dest_file = match &self.code.dest_path {
             Some(dest_path) => dest_path,
             None => {
@@ -202,290 +394,179 @@ impl Language {
             }
         };
 
-        // Checking if the file is already compiled/doesn't need recompilation
-        if self.is_compiled
-            || (!self.do_force_compile
-                && !remake(&self.code.source_path, &PathBuf::from(dest_file)).unwrap_or(true))
+        // Checking if the file is already compiled/doesn't need recompilation. Projects are left
+        // to their build tool, as any of their files may have changed
+        if !self.do_force_compile
+            && self.code.project.is_none()
+            && !remake(&self.code.source_path, &PathBuf::from(dest_file)).unwrap_or(true)
         {
             self.is_compiled = true; // Helps a lot in saving time, checking for need for compilations
             return Ok(());
         }
 
-        let file_path_str =
-            self.code
-                .source_path
-                .to_str()
-                .ok_or(RunnerErrorType::InvalidFileName(
-                    self.code.source_path.to_path_buf(),
-                ))?;
-        let compilers =
-            match self.code.language {
-                LanguageName::C => vec![
-                    (
-                        "gcc",
-                        vec![
-                            "-o",
-                            dest_file
-                                .to_str()
-                                .ok_or(RunnerErrorType::EmptyDestinationPath(
-                                    self.code.source_path.to_path_buf(),
-                                    self.code.language.clone(),
-                                    self.code.compilation_type.clone(),
-                                ))?,
-                            &self.code.source_path.to_str().ok_or(
-                                RunnerErrorType::InvalidFileName(
-                                    self.code.source_path.to_path_buf(),
-                                ),
-                            )?,
-                        ],
-                    ),
-                    (
-                        "clang",
-                        vec![
-                            "-o",
-                            dest_file
-                                .to_str()
-                                .ok_or(RunnerErrorType::EmptyDestinationPath(
-                                    self.code.source_path.to_path_buf(),
-                                    self.code.language.clone(),
-                                    self.code.compilation_type.clone(),
-                                ))?,
-                            &self.code.source_path.to_str().ok_or(
-                                RunnerErrorType::InvalidFileName(
-                                    self.code.source_path.to_path_buf(),
-                                ),
-                            )?,
-                        ],
-                    ),
-                    (
-                        "zig",
-                        vec![
-                            "cc",
-                            "-o",
-                            dest_file
-                                .to_str()
-                                .ok_or(RunnerErrorType::EmptyDestinationPath(
-                                    self.code.source_path.to_path_buf(),
-                                    self.code.language.clone(),
-                                    self.code.compilation_type.clone(),
-                                ))?,
-                            &self.code.source_path.to_str().ok_or(
-                                RunnerErrorType::InvalidFileName(
-                                    self.code.source_path.to_path_buf(),
-                                ),
-                            )?,
-                        ],
-                    ),
-                ],
-                LanguageName::Cpp => vec![
-                    (
-                        "g++",
-                        vec![
-                            "-o",
-                            dest_file
-                                .to_str()
-                                .ok_or(RunnerErrorType::EmptyDestinationPath(
-                                    self.code.source_path.to_path_buf(),
-                                    self.code.language.clone(),
-                                    self.code.compilation_type.clone(),
-                                ))?,
-                            &self.code.source_path.to_str().ok_or(
-                                RunnerErrorType::InvalidFileName(
-                                    self.code.source_path.to_path_buf(),
-                                ),
-                            )?,
-                        ],
-                    ),
-                    (
-                        "clang++",
-                        vec![
-                            "-o",
-                            dest_file
-                                .to_str()
-                                .ok_or(RunnerErrorType::EmptyDestinationPath(
-                                    self.code.source_path.to_path_buf(),
-                                    self.code.language.clone(),
-                                    self.code.compilation_type.clone(),
-                                ))?,
-                            &self.code.source_path.to_str().ok_or(
-                                RunnerErrorType::InvalidFileName(
-                                    self.code.source_path.to_path_buf(),
-                                ),
-                            )?,
-                        ],
-                    ),
-                    (
-                        "zig",
-                        vec![
-                            "c++",
-                            "-o",
-                            dest_file
-                                .to_str()
-                                .ok_or(RunnerErrorType::EmptyDestinationPath(
-                                    self.code.source_path.to_path_buf(),
-                                    self.code.language.clone(),
-                                    self.code.compilation_type.clone(),
-                                ))?,
-                            &self.code.source_path.to_str().ok_or(
-                                RunnerErrorType::InvalidFileName(
-                                    self.code.source_path.to_path_buf(),
-                                ),
-                            )?,
-                        ],
-                    ),
-                ],
-                LanguageName::Rust => {
-                    vec![(
-                        "rustc",
-                        vec![
-                            "-o",
-                            dest_file
-                                .to_str()
-                                .ok_or(RunnerErrorType::EmptyDestinationPath(
-                                    self.code.source_path.to_path_buf(),
-                                    self.code.language.clone(),
-                                    self.code.compilation_type.clone(),
-                                ))?,
-                            &self.code.source_path.to_str().ok_or(
-                                RunnerErrorType::InvalidFileName(
-                                    self.code.source_path.to_path_buf(),
-                                ),
-                            )?,
-                        ],
-                    )]
-                }
-                LanguageName::Java => vec![(
-                    "javac",
-                    vec![
-                        "-d",
-                        self.code
-                            .temp_dir
-                            .as_ref()
-                            .ok_or(RunnerErrorType::EmptyTempDir(
-                                self.code.source_path.to_path_buf(),
-                                self.code.language.clone(),
-                                self.code.compilation_type.clone(),
-                            ))?
-                            .to_str()
-                            .ok_or(RunnerErrorType::InvalidFileName(
-                                self.code.source_path.to_path_buf(),
-                            ))?,
-                        file_path_str,
-                    ],
-                )],
-                _ => {
-                    return Err(RunnerErrorType::InvalidCompilationMapping(
-                        self.code.language.clone(),
-                    ));
-                }
-            };
+        self.run_compilers(dest_file)?;
+        self.is_compiled = true;
+        Ok(())
+    }
+
+    /// Compiles into the cache, unless a program compiled from the same code and toolchain is
+    /// already there
+    fn compile_cached(&mut self, compile_cache: &CompileCache) -> Result<(), RunnerErrorType> {
+        let source = match &self.code.project {
+            Some(project) => project.contents(),
+            None => std::fs::read(&self.code.source_path),
+        }
+        .map_err(|_| RunnerErrorType::FileNotFound(self.code.source_path.to_path_buf()))?;
+        let name = self
+            .code
+            .program_name()
+            .ok_or(RunnerErrorType::FileStemExtractionError(
+                self.code.source_path.to_path_buf(),
+            ))?;
+
+        let key = CompileCache::key(&source, &self.code.language, name, &self.toolchain);
+        let dest_path = compiled_file_path(&compile_cache.entry_dir(&key), name, &self.toolchain);
+
+        if self.do_force_compile || !compile_cache.lookup(&key) {
+            let staging_dir = compile_cache.staging_dir().map_err(|err| *err)?;
+            let compiled =
+                self.run_compilers(&compiled_file_path(&staging_dir, name, &self.toolchain));
+            if let Err(err) = compiled {
+                let _ = std::fs::remove_dir_all(&staging_dir);
+                return Err(err);
+            }
+            compile_cache
+                .insert(&key, &staging_dir)
+                .map_err(|err| *err)?;
+        }
+
+        self.code.dest_path = Some(dest_path);
+        self.is_compiled = true;
+        Ok(())
+    }
+
+    /// Compiles the program to `dest_file`, trying each compiler of the toolchain in order.
+    ///
+    /// If none succeeds, the output of each one is returned in a `CompilationError`.
+    fn run_compilers(&self, dest_file: &Path) -> Result<(), RunnerErrorType> {
+        let compilers = match self.code.project {
+            Some(_) => &self.toolchain.project_compile,
+            None => &self.toolchain.compile,
+        };
+        if compilers.is_empty() {
+            return Err(RunnerErrorType::InvalidCompilationMapping(
+                self.code.language.clone(),
+            ));
+        }
+
+        let mut attempts = Vec::new();
+        for template in compilers {
+            let (compiler, args) = self.expand_for(template, Some(dest_file), "")?;
 
-        for (compiler, args) in compilers {
             // Use default limits for compilation (no limits during compilation)
-            let std_out = program_utils::run_program(compiler, &args, &ExecutionLimits::default());
-            match std_out {
-                Ok(_) => {
-                    self.is_compiled = true;
+            let compiled = program_utils::run_program_with_input(
+                &compiler,
+                &args.iter().map(String::as_str).collect(),
+                "",
+                &ExecutionLimits::default(),
+            );
+            match compiled {
+                Ok(result) if result.is_success() => {
                     return Ok(());
                 }
-                Err(err) => {
-                    eprintln!(
-                        "[RUNNER WARNING] Failed to compile {dest_file:?} code with {compiler} with reason {err}"
-                    );
-                }
+                Ok(result) => attempts.push(CompilerAttempt::failed(&compiler, &result)),
+                Err(err) => attempts.push(CompilerAttempt::not_launched(&compiler, &err)),
             }
         }
 
-        Err(RunnerErrorType::CodeRunFailed(
+        Err(RunnerErrorType::CompilationError(
             self.code.source_path.to_path_buf(),
+            attempts,
         ))
     }
 
-    fn run_interpreted_language(&self, stdin_content: &str) -> Result<String, RunnerErrorType> {
-        let interpreters =
-            match self.code.language {
-                LanguageName::Python => vec![
-                    (
-                        "python3",
-                        vec![self.code.source_path.to_str().ok_or(
-                            RunnerErrorType::InvalidFileName(self.code.source_path.to_path_buf()),
-                        )?],
-                    ),
-                    (
-                        "python",
-                        vec![self.code.source_path.to_str().ok_or(
-                            RunnerErrorType::InvalidFileName(self.code.source_path.to_path_buf()),
-                        )?],
-                    ),
-                ],
-                LanguageName::Ruby => vec![(
-                    "ruby",
-                    vec![self.code.source_path.to_str().ok_or(
-                        RunnerErrorType::InvalidFileName(self.code.source_path.to_path_buf()),
-                    )?],
-                )],
-                LanguageName::Javascript => vec![
-                    (
-                        "node",
-                        vec![self.code.source_path.to_str().ok_or(
-                            RunnerErrorType::InvalidFileName(self.code.source_path.to_path_buf()),
-                        )?],
-                    ),
-                    (
-                        "deno",
-                        vec![
-                            "run",
-                            self.code.source_path.to_str().ok_or(
-                                RunnerErrorType::InvalidFileName(
-                                    self.code.source_path.to_path_buf(),
-                                ),
-                            )?,
-                        ],
-                    ),
-                    (
-                        "bun",
-                        vec![self.code.source_path.to_str().ok_or(
-                            RunnerErrorType::InvalidFileName(self.code.source_path.to_path_buf()),
-                        )?],
-                    ),
-                ],
-                _ => {
-                    return Err(RunnerErrorType::InvalidLanguageMapping(
-                        self.code.language.clone(),
-                        self.code.compilation_type.clone(),
-                    ));
-                }
-            };
+    /// Fills in the placeholders of a toolchain command for this program
+    fn expand(&self, template: &CommandTemplate) -> Result<(String, Vec<String>), RunnerErrorType> {
+        self.expand_for(template, self.code.dest_path.as_deref(), "")
+    }
 
-        for (interpreter, args) in interpreters {
-            let std_out = program_utils::run_program_with_input(
-                interpreter,
-                &args,
-                stdin_content,
-                &self.execution_limits,
-            );
-            match std_out {
-                Ok(output) => {
-                    return Ok(output);
-                }
-                Err(err) => {
-                    eprintln!(
-                        "[INTERPRETER WARNING] Failed to run {} code with {} with reason {}",
-                        self.code
-                            .source_path
-                            .to_str()
-                            .ok_or(RunnerErrorType::InvalidFileName(
-                                self.code.source_path.to_path_buf(),
-                            ))?,
-                        interpreter,
-                        err
-                    );
-                }
+    /// Fills in the placeholders of a toolchain command, with the compiled program at `dest_file`
+    /// and the worker harness at `worker`
+    fn expand_for(
+        &self,
+        template: &CommandTemplate,
+        dest_file: Option<&Path>,
+        worker: &str,
+    ) -> Result<(String, Vec<String>), RunnerErrorType> {
+        let source = self
+            .code
+            .source_path
+            .to_str()
+            .ok_or(RunnerErrorType::InvalidFileName(
+                self.code.source_path.to_path_buf(),
+            ))?;
+        let name = self
+            .code
+            .program_name()
+            .ok_or(RunnerErrorType::FileStemExtractionError(
+                self.code.source_path.to_path_buf(),
+            ))?;
+        let sources = match &self.code.project {
+            Some(project) => project
+                .sources
+                .iter()
+                .map(|source| {
+                    source
+                        .to_str()
+                        .ok_or_else(|| RunnerErrorType::InvalidFileName(source.to_path_buf()))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => vec![source],
+        };
+        let project = match &self.code.project {
+            Some(project) => project.root.as_path(),
+            None => self.code.source_path.parent().unwrap_or(Path::new("")),
+        }
+        .to_str()
+        .ok_or(RunnerErrorType::InvalidFileName(
+            self.code.source_path.to_path_buf(),
+        ))?;
+        // Interpreted programs have no compiled program
+        let output = dest_file.and_then(|dest| dest.to_str()).unwrap_or_default();
+        let output_dir = dest_file
+            .and_then(|dest| dest.parent())
+            .and_then(|dir| dir.to_str())
+            .unwrap_or_default();
+
+        Ok(template.expand(&TemplateValues {
+            source,
+            output,
+            output_dir,
+            name,
+            project,
+            sources: &sources,
+            worker,
+        }))
+    }
+
+    /// Command running the program, for running it in ways other than `run_program_code`.
+    ///
+    /// Uses the first run command whose program is found, without falling back to the next one if
+    /// it fails to launch.
+    pub(crate) fn run_command(&self) -> Result<(String, Vec<String>), Box<RunnerErrorType>> {
+        if self.code.compilation_type != CompilationType::Interpreted && !self.is_compiled {
+            return Err(Box::new(RunnerErrorType::WarmupCompileFatal));
+        }
+
+        for template in self.run_templates() {
+            let (program, args) = self.expand(template)?;
+            if program_utils::program_exists(&program).is_ok() {
+                return Ok((program, args));
             }
         }
 
-        Err(RunnerErrorType::CodeRunFailed(
+        Err(Box::new(RunnerErrorType::CodeRunFailed(
             self.code.source_path.to_path_buf(),
-        ))
+        )))
     }
 }
@@ -12,7 +12,11 @@
 use core::fmt;
 use std::{error::Error, path::PathBuf};
 
+use super::diagnostics::CompilerAttempt;
 use super::language_name::{CompilationType, LanguageName};
+use super::program_store::ProgramRole;
+use super::sanitizer::SanitizerReport;
+use crate::utils::program_utils::{ExecutionResult, ExecutionStatus};
 
 /// Represents errors that can occur during the execution of a code runner.
 ///
@@ -110,20 +114,92 @@ pub enum RunnerErrorType {
     /// The associated `Option<PathBuf>` contains the path to the destination file
     JavaMismatchDestinationFile(Option<PathBuf>),
 
-    /// Indicates that the program execution exceeded the specified time limit.
+    /// Indicates that the program execution exceeded the specified wall clock time limit.
     ///
     /// The associated `u64` contains the time limit in milliseconds.
     TimeLimitExceeded(u64),
 
+    /// Indicates that the program used more CPU time than the specified CPU time limit.
+    ///
+    /// The associated `u64` contains the CPU time limit in milliseconds.
+    CpuTimeLimitExceeded(u64),
+
     /// Indicates that the program execution exceeded the specified memory limit.
     ///
     /// The associated `u64` contains the memory limit in bytes.
     MemoryLimitExceeded(u64),
+
+    /// Indicates that the program wrote more to stdout than the specified output limit.
+    ///
+    /// The associated `u64` contains the output limit in bytes.
+    OutputLimitExceeded(u64),
+
+    /// Indicates that the program ran, but exited with a non-zero exit code or was terminated
+    /// by a signal.
+    ///
+    /// The associated `ExecutionResult` describes how the program ended, including its output.
+    RuntimeError(Box<ExecutionResult>),
+
+    /// Indicates that the output checker itself failed, rather than rejecting the output.
+    ///
+    /// The associated `String` describes the failure, e.g. the special judge's exit status and message.
+    CheckerFailed(String),
+
+    /// Indicates that a toolchain configuration could not be read or is invalid.
+    ///
+    /// The associated `String` describes the problem.
+    InvalidToolchainConfig(String),
+
+    /// Indicates that every compiler of the toolchain failed to compile the program.
+    ///
+    /// The associated `PathBuf` contains the path to the source file, and the
+    /// `Vec<CompilerAttempt>` the output and parsed diagnostics of each compiler tried.
+    CompilationError(PathBuf, Vec<CompilerAttempt>),
+
+    /// Indicates that the programs given for a comparison can't be compared, e.g. there are fewer
+    /// than two of them.
+    ///
+    /// The associated `String` describes the problem.
+    InvalidProgramSet(String),
+
+    /// Indicates that a sanitizer stopped the program, e.g. on an out of bounds access, which is
+    /// only checked in the debug build profile.
+    ///
+    /// The associated `ExecutionResult` describes how the program ended, and the
+    /// `SanitizerReport` what the sanitizer found.
+    SanitizerError(Box<ExecutionResult>, Box<SanitizerReport>),
+
+    /// Indicates that a directory could not be built and run as a project, e.g. as several of its
+    /// source files define `main`.
+    ///
+    /// The associated `PathBuf` contains the path to the directory, and the `String` the reason.
+    InvalidProject(PathBuf, String),
+
+    /// Indicates that one of the programs of a `ProgramStore` failed, e.g. exceeded a limit.
+    ///
+    /// The associated `ProgramRole` tells which program failed, and the `RunnerErrorType` how.
+    ProgramFailed(ProgramRole, Box<RunnerErrorType>),
 }
 
-impl fmt::Display for RunnerErrorType {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let error_description = match self {
+impl RunnerErrorType {
+    /// The error itself, without the role of the program that caused it (see `ProgramFailed`)
+    pub fn program_error(&self) -> &RunnerErrorType {
+        match self {
+            RunnerErrorType::ProgramFailed(_, err) => err.program_error(),
+            err => err,
+        }
+    }
+
+    /// The program that caused the error, if it came from one of the programs of a `ProgramStore`
+    pub fn program_role(&self) -> Option<ProgramRole> {
+        match self {
+            RunnerErrorType::ProgramFailed(role, _) => Some(*role),
+            _ => None,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
             RunnerErrorType::InvalidFileExtension(path_buf) => {
                 format!(
                     "Cannot determine file extension for file: {}",
@@ -184,10 +260,65 @@ impl fmt::Display for RunnerErrorType {
             RunnerErrorType::TimeLimitExceeded(time_limit_ms) => {
                 format!("Program execution exceeded the time limit of {time_limit_ms} milliseconds")
             }
+            RunnerErrorType::CpuTimeLimitExceeded(cpu_time_limit_ms) => {
+                format!(
+                    "Program execution exceeded the CPU time limit of {cpu_time_limit_ms} milliseconds"
+                )
+            }
             RunnerErrorType::MemoryLimitExceeded(memory_limit_bytes) => {
                 format!("Program execution exceeded the memory limit of {memory_limit_bytes} bytes")
             }
-        };
+            RunnerErrorType::OutputLimitExceeded(output_limit_bytes) => {
+                format!("Program output exceeded the output limit of {output_limit_bytes} bytes")
+            }
+            RunnerErrorType::RuntimeError(result) => match result.status {
+                ExecutionStatus::Signaled(signal) => format!(
+                    "Program was terminated by signal {signal}\nError: {}",
+                    result.stderr_lossy()
+                ),
+                ExecutionStatus::NonZeroExit(exit_code) => format!(
+                    "Program exited with exit code {exit_code}\nError: {}",
+                    result.stderr_lossy()
+                ),
+                status => format!(
+                    "Program failed with {status:?}\nError: {}",
+                    result.stderr_lossy()
+                ),
+            },
+            RunnerErrorType::CheckerFailed(reason) => {
+                format!("Output checker failed: {reason}")
+            }
+            RunnerErrorType::InvalidToolchainConfig(reason) => {
+                format!("Invalid toolchain configuration: {reason}")
+            }
+            RunnerErrorType::CompilationError(filepath, attempts) => format!(
+                "Failed to compile {}\n{}",
+                filepath.display(),
+                attempts
+                    .iter()
+                    .map(|attempt| attempt.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+            RunnerErrorType::InvalidProgramSet(reason) => {
+                format!("Invalid set of programs to compare: {reason}")
+            }
+            RunnerErrorType::InvalidProject(path_buf, reason) => {
+                format!("Cannot build project {}: {reason}", path_buf.display())
+            }
+            RunnerErrorType::SanitizerError(_, report) => {
+                format!("Program was stopped by {report}\n{}", report.report)
+            }
+            RunnerErrorType::ProgramFailed(role, err) => {
+                format!("The {role} program failed: {}", err.describe())
+            }
+        }
+    }
+}
+
+impl fmt::Display for RunnerErrorType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let error_description = self.describe();
 
         write!(
             f,
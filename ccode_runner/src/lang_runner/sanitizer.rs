@@ -0,0 +1,293 @@
+//! Recognizes the reports sanitizers print when they stop a program, in the debug build profile.
+//!
+//! Reports are recognized in the formats of:
+//!
+//! - AddressSanitizer and LeakSanitizer: `==42==ERROR: AddressSanitizer: heap-buffer-overflow on
+//!   address ...`, located by the `SUMMARY: AddressSanitizer: ... main.cpp:5 in main` line
+//! - UndefinedBehaviorSanitizer: `main.cpp:4:7: runtime error: signed integer overflow: ...`
+//! - Rust panics, e.g. of the debug build's overflow checks: `thread 'main' (42) panicked at
+//!   src/main.rs:5:20:` followed by the message (or `thread 'main' panicked at 'message',
+//!   src/main.rs:5:20` before Rust 1.73)
+//!
+//! Undefined behavior only stops the program when compiled with `-fno-sanitize-recover`, as done by
+//! the built-in debug toolchains.
+
+use std::fmt;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// `==pid==ERROR: kind: error on address ...`, starting an AddressSanitizer or LeakSanitizer report
+static SANITIZER_ERROR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^==\d+==ERROR: (?P<kind>AddressSanitizer|LeakSanitizer): (?P<error>.*?)(?: on (?:unknown )?address .*)?$")
+        .unwrap()
+});
+
+/// `SUMMARY: kind: error file:line[:column] in function`
+static SANITIZER_SUMMARY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^SUMMARY: \w+: \S+ (?P<file>[^\s:]+):(?P<line>\d+)(?::(?P<column>\d+))?").unwrap()
+});
+
+/// `file:line:column: runtime error: message`, as printed by UndefinedBehaviorSanitizer
+static UNDEFINED_BEHAVIOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?P<file>[^:\s][^:]*):(?P<line>\d+):(?P<column>\d+): runtime error: (?P<message>.*)$",
+    )
+    .unwrap()
+});
+
+/// `thread 'name' (id) panicked at file:line:column:`, followed by the message on the next lines.
+/// The thread id is only printed by recent versions of Rust.
+static PANIC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^thread '[^']*'(?: \(\d+\))? panicked at (?P<file>[^:\s][^:]*):(?P<line>\d+):(?P<column>\d+):$")
+        .unwrap()
+});
+
+/// `thread 'name' panicked at 'message', file:line:column`, as printed before Rust 1.73
+static LEGACY_PANIC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^thread '[^']*' panicked at '(?P<message>.*)', (?P<file>[^:\s][^:]*):(?P<line>\d+):(?P<column>\d+)$")
+        .unwrap()
+});
+
+/// The sanitizer which stopped the program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SanitizerKind {
+    /// Out of bounds accesses, use after free and other memory errors
+    Address,
+    /// Memory which was never freed
+    Leak,
+    /// Integer overflow, invalid shifts and other undefined behavior
+    UndefinedBehavior,
+    /// A panicking Rust program, e.g. on an integer overflow caught by the debug build's overflow
+    /// checks, or an out of bounds index
+    Panic,
+}
+
+impl fmt::Display for SanitizerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SanitizerKind::Address => write!(f, "AddressSanitizer"),
+            SanitizerKind::Leak => write!(f, "LeakSanitizer"),
+            SanitizerKind::UndefinedBehavior => write!(f, "UndefinedBehaviorSanitizer"),
+            SanitizerKind::Panic => write!(f, "Rust panic"),
+        }
+    }
+}
+
+/// Where in the source code the sanitizer stopped the program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SanitizerLocation {
+    pub file: String,
+    /// Line, starting at 1
+    pub line: u32,
+    /// Column, starting at 1, if the sanitizer reported it
+    pub column: Option<u32>,
+}
+
+impl fmt::Display for SanitizerLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ":{column}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The error a sanitizer found, which stopped the program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SanitizerReport {
+    pub kind: SanitizerKind,
+    /// What went wrong, e.g. `heap-buffer-overflow`
+    pub error: String,
+    /// Where it went wrong, if the program was compiled with debug information
+    pub location: Option<SanitizerLocation>,
+    /// The whole report, including the stack trace
+    pub report: String,
+}
+
+impl fmt::Display for SanitizerReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind, self.error)?;
+        if let Some(location) = &self.location {
+            write!(f, " at {location}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the first sanitizer report out of the stderr of a program, `None` if there is none
+pub fn parse_sanitizer_report(stderr: &str) -> Option<SanitizerReport> {
+    let lines: Vec<&str> = stderr.lines().collect();
+
+    for (index, line) in lines.iter().enumerate() {
+        if let Some(captures) = SANITIZER_ERROR.captures(line) {
+            let kind = match &captures["kind"] {
+                "LeakSanitizer" => SanitizerKind::Leak,
+                _ => SanitizerKind::Address,
+            };
+            let location = lines[index + 1..]
+                .iter()
+                .find_map(|line| SANITIZER_SUMMARY.captures(line))
+                .map(|summary| SanitizerLocation {
+                    file: summary["file"].to_string(),
+                    line: summary["line"].parse().unwrap_or_default(),
+                    column: summary
+                        .name("column")
+                        .and_then(|column| column.as_str().parse().ok()),
+                });
+            return Some(SanitizerReport {
+                kind,
+                error: captures["error"].trim().to_string(),
+                location,
+                report: lines[index..].join("\n"),
+            });
+        }
+
+        if let Some(captures) = UNDEFINED_BEHAVIOR.captures(line) {
+            return Some(SanitizerReport {
+                kind: SanitizerKind::UndefinedBehavior,
+                error: captures["message"].trim().to_string(),
+                location: Some(SanitizerLocation {
+                    file: captures["file"].to_string(),
+                    line: captures["line"].parse().unwrap_or_default(),
+                    column: captures["column"].parse().ok(),
+                }),
+                report: lines[index..].join("\n"),
+            });
+        }
+
+        if let Some(captures) = PANIC.captures(line) {
+            return Some(SanitizerReport {
+                kind: SanitizerKind::Panic,
+                // The message may span several lines (e.g. of `assert_eq!`), the first one says
+                // what went wrong
+                error: lines
+                    .get(index + 1)
+                    .map_or_else(String::new, |message| message.trim().to_string()),
+                location: Some(SanitizerLocation {
+                    file: captures["file"].to_string(),
+                    line: captures["line"].parse().unwrap_or_default(),
+                    column: captures["column"].parse().ok(),
+                }),
+                report: lines[index..].join("\n"),
+            });
+        }
+
+        if let Some(captures) = LEGACY_PANIC.captures(line) {
+            return Some(SanitizerReport {
+                kind: SanitizerKind::Panic,
+                error: captures["message"].trim().to_string(),
+                location: Some(SanitizerLocation {
+                    file: captures["file"].to_string(),
+                    line: captures["line"].parse().unwrap_or_default(),
+                    column: captures["column"].parse().ok(),
+                }),
+                report: lines[index..].join("\n"),
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_address_sanitizer_report() {
+        let stderr = "\
+=================================================================
+==25379==ERROR: AddressSanitizer: heap-buffer-overflow on address 0x602000000024 at pc 0x55f24513992b bp 0x7fff289e85c0 sp 0x7fff289e85b8
+READ of size 4 at 0x602000000024 thread T0
+    #0 0x55f24513992a in main /tmp/main.cpp:3
+
+SUMMARY: AddressSanitizer: heap-buffer-overflow /tmp/main.cpp:3 in main
+==25379==ABORTING
+";
+        let report = parse_sanitizer_report(stderr).unwrap();
+
+        assert_eq!(report.kind, SanitizerKind::Address);
+        assert_eq!(report.error, "heap-buffer-overflow");
+        assert_eq!(
+            report.location,
+            Some(SanitizerLocation {
+                file: String::from("/tmp/main.cpp"),
+                line: 3,
+                column: None,
+            })
+        );
+        assert!(report.report.ends_with("==25379==ABORTING"));
+        assert_eq!(
+            report.to_string(),
+            "AddressSanitizer: heap-buffer-overflow at /tmp/main.cpp:3"
+        );
+    }
+
+    #[test]
+    fn parses_undefined_behavior_sanitizer_report() {
+        let stderr = "\
+main.cpp:3:98: runtime error: signed integer overflow: 1 + 2147483647 cannot be represented in type 'int'
+";
+        let report = parse_sanitizer_report(stderr).unwrap();
+
+        assert_eq!(report.kind, SanitizerKind::UndefinedBehavior);
+        assert_eq!(
+            report.error,
+            "signed integer overflow: 1 + 2147483647 cannot be represented in type 'int'"
+        );
+        assert_eq!(report.location.unwrap().to_string(), "main.cpp:3:98");
+    }
+
+    #[test]
+    fn parses_rust_panic() {
+        let stderr = "\
+thread 'main' panicked at src/main.rs:6:20:
+attempt to multiply with overflow
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+";
+        let report = parse_sanitizer_report(stderr).unwrap();
+
+        assert_eq!(report.kind, SanitizerKind::Panic);
+        assert_eq!(report.error, "attempt to multiply with overflow");
+        assert_eq!(
+            report.to_string(),
+            "Rust panic: attempt to multiply with overflow at src/main.rs:6:20"
+        );
+    }
+
+    #[test]
+    fn parses_rust_panic_with_thread_id() {
+        let stderr = "\
+thread 'main' (10657) panicked at /tmp/source.rs:6:20:
+attempt to multiply with overflow
+";
+        let report = parse_sanitizer_report(stderr).unwrap();
+
+        assert_eq!(report.kind, SanitizerKind::Panic);
+        assert_eq!(report.location.unwrap().to_string(), "/tmp/source.rs:6:20");
+    }
+
+    #[test]
+    fn parses_legacy_rust_panic() {
+        let stderr = "thread 'main' panicked at 'index out of bounds: the len is 3 but the index is 3', main.rs:4:5";
+        let report = parse_sanitizer_report(stderr).unwrap();
+
+        assert_eq!(report.kind, SanitizerKind::Panic);
+        assert_eq!(
+            report.error,
+            "index out of bounds: the len is 3 but the index is 3"
+        );
+        assert_eq!(report.location.unwrap().to_string(), "main.rs:4:5");
+    }
+
+    #[test]
+    fn ignores_other_output() {
+        assert_eq!(
+            parse_sanitizer_report("Traceback (most recent call last):\nZeroDivisionError"),
+            None
+        );
+        assert_eq!(parse_sanitizer_report("thread 'main' panicked"), None);
+    }
+}
@@ -0,0 +1,479 @@
+//! Describes how each language is compiled and run, instead of hard-coding the command lines.
+//!
+//! The built-in toolchains are defined in `toolchains.toml`, shipped with the crate. A toolchain
+//! configuration file uses the same format, and overrides only the fields it sets, so matching the
+//! exact flags of a contest judge takes a few lines:
+//!
+//! ```toml
+//! [cpp]
+//! compile = [{ program = "g++", args = ["-std=c++20", "-O2", "-DONLINE_JUDGE", "-o", "{output}", "{source}"] }]
+//! ```
+//!
+//! # Types
+//!
+//! - [`ToolchainConfig`]: Toolchains of every supported language
+//! - [`LanguageToolchain`]: Extensions, compilers and run commands of a language
+//! - [`CommandTemplate`]: Command line, with placeholders filled in for each program
+//!
+//! # Placeholders
+//!
+//! - `{source}`: Path of the source file
+//! - `{output}`: Path of the compiled program
+//! - `{output_dir}`: Directory containing the compiled program
+//! - `{name}`: Source file name without its extension, e.g. the class name in Java
+//! - `{project}`: Directory of a project, the directory of the source file otherwise
+//! - `{sources}`: As a whole argument, every source file of a project, as separate arguments
+//! - `{worker}`: Path of the worker harness, in `worker` commands
+//!
+//! # Projects
+//!
+//! Solutions which are directories (see the `project` module) are compiled with the
+//! `project_compile` commands and run with the `project_run` commands, or the `run` commands if
+//! there are none. `{name}` is then the entry point of the project, e.g. the main class in Java.
+//!
+//! # Build Profiles
+//!
+//! With [`ToolchainConfig::with_profile`] and [`BuildProfile::Debug`], languages with
+//! `debug_compile` commands are compiled with them instead, e.g. with sanitizers for C and C++, and
+//! with overflow checks for Rust.
+//!
+//! # Workers
+//!
+//! With [`ToolchainConfig::with_workers`], Python, Java and JavaScript programs are run by warm
+//! workers started with the `worker` commands, each one running the program once per input without
+//! paying the interpreter or JVM startup again (see the `worker_pool` module).
+//!
+//! # Caching
+//!
+//! With [`ToolchainConfig::with_compile_cache`], programs are compiled once into a
+//! [`CompileCache`] shared between runs, instead of into a fresh temporary directory each time.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use serde::{Deserialize, Serialize};
+
+use super::compile_cache::CompileCache;
+use super::language_name::{CompilationType, LanguageName, get_language_compilation_type};
+use super::runner_error_types::RunnerErrorType;
+
+const BUILTIN_TOOLCHAINS: &str = include_str!("toolchains.toml");
+
+static BUILTIN: LazyLock<ToolchainConfig> = LazyLock::new(|| {
+    ToolchainConfig::parse(BUILTIN_TOOLCHAINS, HashMap::new())
+        .expect("built-in toolchains.toml is valid")
+});
+
+/// A command line, whose program and arguments may contain placeholders
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandTemplate {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// Values of the placeholders for one program
+pub(crate) struct TemplateValues<'a> {
+    pub(crate) source: &'a str,
+    pub(crate) output: &'a str,
+    pub(crate) output_dir: &'a str,
+    pub(crate) name: &'a str,
+    pub(crate) project: &'a str,
+    pub(crate) sources: &'a [&'a str],
+    pub(crate) worker: &'a str,
+}
+
+impl CommandTemplate {
+    /// Fills in the placeholders, returning the program and its arguments
+    pub(crate) fn expand(&self, values: &TemplateValues) -> (String, Vec<String>) {
+        let expand = |text: &str| {
+            text.replace("{sources}", &values.sources.join(" "))
+                .replace("{source}", values.source)
+                .replace("{output_dir}", values.output_dir)
+                .replace("{output}", values.output)
+                .replace("{name}", values.name)
+                .replace("{project}", values.project)
+                .replace("{worker}", values.worker)
+        };
+        let args = self
+            .args
+            .iter()
+            .flat_map(|arg| match arg.as_str() {
+                "{sources}" => values
+                    .sources
+                    .iter()
+                    .map(|source| source.to_string())
+                    .collect(),
+                _ => vec![expand(arg)],
+            })
+            .collect();
+        (expand(&self.program), args)
+    }
+}
+
+/// Which compile commands programs are compiled with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BuildProfile {
+    /// The `compile` commands, matching how judges compile
+    #[default]
+    Release,
+    /// The `debug_compile` commands, catching memory errors and undefined behavior, falling back
+    /// to the `compile` commands for languages without any
+    Debug,
+}
+
+/// How a language is compiled and run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguageToolchain {
+    /// Source file extensions, the first one is used for programs created from text
+    pub extensions: Vec<String>,
+    /// Extension of the compiled program, for compilers requiring one
+    #[serde(default)]
+    pub output_extension: Option<String>,
+    /// Compilers, tried in order until one succeeds (empty for interpreted languages)
+    #[serde(default)]
+    pub compile: Vec<CommandTemplate>,
+    /// Compilers of the debug build profile, tried in order until one succeeds
+    #[serde(default)]
+    pub debug_compile: Vec<CommandTemplate>,
+    /// Compilers of projects, tried in order until one succeeds
+    #[serde(default)]
+    pub project_compile: Vec<CommandTemplate>,
+    /// Commands running projects, tried in order until one can be launched (`run` if empty)
+    #[serde(default)]
+    pub project_run: Vec<CommandTemplate>,
+    /// Commands running the program, tried in order until one can be launched
+    pub run: Vec<CommandTemplate>,
+    /// Commands starting a worker running the program once per input, the first one found is used
+    #[serde(default)]
+    pub worker: Vec<CommandTemplate>,
+}
+
+/// A language table of a configuration file, where every field is optional
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LanguageToolchainOverride {
+    extensions: Option<Vec<String>>,
+    output_extension: Option<String>,
+    compile: Option<Vec<CommandTemplate>>,
+    debug_compile: Option<Vec<CommandTemplate>>,
+    project_compile: Option<Vec<CommandTemplate>>,
+    project_run: Option<Vec<CommandTemplate>>,
+    run: Option<Vec<CommandTemplate>>,
+    worker: Option<Vec<CommandTemplate>>,
+}
+
+/// Toolchains of every supported language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolchainConfig {
+    languages: HashMap<LanguageName, LanguageToolchain>,
+    compile_cache: Option<CompileCache>,
+    profile: BuildProfile,
+    workers: usize,
+}
+
+impl Default for ToolchainConfig {
+    fn default() -> Self {
+        Self::builtin().clone()
+    }
+}
+
+impl ToolchainConfig {
+    /// The built-in toolchains
+    pub fn builtin() -> &'static ToolchainConfig {
+        &BUILTIN
+    }
+
+    /// Parses a toolchain configuration, on top of the built-in toolchains.
+    ///
+    /// Tables are named after the language (`cpp`, `python`, ...) and only override the fields
+    /// they set.
+    pub fn from_toml_str(config: &str) -> Result<Self, Box<RunnerErrorType>> {
+        Self::parse(config, Self::builtin().languages.clone())
+    }
+
+    /// Reads a toolchain configuration file, see `from_toml_str`
+    pub fn from_file(path: &Path) -> Result<Self, Box<RunnerErrorType>> {
+        let config = std::fs::read_to_string(path).map_err(|err| {
+            Box::new(RunnerErrorType::InvalidToolchainConfig(format!(
+                "Failed to read {}: {err}",
+                path.display()
+            )))
+        })?;
+        Self::from_toml_str(&config)
+    }
+
+    fn parse(
+        config: &str,
+        mut languages: HashMap<LanguageName, LanguageToolchain>,
+    ) -> Result<Self, Box<RunnerErrorType>> {
+        let overrides: HashMap<String, LanguageToolchainOverride> = toml::from_str(config)
+            .map_err(|err| Box::new(RunnerErrorType::InvalidToolchainConfig(err.to_string())))?;
+
+        for (name, toolchain_override) in overrides {
+            let lang = LanguageName::try_from(name)
+                .map_err(|err| Box::new(RunnerErrorType::InvalidToolchainConfig(err)))?;
+
+            let toolchain = match languages.remove(&lang) {
+                Some(mut toolchain) => {
+                    let LanguageToolchainOverride {
+                        extensions,
+                        output_extension,
+                        compile,
+                        debug_compile,
+                        project_compile,
+                        project_run,
+                        run,
+                        worker,
+                    } = toolchain_override;
+                    toolchain.extensions = extensions.unwrap_or(toolchain.extensions);
+                    toolchain.output_extension = output_extension.or(toolchain.output_extension);
+                    toolchain.compile = compile.unwrap_or(toolchain.compile);
+                    toolchain.debug_compile = debug_compile.unwrap_or(toolchain.debug_compile);
+                    toolchain.project_compile =
+                        project_compile.unwrap_or(toolchain.project_compile);
+                    toolchain.project_run = project_run.unwrap_or(toolchain.project_run);
+                    toolchain.run = run.unwrap_or(toolchain.run);
+                    toolchain.worker = worker.unwrap_or(toolchain.worker);
+                    toolchain
+                }
+                None => LanguageToolchain {
+                    extensions: toolchain_override.extensions.unwrap_or_default(),
+                    output_extension: toolchain_override.output_extension,
+                    compile: toolchain_override.compile.unwrap_or_default(),
+                    debug_compile: toolchain_override.debug_compile.unwrap_or_default(),
+                    project_compile: toolchain_override.project_compile.unwrap_or_default(),
+                    project_run: toolchain_override.project_run.unwrap_or_default(),
+                    run: toolchain_override.run.unwrap_or_default(),
+                    worker: toolchain_override.worker.unwrap_or_default(),
+                },
+            };
+            languages.insert(lang, toolchain);
+        }
+
+        let config = ToolchainConfig {
+            languages,
+            compile_cache: None,
+            profile: BuildProfile::Release,
+            workers: 0,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), Box<RunnerErrorType>> {
+        let invalid =
+            |reason: String| Err(Box::new(RunnerErrorType::InvalidToolchainConfig(reason)));
+        let mut extension_languages = HashMap::new();
+
+        for (lang, toolchain) in &self.languages {
+            if toolchain.extensions.is_empty() {
+                return invalid(format!("No extensions for {lang}"));
+            }
+            if toolchain.run.is_empty() {
+                return invalid(format!("No run commands for {lang}"));
+            }
+            if get_language_compilation_type(lang) != CompilationType::Interpreted
+                && toolchain.compile.is_empty()
+            {
+                return invalid(format!("No compile commands for {lang}"));
+            }
+            for extension in &toolchain.extensions {
+                if let Some(other) = extension_languages.insert(extension, lang) {
+                    return invalid(format!(
+                        "Extension `{extension}` is used by both {other} and {lang}"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Compile programs into the cache, reusing programs compiled before with the same code and
+    /// toolchain. Programs with a custom destination path are still compiled there.
+    pub fn with_compile_cache(mut self, compile_cache: CompileCache) -> Self {
+        self.compile_cache = Some(compile_cache);
+        self
+    }
+
+    /// The compilation cache, if any
+    pub fn compile_cache(&self) -> Option<&CompileCache> {
+        self.compile_cache.as_ref()
+    }
+
+    /// Compile programs with the commands of the build profile
+    pub fn with_profile(mut self, profile: BuildProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// The build profile
+    pub fn profile(&self) -> BuildProfile {
+        self.profile
+    }
+
+    /// Run programs in warm workers, keeping up to `workers` of them per program, for languages
+    /// with `worker` commands. 0 runs every program in a fresh process, the default.
+    ///
+    /// Workers only enforce the wall clock time limit, so runs with other limits still get a fresh
+    /// process.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    /// Number of workers kept per program
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Toolchain of the language, compiling with the commands of the build profile
+    pub fn resolve(&self, lang: &LanguageName) -> Result<LanguageToolchain, Box<RunnerErrorType>> {
+        let mut toolchain = self.get(lang)?.clone();
+        if self.profile == BuildProfile::Debug && !toolchain.debug_compile.is_empty() {
+            toolchain.compile = toolchain.debug_compile.clone();
+        }
+        Ok(toolchain)
+    }
+
+    /// Toolchain of the language, as configured
+    pub fn get(&self, lang: &LanguageName) -> Result<&LanguageToolchain, Box<RunnerErrorType>> {
+        self.languages.get(lang).ok_or_else(|| {
+            Box::new(RunnerErrorType::InvalidToolchainConfig(format!(
+                "No toolchain for {lang}"
+            )))
+        })
+    }
+
+    /// Language of the source file, from its extension
+    pub fn language_of(&self, file_path: &Path) -> Result<LanguageName, Box<RunnerErrorType>> {
+        let extension = file_path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .ok_or_else(|| {
+                Box::new(RunnerErrorType::InvalidFileExtension(
+                    file_path.to_path_buf(),
+                ))
+            })?;
+
+        self.languages
+            .iter()
+            .find(|(_, toolchain)| toolchain.extensions.iter().any(|ext| ext == extension))
+            .map(|(lang, _)| lang.clone())
+            .ok_or_else(|| {
+                Box::new(RunnerErrorType::UnsupportedLanguage(
+                    file_path.to_path_buf(),
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_toolchains_cover_every_language() {
+        let config = ToolchainConfig::builtin();
+
+        assert_eq!(config.languages.len(), 12);
+        assert_eq!(
+            config.language_of(Path::new("main.cc")).unwrap(),
+            LanguageName::Cpp
+        );
+        assert_eq!(
+            config.get(&LanguageName::Kotlin).unwrap().output_extension,
+            Some(String::from("jar"))
+        );
+    }
+
+    #[test]
+    fn config_overrides_only_the_fields_it_sets() {
+        let config = ToolchainConfig::from_toml_str(
+            r#"
+            [CPP]
+            compile = [{ program = "g++", args = ["-std=c++20", "-o", "{output}", "{source}"] }]
+            "#,
+        )
+        .unwrap();
+        let cpp = config.get(&LanguageName::Cpp).unwrap();
+
+        assert_eq!(cpp.compile.len(), 1);
+        assert_eq!(cpp.compile[0].args[0], "-std=c++20");
+        assert_eq!(
+            cpp.run,
+            ToolchainConfig::builtin()
+                .get(&LanguageName::Cpp)
+                .unwrap()
+                .run
+        );
+        assert_eq!(
+            config.get(&LanguageName::Python).unwrap(),
+            ToolchainConfig::builtin()
+                .get(&LanguageName::Python)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn config_rejects_invalid_toolchains() {
+        assert!(ToolchainConfig::from_toml_str("[cobol]\nrun = []").is_err());
+        assert!(ToolchainConfig::from_toml_str("[rust]\nrun = []").is_err());
+        assert!(ToolchainConfig::from_toml_str("[rust]\ncompile = []").is_err());
+        assert!(ToolchainConfig::from_toml_str("[ruby]\nextensions = [\"py\"]").is_err());
+        assert!(ToolchainConfig::from_toml_str("[ruby]\nflags = []").is_err());
+    }
+
+    #[test]
+    fn debug_profile_swaps_in_debug_compilers() {
+        let config = ToolchainConfig::default().with_profile(BuildProfile::Debug);
+
+        let cpp = config.resolve(&LanguageName::Cpp).unwrap();
+        assert!(
+            cpp.compile[0]
+                .args
+                .iter()
+                .any(|arg| arg.starts_with("-fsanitize="))
+        );
+        assert_eq!(
+            config.resolve(&LanguageName::Java).unwrap().compile,
+            config.get(&LanguageName::Java).unwrap().compile
+        );
+        assert_eq!(
+            ToolchainConfig::default()
+                .resolve(&LanguageName::Cpp)
+                .unwrap()
+                .compile,
+            config.get(&LanguageName::Cpp).unwrap().compile
+        );
+    }
+
+    #[test]
+    fn template_fills_in_placeholders() {
+        let template = CommandTemplate {
+            program: String::from("mcs"),
+            args: vec![String::from("-out:{output}"), String::from("{source}")],
+        };
+        let values = TemplateValues {
+            source: "/src/main.cs",
+            output: "/out/main.exe",
+            output_dir: "/out",
+            name: "main",
+            project: "/src",
+            sources: &["/src/main.cs"],
+            worker: "",
+        };
+
+        assert_eq!(
+            template.expand(&values),
+            (
+                String::from("mcs"),
+                vec![
+                    String::from("-out:/out/main.exe"),
+                    String::from("/src/main.cs")
+                ]
+            )
+        );
+    }
+}
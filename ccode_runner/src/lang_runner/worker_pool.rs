@@ -0,0 +1,378 @@
+//! Keeps interpreters and JVMs running between runs of a program, as their startup otherwise
+//! dominates each run of a short program.
+//!
+//! A worker is a harness (in `workers/`) started once with the program, which then runs the
+//! program on each input it is sent: in a fresh `__main__` module (importing its modules afresh)
+//! for Python, a fresh class loader for Java, and a fresh worker thread for JavaScript. Requests are sent to its stdin as
+//! `<input length>\n<input>`, and it answers on its stdout with
+//! `<exit code> <stdout length> <stderr length>\n<stdout><stderr>`.
+//!
+//! Workers share one process between runs, so they only enforce the wall clock time limit, killing
+//! the worker once it is exceeded. Programs needing a process of their own, because of other limits
+//! or because they end the process themselves (e.g. with `System.exit`, which takes the worker
+//! down), are run in fresh processes instead.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tempfile::TempDir;
+
+use super::language_name::LanguageName;
+use crate::utils::program_utils::{
+    ExecutionLimits, ExecutionResult, ExecutionStatus, ResourceBackend, program_exists,
+};
+
+/// How often a run waiting for a worker, or for its answer, checks whether it was cancelled
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Harness of the language, as its file name and its source
+pub(crate) fn harness(lang: &LanguageName) -> Option<(&'static str, &'static str)> {
+    match lang {
+        LanguageName::Python => Some(("worker.py", include_str!("workers/worker.py"))),
+        LanguageName::Java => Some(("Worker.java", include_str!("workers/Worker.java"))),
+        LanguageName::Javascript => Some(("worker.js", include_str!("workers/worker.js"))),
+        _ => None,
+    }
+}
+
+/// Writes the harness of the language to a new temporary directory
+pub(crate) fn write_harness(lang: &LanguageName) -> io::Result<Option<(TempDir, String)>> {
+    let Some((file_name, source)) = harness(lang) else {
+        return Ok(None);
+    };
+    let dir = tempfile::Builder::new().prefix("cpast_worker_").tempdir()?;
+    let path = dir.path().join(file_name);
+    std::fs::write(&path, source)?;
+    let path = path
+        .to_str()
+        .ok_or_else(|| io::Error::other("Invalid temporary directory"))?
+        .to_string();
+    Ok(Some((dir, path)))
+}
+
+/// Whether runs with these limits can share a worker, which only enforces the wall clock time
+/// limit
+pub(crate) fn supports_limits(limits: &ExecutionLimits) -> bool {
+    limits.cpu_time_limit_ms.is_none()
+        && limits.memory_limit_bytes.is_none()
+        && limits.output_limit_bytes.is_none()
+        && limits.process_limit.is_none()
+        && limits.sandbox.is_none()
+}
+
+/// Pre-started workers of one program
+#[derive(Debug)]
+pub(crate) struct WorkerPool {
+    program: String,
+    args: Vec<String>,
+    /// Most workers running at once, further runs wait for one of them
+    size: usize,
+    workers: Mutex<Workers>,
+    /// Notified when a worker is back, or no longer running
+    available: Condvar,
+    /// Set once a worker died during a run, e.g. as the program ended the process itself
+    disabled: AtomicBool,
+    /// Holds the harness, removed along with the pool
+    _harness_dir: TempDir,
+}
+
+#[derive(Debug)]
+struct Workers {
+    idle: Vec<Worker>,
+    /// Idle workers, and workers in use
+    running: usize,
+}
+
+impl WorkerPool {
+    /// Starts `size` workers running the command
+    pub(crate) fn start(
+        program: String,
+        args: Vec<String>,
+        size: usize,
+        harness_dir: TempDir,
+    ) -> io::Result<Self> {
+        program_exists(&program).map_err(io::Error::other)?;
+        let idle = (0..size)
+            .map(|_| Worker::spawn(&program, &args))
+            .collect::<io::Result<_>>()?;
+
+        Ok(WorkerPool {
+            program,
+            args,
+            size,
+            workers: Mutex::new(Workers {
+                idle,
+                running: size,
+            }),
+            available: Condvar::new(),
+            disabled: AtomicBool::new(false),
+            _harness_dir: harness_dir,
+        })
+    }
+
+    /// Runs the program on the input in a worker, `None` if it must be run in a fresh process
+    /// instead, as the worker died during the run or the pool is disabled.
+    ///
+    /// Once `cancelled` is set, the worker is killed.
+    pub(crate) fn run(
+        &self,
+        stdin_content: &str,
+        time_limit: Option<Duration>,
+        cancelled: Option<&AtomicBool>,
+    ) -> Option<ExecutionResult> {
+        let mut worker = self.checkout(cancelled)?;
+
+        match worker.run(stdin_content, time_limit, cancelled) {
+            RunOutcome::Completed(result) => {
+                self.workers.lock().unwrap().idle.push(worker);
+                self.available.notify_one();
+                Some(result)
+            }
+            // The worker is killed once dropped, and replaced by the next run needing one
+            RunOutcome::TimedOut(result) => {
+                self.release();
+                Some(result)
+            }
+            RunOutcome::Cancelled => {
+                self.release();
+                None
+            }
+            RunOutcome::Died => {
+                self.disabled.store(true, Ordering::Relaxed);
+                self.release();
+                None
+            }
+        }
+    }
+
+    /// Takes an idle worker, or starts one if fewer than `size` are running, waiting for one to be
+    /// back otherwise
+    fn checkout(&self, cancelled: Option<&AtomicBool>) -> Option<Worker> {
+        let mut workers = self.workers.lock().unwrap();
+        loop {
+            if self.disabled.load(Ordering::Relaxed)
+                || cancelled.is_some_and(|cancelled| cancelled.load(Ordering::Relaxed))
+            {
+                return None;
+            }
+            if let Some(worker) = workers.idle.pop() {
+                return Some(worker);
+            }
+            if workers.running < self.size {
+                workers.running += 1;
+                drop(workers);
+                let worker = Worker::spawn(&self.program, &self.args);
+                if worker.is_err() {
+                    self.release();
+                }
+                return worker.ok();
+            }
+            workers = self
+                .available
+                .wait_timeout(workers, CANCEL_POLL_INTERVAL)
+                .unwrap()
+                .0;
+        }
+    }
+
+    /// Marks a worker taken from the pool as no longer running
+    fn release(&self) {
+        self.workers.lock().unwrap().running -= 1;
+        self.available.notify_all();
+    }
+
+    /// Same as `run`, without blocking the async runtime. Dropping the future kills the worker.
+    pub(crate) async fn run_async(
+        self: Arc<Self>,
+        stdin_content: String,
+        time_limit: Option<Duration>,
+    ) -> Option<ExecutionResult> {
+        /// Cancels the run once dropped, which is a no-op if the run is over
+        struct CancelOnDrop(Arc<AtomicBool>);
+
+        impl Drop for CancelOnDrop {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::Relaxed);
+            }
+        }
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let cancel_on_drop = CancelOnDrop(cancelled.clone());
+
+        let result = tokio::task::spawn_blocking(move || {
+            self.run(&stdin_content, time_limit, Some(&cancelled))
+        })
+        .await
+        .ok()
+        .flatten();
+        drop(cancel_on_drop);
+        result
+    }
+}
+
+/// Answer of a worker to a request
+struct Response {
+    exit_code: i32,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
+
+enum RunOutcome {
+    Completed(ExecutionResult),
+    TimedOut(ExecutionResult),
+    Cancelled,
+    Died,
+}
+
+/// A running harness, answering requests one at a time
+#[derive(Debug)]
+struct Worker {
+    child: Child,
+    stdin: ChildStdin,
+    /// Responses read from its stdout, disconnected once it exits
+    responses: mpsc::Receiver<Response>,
+}
+
+impl Worker {
+    fn spawn(program: &str, args: &[String]) -> io::Result<Self> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| io::Error::other("No stdin"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::other("No stdout"))?;
+
+        let (sender, responses) = mpsc::channel();
+        thread::spawn(move || {
+            let mut stdout = BufReader::new(stdout);
+            while let Ok(response) = read_response(&mut stdout) {
+                if sender.send(response).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Worker {
+            child,
+            stdin,
+            responses,
+        })
+    }
+
+    fn run(
+        &mut self,
+        stdin_content: &str,
+        time_limit: Option<Duration>,
+        cancelled: Option<&AtomicBool>,
+    ) -> RunOutcome {
+        let start_time = Instant::now();
+        let request = [
+            format!("{}\n", stdin_content.len()).as_bytes(),
+            stdin_content.as_bytes(),
+        ]
+        .concat();
+        if self
+            .stdin
+            .write_all(&request)
+            .and_then(|_| self.stdin.flush())
+            .is_err()
+        {
+            return RunOutcome::Died;
+        }
+
+        loop {
+            let wait = match time_limit {
+                Some(time_limit) => time_limit
+                    .saturating_sub(start_time.elapsed())
+                    .min(CANCEL_POLL_INTERVAL),
+                None => CANCEL_POLL_INTERVAL,
+            };
+            match self.responses.recv_timeout(wait) {
+                Ok(response) => {
+                    let status = match response.exit_code {
+                        0 => ExecutionStatus::Success,
+                        code => ExecutionStatus::NonZeroExit(code),
+                    };
+                    return RunOutcome::Completed(ExecutionResult {
+                        exit_code: Some(response.exit_code),
+                        stdout: response.stdout,
+                        stderr: response.stderr,
+                        status,
+                        ..worker_result(start_time)
+                    });
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => return RunOutcome::Died,
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+            }
+
+            if cancelled.is_some_and(|cancelled| cancelled.load(Ordering::Relaxed)) {
+                return RunOutcome::Cancelled;
+            }
+            if time_limit.is_some_and(|time_limit| start_time.elapsed() >= time_limit) {
+                return RunOutcome::TimedOut(ExecutionResult {
+                    status: ExecutionStatus::TimeLimitExceeded,
+                    ..worker_result(start_time)
+                });
+            }
+        }
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Result of a run in a worker, which only measures the wall clock time
+fn worker_result(start_time: Instant) -> ExecutionResult {
+    ExecutionResult {
+        exit_code: None,
+        signal: None,
+        stdout: Vec::new(),
+        stderr: Vec::new(),
+        wall_time: start_time.elapsed(),
+        cpu_time: None,
+        peak_memory_bytes: None,
+        peak_processes: None,
+        status: ExecutionStatus::Success,
+        resource_backend: ResourceBackend::Worker,
+    }
+}
+
+/// Reads a `<exit code> <stdout length> <stderr length>\n<stdout><stderr>` response
+fn read_response(reader: &mut impl BufRead) -> io::Result<Response> {
+    let mut header = String::new();
+    if reader.read_line(&mut header)? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "Invalid worker response");
+    let mut fields = header.split_whitespace();
+    let mut next_field = || fields.next().ok_or_else(invalid);
+    let exit_code = next_field()?.parse().map_err(|_| invalid())?;
+    let stdout_len = next_field()?.parse().map_err(|_| invalid())?;
+    let stderr_len = next_field()?.parse().map_err(|_| invalid())?;
+
+    let mut stdout = vec![0; stdout_len];
+    reader.read_exact(&mut stdout)?;
+    let mut stderr = vec![0; stderr_len];
+    reader.read_exact(&mut stderr)?;
+    Ok(Response {
+        exit_code,
+        stdout,
+        stderr,
+    })
+}
@@ -0,0 +1,87 @@
+import java.io.ByteArrayInputStream;
+import java.io.ByteArrayOutputStream;
+import java.io.DataInputStream;
+import java.io.File;
+import java.io.FileDescriptor;
+import java.io.FileInputStream;
+import java.io.FileOutputStream;
+import java.io.IOException;
+import java.io.InputStream;
+import java.io.OutputStream;
+import java.io.PrintStream;
+import java.lang.reflect.InvocationTargetException;
+import java.lang.reflect.Method;
+import java.net.URL;
+import java.net.URLClassLoader;
+import java.nio.charset.StandardCharsets;
+
+/**
+ * Runs a Java program once per input, in a fresh class loader, keeping the JVM warm.
+ *
+ * <p>Usage: java Worker.java &lt;class path&gt; &lt;main class&gt;
+ *
+ * <p>Requests are read from stdin as {@code <input length>\n<input>}, and answered on stdout with
+ * {@code <exit code> <stdout length> <stderr length>\n<stdout><stderr>}, lengths being in bytes.
+ */
+public class Worker {
+    public static void main(String[] args) throws IOException {
+        URL[] classPath = {new File(args[0]).toURI().toURL()};
+        String mainClass = args[1];
+        DataInputStream requests = new DataInputStream(new FileInputStream(FileDescriptor.in));
+        OutputStream responses = new FileOutputStream(FileDescriptor.out);
+        InputStream savedIn = System.in;
+        PrintStream savedOut = System.out;
+        PrintStream savedErr = System.err;
+
+        String header;
+        while ((header = readLine(requests)) != null) {
+            byte[] input = new byte[Integer.parseInt(header.trim())];
+            requests.readFully(input);
+
+            ByteArrayOutputStream stdout = new ByteArrayOutputStream();
+            ByteArrayOutputStream stderr = new ByteArrayOutputStream();
+            System.setIn(new ByteArrayInputStream(input));
+            System.setOut(new PrintStream(stdout, false, StandardCharsets.UTF_8));
+            System.setErr(new PrintStream(stderr, true, StandardCharsets.UTF_8));
+
+            int code = 0;
+            // Not delegating to the application class loader, so that static state starts afresh
+            try (URLClassLoader loader =
+                    new URLClassLoader(classPath, ClassLoader.getPlatformClassLoader())) {
+                Method main = Class.forName(mainClass, true, loader).getMethod("main", String[].class);
+                main.invoke(null, (Object) new String[0]);
+            } catch (InvocationTargetException e) {
+                e.getCause().printStackTrace();
+                code = 1;
+            } catch (Throwable e) {
+                e.printStackTrace();
+                code = 1;
+            }
+
+            System.out.flush();
+            System.err.flush();
+            System.setIn(savedIn);
+            System.setOut(savedOut);
+            System.setErr(savedErr);
+
+            String response = code + " " + stdout.size() + " " + stderr.size() + "\n";
+            responses.write(response.getBytes(StandardCharsets.UTF_8));
+            stdout.writeTo(responses);
+            stderr.writeTo(responses);
+            responses.flush();
+        }
+    }
+
+    /** Line read from the stream, null at its end */
+    private static String readLine(InputStream stream) throws IOException {
+        StringBuilder line = new StringBuilder();
+        int c;
+        while ((c = stream.read()) != '\n') {
+            if (c == -1) {
+                return null;
+            }
+            line.append((char) c);
+        }
+        return line.toString();
+    }
+}
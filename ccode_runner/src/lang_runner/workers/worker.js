@@ -0,0 +1,99 @@
+'use strict';
+// Runs a JavaScript program once per input, in a fresh worker thread, keeping Node warm.
+//
+// Usage: node worker.js <program>
+//
+// Requests are read from stdin as `<input length>\n<input>`, and answered on stdout with
+// `<exit code> <stdout length> <stderr length>\n<stdout><stderr>`, lengths being in bytes.
+
+const fs = require('node:fs');
+const path = require('node:path');
+const { PassThrough } = require('node:stream');
+const { Worker, isMainThread, workerData } = require('node:worker_threads');
+
+/** Collects the chunks of a stream, resolving once it ends */
+function collect(stream) {
+  const chunks = [];
+  stream.on('data', (chunk) => chunks.push(chunk));
+  return new Promise((resolve) => stream.on('end', () => resolve(Buffer.concat(chunks))));
+}
+
+async function run(program, input) {
+  const worker = new Worker(__filename, {
+    workerData: { program, input },
+    argv: [program],
+    stdout: true,
+    stderr: true,
+  });
+
+  const stdout = collect(worker.stdout);
+  const stderr = collect(worker.stderr);
+  const errors = [];
+  worker.on('error', (err) => errors.push(Buffer.from(`${err && err.stack ? err.stack : err}\n`)));
+  const code = await new Promise((resolve) => worker.on('exit', resolve));
+
+  return {
+    code: errors.length > 0 && code === 0 ? 1 : code,
+    stdout: await stdout,
+    stderr: Buffer.concat([await stderr, ...errors]),
+  };
+}
+
+async function serve(program) {
+  let pending = Buffer.alloc(0);
+  for await (const chunk of process.stdin) {
+    pending = Buffer.concat([pending, chunk]);
+    for (;;) {
+      const newline = pending.indexOf('\n');
+      if (newline === -1) {
+        break;
+      }
+      const length = Number(pending.subarray(0, newline).toString());
+      if (pending.length < newline + 1 + length) {
+        break;
+      }
+      const input = pending.subarray(newline + 1, newline + 1 + length);
+      pending = pending.subarray(newline + 1 + length);
+
+      const { code, stdout, stderr } = await run(program, input);
+      process.stdout.write(`${code} ${stdout.length} ${stderr.length}\n`);
+      process.stdout.write(stdout);
+      process.stdout.write(stderr);
+    }
+  }
+}
+
+/**
+ * Runs the program in this worker thread, with the input on `process.stdin`. File descriptors 0 to
+ * 2 are the ones of the whole worker, so they are redirected to the input and the thread's streams.
+ */
+function runProgram(program, input) {
+  const stdin = new PassThrough();
+  stdin.end(input);
+  Object.defineProperty(process, 'stdin', { value: stdin, configurable: true });
+
+  const readFileSync = fs.readFileSync;
+  fs.readFileSync = function (file, options) {
+    if (file === 0 || file === '/dev/stdin') {
+      const encoding = typeof options === 'string' ? options : options && options.encoding;
+      return encoding ? input.toString(encoding) : Buffer.from(input);
+    }
+    return readFileSync.apply(this, arguments);
+  };
+  const writeSync = fs.writeSync;
+  fs.writeSync = function (fd, data, ...rest) {
+    if (fd === 1 || fd === 2) {
+      (fd === 1 ? process.stdout : process.stderr).write(data);
+      return Buffer.byteLength(data);
+    }
+    return writeSync.call(this, fd, data, ...rest);
+  };
+
+  require(program);
+}
+
+if (isMainThread) {
+  serve(path.resolve(process.argv[2]));
+} else {
+  runProgram(workerData.program, Buffer.from(workerData.input));
+}
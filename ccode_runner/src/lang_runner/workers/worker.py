@@ -0,0 +1,102 @@
+"""Runs a Python program once per input, in a fresh __main__ module, keeping the interpreter warm.
+
+The modules the program imports are imported afresh for each input too.
+
+Usage: worker.py <program>, where the program is a file or a directory with a __main__.py.
+
+Requests are read from stdin as `<input length>\n<input>`, and answered on stdout with
+`<exit code> <stdout length> <stderr length>\n<stdout><stderr>`, lengths being in bytes.
+"""
+
+import io
+import os
+import runpy
+import sys
+import tempfile
+import traceback
+
+
+class Buffer(io.BytesIO):
+    """Output of the program, kept open when the program closes its stream"""
+
+    def close(self):
+        pass
+
+
+def exit_code(code):
+    """Exit code of `sys.exit(code)`"""
+    if code is None:
+        return 0
+    if isinstance(code, int):
+        return code
+    print(code, file=sys.stderr)
+    return 1
+
+
+def main():
+    program = os.path.abspath(sys.argv[1])
+    requests = os.fdopen(os.dup(0), "rb")
+    responses = os.fdopen(os.dup(1), "wb")
+    # Stray writes to stdout must not garble the responses
+    os.dup2(2, 1)
+    empty_stdin = os.open(os.devnull, os.O_RDONLY)
+
+    # As when running `python3 <program>`
+    sys.argv = [program]
+    if os.path.isfile(program):
+        sys.path.insert(0, os.path.dirname(program))
+    recursion_limit = sys.getrecursionlimit()
+    saved_streams = (sys.stdin, sys.stdout, sys.stderr)
+
+    while True:
+        header = requests.readline()
+        if not header:
+            return
+        data = requests.read(int(header))
+        # The program may have closed descriptor 0, which the temporary file must not take
+        os.dup2(empty_stdin, 0)
+
+        # Backed by a file on descriptor 0, so that `open(0)` reads the input too
+        with tempfile.TemporaryFile() as stdin_file:
+            stdin_file.write(data)
+            stdin_file.seek(0)
+            os.dup2(stdin_file.fileno(), 0)
+
+            stdout = Buffer()
+            stderr = Buffer()
+            streams = (
+                open(0, encoding="utf-8", closefd=False),
+                io.TextIOWrapper(stdout, encoding="utf-8"),
+                io.TextIOWrapper(stderr, encoding="utf-8"),
+            )
+            sys.stdin, sys.stdout, sys.stderr = streams
+
+            modules = set(sys.modules)
+            code = 0
+            try:
+                runpy.run_path(program, run_name="__main__")
+            except SystemExit as exit:
+                code = exit_code(exit.code)
+            except BaseException:
+                traceback.print_exc()
+                code = 1
+
+            for stream in streams:
+                if not stream.closed:
+                    stream.flush()
+            sys.stdin, sys.stdout, sys.stderr = saved_streams
+            sys.setrecursionlimit(recursion_limit)
+            # Modules imported by the program would keep their state for the next input
+            for name in set(sys.modules) - modules:
+                del sys.modules[name]
+
+        stdout = stdout.getvalue()
+        stderr = stderr.getvalue()
+        responses.write(b"%d %d %d\n" % (code, len(stdout), len(stderr)))
+        responses.write(stdout)
+        responses.write(stderr)
+        responses.flush()
+
+
+if __name__ == "__main__":
+    main()
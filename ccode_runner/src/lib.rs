@@ -5,11 +5,13 @@
 //!
 //! ## Features
 //!
-//! - **Multi-language Support**: Capable of running code in Rust, Python, C, C++, Java, Ruby, and JavaScript.
-//! - **Compilation and Interpretation**: Automatically handles compilation for languages like C, C++, Rust, and Java, and interpretation for languages like Python, Ruby, and JavaScript.
+//! - **Multi-language Support**: Capable of running code in Rust, Python, C, C++, Java, Ruby, JavaScript, Go, Kotlin, C#, Haskell, and PHP.
+//! - **Compilation and Interpretation**: Automatically handles compilation for languages like C, C++, Rust, Go, Haskell, Java, Kotlin, and C#, and interpretation for languages like Python, Ruby, JavaScript, and PHP.
 //! - **Optimized Execution**: Implements caching and precompilation to reduce execution times, especially useful in scenarios with repeated executions.
 //! - **Program Store**: Manages and stores compiled programs for efficient reuse.
-//! - **Execution Limits**: Support for time and memory limits to prevent runaway processes.
+//! - **Execution Limits**: Support for time, memory and output limits to prevent runaway processes.
+//! - **Sandbox**: Optional Linux sandbox (namespaces, seccomp, minimal read-only root) for running untrusted code.
+//! - **Output Diffs**: Locates the first differing line and token of two outputs, for readable mismatch reports.
 //!
 //! ## Modules
 //!
@@ -19,5 +21,12 @@
 pub mod lang_runner;
 mod utils;
 
-// Re-export ExecutionLimits for public API
-pub use utils::program_utils::ExecutionLimits;
+// Re-export execution limits and results for public API
+pub use utils::output_diff::{
+    DEFAULT_CONTEXT_LINES, DiffLine, DiffSegment, OutputDiff, Side, TokenDiff, diff_outputs,
+};
+pub use utils::program_utils::{
+    DEFAULT_OUTPUT_LIMIT_BYTES, DEFAULT_TIME_LIMIT_MS, ExecutionLimits, ExecutionResult,
+    ExecutionStatus, ResourceBackend,
+};
+pub use utils::sandbox::SandboxConfig;
@@ -1,3 +1,7 @@
+#[cfg(target_os = "linux")]
+pub(crate) mod cgroup;
 pub(crate) mod file_utils;
 pub(crate) mod java_classname;
+pub mod output_diff;
 pub mod program_utils;
+pub mod sandbox;
@@ -0,0 +1,242 @@
+//! cgroups v2 backend for enforcing and measuring the resources of a program
+//!
+//! Each execution gets its own cgroup, created under a parent cgroup with the `memory` and `pids`
+//! controllers enabled for its children. The parent is, in order:
+//! - The cgroup in the `CCODE_RUNNER_CGROUP` environment variable (path relative to the cgroup2
+//!   mount, e.g. `/ccode_runner`), for deployments which delegate a cgroup to the runner
+//! - The parent of the runner's own cgroup, so executions run in sibling cgroups
+//!
+//! When neither is usable (cgroups v1 or hybrid hierarchy, missing controllers or permissions),
+//! executions fall back to the rlimit path.
+
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use super::program_utils::ExecutionLimits;
+
+const CGROUP_PARENT_ENV: &str = "CCODE_RUNNER_CGROUP";
+const REQUIRED_CONTROLLERS: [&str; 2] = ["memory", "pids"];
+
+/// Resources used by the processes of a cgroup
+#[derive(Debug, Default)]
+pub(crate) struct CgroupStats {
+    pub(crate) cpu_time: Option<Duration>,
+    pub(crate) peak_memory_bytes: Option<u64>,
+    pub(crate) peak_processes: Option<u64>,
+    pub(crate) oom_killed: bool,
+}
+
+/// cgroup of a single execution, removed on drop
+#[derive(Debug)]
+pub(crate) struct Cgroup {
+    path: PathBuf,
+}
+
+impl Cgroup {
+    /// Creates a cgroup with the limits applied, if cgroups v2 is usable
+    pub(crate) fn create(limits: &ExecutionLimits) -> Option<Self> {
+        static EXECUTION_COUNT: AtomicU64 = AtomicU64::new(0);
+
+        let parent = cgroup_parent()?;
+        let path = parent.join(format!(
+            "ccode_runner-{}-{}",
+            std::process::id(),
+            EXECUTION_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir(&path).ok()?;
+
+        let cgroup = Self { path };
+        cgroup.apply_limits(limits).ok()?;
+        Some(cgroup)
+    }
+
+    fn apply_limits(&self, limits: &ExecutionLimits) -> io::Result<()> {
+        if let Some(memory_limit) = limits.memory_limit_bytes {
+            fs::write(self.path.join("memory.max"), memory_limit.to_string())?;
+            // Swapping out would let the program exceed the limit, unsupported without swap
+            let _ = fs::write(self.path.join("memory.swap.max"), "0");
+        }
+        if let Some(process_limit) = limits.process_limit {
+            fs::write(self.path.join("pids.max"), process_limit.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Makes the spawned process join the cgroup before it execs, so nothing escapes accounting.
+    pub(crate) fn add_to_command(&self, command: &mut Command) -> io::Result<()> {
+        use std::os::unix::process::CommandExt;
+
+        let procs_path = CString::new(self.path.join("cgroup.procs").as_os_str().as_bytes())?;
+
+        unsafe {
+            command.pre_exec(move || {
+                // Writing 0 moves the writing process itself
+                let fd = libc::open(procs_path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+                if fd == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                let written = libc::write(fd, b"0".as_ptr().cast(), 1);
+                libc::close(fd);
+
+                if written != 1 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        Ok(())
+    }
+
+    /// Kills every process left in the cgroup (e.g. orphaned children of the program)
+    pub(crate) fn kill_all(&self) {
+        let _ = fs::write(self.path.join("cgroup.kill"), "1");
+    }
+
+    /// CPU time used so far by every process of the cgroup, including exited ones
+    pub(crate) fn cpu_time(&self) -> Option<Duration> {
+        fs::read_to_string(self.path.join("cpu.stat"))
+            .ok()
+            .and_then(|stat| parse_keyed_value(&stat, "usage_usec"))
+            .map(Duration::from_micros)
+    }
+
+    pub(crate) fn stats(&self) -> CgroupStats {
+        let read = |file: &str| fs::read_to_string(self.path.join(file)).ok();
+
+        CgroupStats {
+            cpu_time: self.cpu_time(),
+            peak_memory_bytes: read("memory.peak").and_then(|peak| peak.trim().parse().ok()),
+            peak_processes: read("pids.peak").and_then(|peak| peak.trim().parse().ok()),
+            oom_killed: read("memory.events")
+                .and_then(|events| parse_keyed_value(&events, "oom_kill"))
+                .is_some_and(|oom_kills| oom_kills > 0),
+        }
+    }
+}
+
+impl Drop for Cgroup {
+    fn drop(&mut self) {
+        // Processes can take a moment to leave the cgroup once killed
+        for _ in 0..50 {
+            if fs::remove_dir(&self.path).is_ok() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(2));
+        }
+    }
+}
+
+/// Parent cgroup for executions, detected once
+fn cgroup_parent() -> Option<&'static Path> {
+    static CGROUP_PARENT: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+    CGROUP_PARENT
+        .get_or_init(|| {
+            let mount_point = fs::read_to_string("/proc/self/mountinfo")
+                .ok()
+                .and_then(|mountinfo| parse_cgroup2_mount_point(&mountinfo))?;
+
+            let parent = match std::env::var(CGROUP_PARENT_ENV) {
+                Ok(parent) => parent,
+                Err(_) => {
+                    let own_cgroup = fs::read_to_string("/proc/self/cgroup")
+                        .ok()
+                        .and_then(|cgroup| parse_unified_cgroup(&cgroup))?;
+                    Path::new(&own_cgroup).parent()?.to_str()?.to_string()
+                }
+            };
+
+            let parent = mount_point.join(parent.trim_start_matches('/'));
+            has_required_controllers(&parent).then_some(parent)
+        })
+        .as_deref()
+}
+
+fn has_required_controllers(cgroup: &Path) -> bool {
+    fs::read_to_string(cgroup.join("cgroup.subtree_control")).is_ok_and(|controllers| {
+        REQUIRED_CONTROLLERS
+            .iter()
+            .all(|required| controllers.split_whitespace().any(|c| c == *required))
+    })
+}
+
+/// Finds the mount point of the cgroup2 filesystem in `/proc/self/mountinfo`
+fn parse_cgroup2_mount_point(mountinfo: &str) -> Option<PathBuf> {
+    mountinfo.lines().find_map(|line| {
+        // Optional fields end with a lone "-", followed by the filesystem type
+        let (mount_fields, fs_fields) = line.split_once(" - ")?;
+        if fs_fields.split_whitespace().next()? != "cgroup2" {
+            return None;
+        }
+        mount_fields.split_whitespace().nth(4).map(PathBuf::from)
+    })
+}
+
+/// Finds the cgroup v2 path (`0::<path>`) in `/proc/self/cgroup`
+fn parse_unified_cgroup(cgroup: &str) -> Option<String> {
+    cgroup
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(str::to_string)
+}
+
+/// Reads a value of a flat keyed file, like `cpu.stat` or `memory.events`
+fn parse_keyed_value(content: &str, key: &str) -> Option<u64> {
+    content.lines().find_map(|line| {
+        let (line_key, value) = line.split_once(' ')?;
+        (line_key == key).then(|| value.trim().parse().ok())?
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_cgroup2_mount_point, parse_keyed_value, parse_unified_cgroup};
+    use std::path::PathBuf;
+
+    #[test]
+    fn parse_cgroup2_mount_point_finds_unified_hierarchy() {
+        let mountinfo = "\
+25 30 0:23 / /sys rw,nosuid,nodev,noexec,relatime shared:7 - sysfs sysfs rw
+32 25 0:28 / /sys/fs/cgroup/memory rw,relatime shared:12 - cgroup cgroup rw,memory
+33 25 0:29 / /sys/fs/cgroup/unified rw,relatime shared:13 - cgroup2 cgroup2 rw";
+
+        assert_eq!(
+            parse_cgroup2_mount_point(mountinfo),
+            Some(PathBuf::from("/sys/fs/cgroup/unified"))
+        );
+    }
+
+    #[test]
+    fn parse_cgroup2_mount_point_without_cgroup2() {
+        let mountinfo = "32 25 0:28 / /sys/fs/cgroup/memory rw - cgroup cgroup rw,memory";
+
+        assert_eq!(parse_cgroup2_mount_point(mountinfo), None);
+    }
+
+    #[test]
+    fn parse_unified_cgroup_skips_v1_hierarchies() {
+        let cgroup = "4:memory:/user.slice\n0::/user.slice/user-1000.slice/session-2.scope\n";
+
+        assert_eq!(
+            parse_unified_cgroup(cgroup).as_deref(),
+            Some("/user.slice/user-1000.slice/session-2.scope")
+        );
+    }
+
+    #[test]
+    fn parse_keyed_value_reads_matching_key() {
+        let memory_events = "low 0\nhigh 0\nmax 12\noom 1\noom_kill 1\noom_group_kill 0\n";
+
+        assert_eq!(parse_keyed_value(memory_events, "oom_kill"), Some(1));
+        assert_eq!(parse_keyed_value(memory_events, "max"), Some(12));
+        assert_eq!(parse_keyed_value(memory_events, "usage_usec"), None);
+    }
+}
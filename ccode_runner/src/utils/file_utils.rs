@@ -1,4 +1,4 @@
-fn normalize(input: &str) -> String {
+pub(crate) fn normalize(input: &str) -> String {
     // Remove BOM at the very start only
     let s = if let Some(rest) = input.strip_prefix('\u{FEFF}') {
         rest
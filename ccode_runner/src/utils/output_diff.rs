@@ -0,0 +1,440 @@
+//! Locates where two outputs first differ, for reports readable even when the outputs are large.
+//!
+//! The outputs are compared as `TextChecker` does, ignoring BOM, EOL differences and trailing
+//! spaces/tabs, so [`diff_outputs`] only finds a difference where the default checker would. The
+//! result is plain data, left to the CLI, the API and the bot to render. [`OutputDiff::render`]
+//! renders it in the format of a unified diff, styled by the caller (e.g. colored by the CLI), and
+//! its `Display` is the plain text rendering.
+
+use std::fmt;
+
+use serde::Serialize;
+
+#[cfg(feature = "api")]
+use utoipa::ToSchema;
+
+use super::file_utils::normalize;
+
+/// Lines shown around the first difference by default
+pub const DEFAULT_CONTEXT_LINES: usize = 2;
+
+/// Where two outputs first differ, with the lines around it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "api", derive(ToSchema))]
+pub struct OutputDiff {
+    /// Line of the first difference, starting at 1
+    pub line: usize,
+    /// First differing token on that line, None if the line only differs in whitespace
+    pub token: Option<TokenDiff>,
+    /// Lines before the first difference, the same in both outputs
+    pub context: Vec<DiffLine>,
+    /// The differing line of the expected output, followed by the context after it
+    pub expected: Vec<DiffLine>,
+    /// The differing line of the actual output, followed by the context after it
+    pub actual: Vec<DiffLine>,
+    /// Number of lines of the expected output
+    pub expected_lines: usize,
+    /// Number of lines of the actual output
+    pub actual_lines: usize,
+    /// Whether the expected output ends with a newline
+    pub expected_final_newline: bool,
+    /// Whether the actual output ends with a newline
+    pub actual_final_newline: bool,
+}
+
+/// The first differing token of a line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "api", derive(ToSchema))]
+pub struct TokenDiff {
+    /// Index of the token on the line, starting at 1
+    pub index: usize,
+    /// Column the token starts at, in characters starting at 1
+    pub column: usize,
+    /// The token in the expected output, None if its line ends before it
+    pub expected: Option<String>,
+    /// The token in the actual output, None if its line ends before it
+    pub actual: Option<String>,
+}
+
+/// A line of one of the outputs, split into the parts which differ from the other output and those
+/// which don't
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "api", derive(ToSchema))]
+pub struct DiffLine {
+    /// Line number, starting at 1
+    pub number: usize,
+    pub segments: Vec<DiffSegment>,
+}
+
+/// Part of a line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "api", derive(ToSchema))]
+pub struct DiffSegment {
+    pub text: String,
+    /// Whether this part differs from the same line of the other output
+    pub changed: bool,
+}
+
+impl DiffLine {
+    /// The whole line, without its highlighting
+    pub fn text(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect()
+    }
+}
+
+/// Finds where the actual output first differs from the expected output, with `context_lines`
+/// lines around it.
+///
+/// # Returns
+///
+/// * `Some(OutputDiff)` - The first difference
+/// * `None` - If the outputs are the same, once normalized as `TextChecker` does
+pub fn diff_outputs(expected: &str, actual: &str, context_lines: usize) -> Option<OutputDiff> {
+    let expected = normalize(expected);
+    let actual = normalize(actual);
+    if expected == actual {
+        return None;
+    }
+
+    let expected_lines = split_lines(&expected);
+    let actual_lines = split_lines(&actual);
+    // Outputs with the same lines only differ in their final newline, shown on the last line
+    let first_difference = (0..expected_lines.len().max(actual_lines.len()))
+        .find(|&index| expected_lines.get(index) != actual_lines.get(index))
+        .unwrap_or(expected_lines.len().saturating_sub(1));
+
+    let context = expected_lines[first_difference.saturating_sub(context_lines)..first_difference]
+        .iter()
+        .enumerate()
+        .map(|(offset, line)| DiffLine {
+            number: first_difference.saturating_sub(context_lines) + offset + 1,
+            segments: vec![DiffSegment {
+                text: line.to_string(),
+                changed: false,
+            }],
+        })
+        .collect();
+    let shown = first_difference..first_difference + context_lines + 1;
+
+    Some(OutputDiff {
+        line: first_difference + 1,
+        token: diff_tokens(
+            expected_lines.get(first_difference).copied(),
+            actual_lines.get(first_difference).copied(),
+        ),
+        context,
+        expected: diff_lines(&expected_lines, &actual_lines, shown.clone()),
+        actual: diff_lines(&actual_lines, &expected_lines, shown),
+        expected_lines: expected_lines.len(),
+        actual_lines: actual_lines.len(),
+        expected_final_newline: expected.ends_with('\n'),
+        actual_final_newline: actual.ends_with('\n'),
+    })
+}
+
+/// Lines of the output, a final newline ending the last line rather than starting an empty one
+fn split_lines(output: &str) -> Vec<&str> {
+    match output.strip_suffix('\n') {
+        Some(output) => output.split('\n').collect(),
+        None if output.is_empty() => Vec::new(),
+        None => output.split('\n').collect(),
+    }
+}
+
+/// Lines of `lines` in the range, highlighted against the same lines of `other`
+fn diff_lines(lines: &[&str], other: &[&str], range: std::ops::Range<usize>) -> Vec<DiffLine> {
+    range
+        .filter_map(|index| {
+            let line = lines.get(index)?;
+            Some(DiffLine {
+                number: index + 1,
+                segments: diff_segments(line, other.get(index).copied()),
+            })
+        })
+        .collect()
+}
+
+/// Splits the line into its common prefix and suffix with the other line, and the changed part in
+/// between
+fn diff_segments(line: &str, other: Option<&str>) -> Vec<DiffSegment> {
+    let Some(other) = other else {
+        return vec![DiffSegment {
+            text: line.to_string(),
+            changed: true,
+        }];
+    };
+
+    let chars: Vec<char> = line.chars().collect();
+    let other_chars: Vec<char> = other.chars().collect();
+    let prefix = chars
+        .iter()
+        .zip(&other_chars)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = chars[prefix..]
+        .iter()
+        .rev()
+        .zip(other_chars[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    [
+        (&chars[..prefix], false),
+        (&chars[prefix..chars.len() - suffix], true),
+        (&chars[chars.len() - suffix..], false),
+    ]
+    .into_iter()
+    .filter(|(text, _)| !text.is_empty())
+    .map(|(text, changed)| DiffSegment {
+        text: text.iter().collect(),
+        changed,
+    })
+    .collect()
+}
+
+/// Whitespace separated tokens of the line, with the column each starts at
+fn tokens(line: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (column, (index, c)) in line.char_indices().enumerate() {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some((column, index)),
+            (true, Some((start_column, start_index))) => {
+                tokens.push((start_column + 1, &line[start_index..index]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((start_column, start_index)) = start {
+        tokens.push((start_column + 1, &line[start_index..]));
+    }
+    tokens
+}
+
+fn diff_tokens(expected: Option<&str>, actual: Option<&str>) -> Option<TokenDiff> {
+    let expected = tokens(expected.unwrap_or_default());
+    let actual = tokens(actual.unwrap_or_default());
+
+    let index = (0..expected.len().max(actual.len()))
+        .find(|&index| expected.get(index).map(|t| t.1) != actual.get(index).map(|t| t.1))?;
+    let column = expected
+        .get(index)
+        .or(actual.get(index))
+        .map_or(1, |(column, _)| *column);
+    Some(TokenDiff {
+        index: index + 1,
+        column,
+        expected: expected.get(index).map(|(_, token)| token.to_string()),
+        actual: actual.get(index).map(|(_, token)| token.to_string()),
+    })
+}
+
+/// Part of an `OutputDiff` being rendered, for `OutputDiff::render` to style
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// Where the outputs first differ, and whether only one of them ends with a newline
+    Summary,
+    /// Lines before the first difference, the same in both outputs
+    Context,
+    /// Lines of the expected output
+    Expected,
+    /// Lines of the actual output
+    Actual,
+}
+
+impl OutputDiff {
+    /// Renders the difference in the format of a unified diff, passing each piece of text through
+    /// `paint` along with the part it belongs to and whether it differs from the other output
+    /// (e.g. to color it)
+    pub fn render(&self, paint: impl Fn(&str, Side, bool) -> String) -> String {
+        let describe = |token: &Option<String>| match token {
+            Some(token) => format!("`{token}`"),
+            None => String::from("the end of the line"),
+        };
+        let location = match &self.token {
+            Some(token) => format!(
+                "First difference at line {}, token {} (column {}): expected {}, found {}",
+                self.line,
+                token.index,
+                token.column,
+                describe(&token.expected),
+                describe(&token.actual)
+            ),
+            None => format!("First difference at line {}, in whitespace", self.line),
+        };
+        let mut rendered = paint(&location, Side::Summary, false) + "\n";
+
+        let width = self
+            .expected
+            .iter()
+            .chain(&self.actual)
+            .map(|line| line.number.to_string().len())
+            .max()
+            .unwrap_or(1);
+        for line in &self.context {
+            let line = format!("  {:>width$} | {}", line.number, line.text());
+            rendered += &(paint(&line, Side::Context, false) + "\n");
+        }
+        for (sign, lines, total, side) in [
+            ('-', &self.expected, self.expected_lines, Side::Expected),
+            ('+', &self.actual, self.actual_lines, Side::Actual),
+        ] {
+            for line in lines {
+                rendered += &paint(&format!("{sign} {:>width$} | ", line.number), side, false);
+                for segment in &line.segments {
+                    rendered += &paint(&segment.text, side, segment.changed);
+                }
+                rendered += "\n";
+            }
+            if lines.is_empty() {
+                let end = format!("{sign} (ends after line {total})");
+                rendered += &(paint(&end, side, false) + "\n");
+            }
+        }
+
+        if self.expected_final_newline != self.actual_final_newline {
+            let (with, without) = match self.expected_final_newline {
+                true => ("expected", "actual"),
+                false => ("actual", "expected"),
+            };
+            let note =
+                format!("The {with} output ends with a newline, the {without} output doesn't");
+            rendered += &(paint(&note, Side::Summary, false) + "\n");
+        }
+        rendered
+    }
+}
+
+impl fmt::Display for OutputDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(|text, _, _| text.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DiffSegment, Side, diff_outputs, split_lines, tokens};
+
+    fn segment(text: &str, changed: bool) -> DiffSegment {
+        DiffSegment {
+            text: text.to_string(),
+            changed,
+        }
+    }
+
+    #[test]
+    fn same_outputs_have_no_diff() {
+        assert_eq!(diff_outputs("1 2\n3\n", "1 2  \r\n3\r\n", 2), None);
+        assert_eq!(diff_outputs("", "", 2), None);
+    }
+
+    #[test]
+    fn finds_first_differing_line_and_token() {
+        let expected = "a\nb\nc\n1 2 3\nd\ne\nf\n";
+        let actual = "a\nb\nc\n1 5 3\nd\nx\nf\n";
+        let diff = diff_outputs(expected, actual, 2).unwrap();
+
+        assert_eq!(diff.line, 4);
+        let token = diff.token.unwrap();
+        assert_eq!((token.index, token.column), (2, 3));
+        assert_eq!(token.expected.as_deref(), Some("2"));
+        assert_eq!(token.actual.as_deref(), Some("5"));
+
+        let context: Vec<_> = diff.context.iter().map(|line| line.number).collect();
+        assert_eq!(context, [2, 3]);
+        let shown: Vec<_> = diff.actual.iter().map(|line| line.text()).collect();
+        assert_eq!(shown, ["1 5 3", "d", "x"]);
+        assert_eq!(
+            diff.actual[0].segments,
+            [
+                segment("1 ", false),
+                segment("5", true),
+                segment(" 3", false)
+            ]
+        );
+        assert_eq!(diff.actual[1].segments, [segment("d", false)]);
+        assert_eq!(diff.actual[2].segments, [segment("x", true)]);
+    }
+
+    #[test]
+    fn missing_lines_and_tokens() {
+        let diff = diff_outputs("1\n2\n3\n", "1\n", 2).unwrap();
+        assert_eq!(diff.line, 2);
+        assert!(diff.actual.is_empty());
+        assert_eq!((diff.expected_lines, diff.actual_lines), (3, 1));
+        let token = diff.token.unwrap();
+        assert_eq!(token.expected.as_deref(), Some("2"));
+        assert_eq!(token.actual, None);
+
+        let diff = diff_outputs("1 2\n", "1\n", 2).unwrap();
+        let token = diff.token.unwrap();
+        assert_eq!((token.index, token.column), (2, 3));
+        assert_eq!(token.actual, None);
+    }
+
+    #[test]
+    fn whitespace_only_difference_has_no_token() {
+        let diff = diff_outputs("1 2\n", "1  2\n", 2).unwrap();
+        assert_eq!(diff.line, 1);
+        assert_eq!(diff.token, None);
+    }
+
+    #[test]
+    fn final_newline_difference_is_shown_on_last_line() {
+        let diff = diff_outputs("a\nb\n", "a\nb", 2).unwrap();
+        assert_eq!(diff.line, 2);
+        assert_eq!(diff.token, None);
+        assert!(diff.expected_final_newline && !diff.actual_final_newline);
+        assert!(
+            diff.to_string()
+                .contains("The expected output ends with a newline")
+        );
+    }
+
+    #[test]
+    fn display_renders_unified_diff() {
+        let diff = diff_outputs("x\n1 2\n", "x\n1 3\n", 1).unwrap();
+        assert_eq!(
+            diff.to_string(),
+            "First difference at line 2, token 2 (column 3): expected `2`, found `3`\n  1 | x\n- 2 | 1 2\n+ 2 | 1 3\n"
+        );
+
+        let diff = diff_outputs("1\n", "", 1).unwrap();
+        assert_eq!(
+            diff.to_string(),
+            "First difference at line 1, token 1 (column 1): expected `1`, found the end of the line\n- 1 | 1\n+ (ends after line 0)\nThe expected output ends with a newline, the actual output doesn't\n"
+        );
+    }
+
+    #[test]
+    fn render_paints_each_part() {
+        let diff = diff_outputs("x\n1 2\n", "x\n1 3\n", 1).unwrap();
+        let rendered = diff.render(|text, side, changed| match (side, changed) {
+            (_, true) => format!("[{text}]"),
+            (Side::Summary, _) => format!("!{text}"),
+            (Side::Context, _) => format!("~{text}"),
+            (Side::Expected, _) => format!("<{text}>"),
+            (Side::Actual, _) => format!("({text})"),
+        });
+        assert_eq!(
+            rendered,
+            "!First difference at line 2, token 2 (column 3): expected `2`, found `3`\n~  1 | x\n<- 2 | ><1 >[2]\n(+ 2 | )(1 )[3]\n"
+        );
+    }
+
+    #[test]
+    fn split_lines_ignores_final_newline() {
+        assert_eq!(split_lines("a\nb\n"), ["a", "b"]);
+        assert_eq!(split_lines("a\n\n"), ["a", ""]);
+        assert!(split_lines("").is_empty());
+    }
+
+    #[test]
+    fn tokens_report_char_columns() {
+        assert_eq!(tokens("  é 12\tx "), [(3, "é"), (5, "12"), (8, "x")]);
+    }
+}
@@ -1,27 +1,60 @@
+use std::borrow::Cow;
+use std::io::{self, Read, Write};
 use std::path::Path;
-use std::process::Command;
-use std::process::{Output, Stdio};
-use std::time::Duration;
-use std::{io, io::Write};
-use wait_timeout::ChildExt;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::sync::Notify;
 use which::which;
 
+#[cfg(target_os = "linux")]
+use super::cgroup::Cgroup;
+use super::sandbox::{self, SandboxConfig};
+
+/// Default wall clock time limit of tools running arbitrary programs, 10 seconds, which stops
+/// programs that never finish
+pub const DEFAULT_TIME_LIMIT_MS: u64 = 10_000;
+
+/// Default output limit of tools running arbitrary programs, 64 MiB, which stops programs that
+/// print forever before they exhaust the memory holding their output
+pub const DEFAULT_OUTPUT_LIMIT_BYTES: u64 = 64 * 1024 * 1024;
+
 /// Execution limits for running programs
 ///
 /// # Platform Support
 ///
-/// - **Time limits**: Supported on all platforms (Linux, macOS, Windows)
+/// - **Time limits**: Wall clock limits are supported on all platforms (Linux, macOS, Windows)
+/// - **CPU time limits**: Unix only, ignored on Windows
+///   - Linux: CPU time is sampled while the program runs (from the cgroup, or `/proc` for the rlimit
+///     backend), with `setrlimit(RLIMIT_CPU)` as a backstop
+///   - macOS: `setrlimit(RLIMIT_CPU)`, rounded up to whole seconds, and checked against the CPU
+///     time reported by `wait4` afterwards (not reported for async runs, which only get killed)
 /// - **Memory limits**: Supported on all platforms
-///   - Linux: Uses `setrlimit(RLIMIT_AS)` for native OS enforcement
+///   - Linux: Uses a cgroups v2 `memory.max` when available (see `ResourceBackend`), otherwise
+///     falls back to `setrlimit(RLIMIT_AS)` for native OS enforcement
 ///   - macOS/Windows: Uses active RSS monitoring via `sysinfo` to track and kill over-limit processes
 ///     (`RLIMIT_AS` is not used on macOS because high-level runtimes like Python pre-map large
 ///     virtual address spaces at startup, making virtual-memory limits unreliable)
+/// - **Process limits**: Linux with cgroups v2 only (`pids.max`), ignored otherwise
+/// - **Output limits**: Supported on all platforms, stdout is no longer read past the limit
+/// - **Sandbox**: Linux only, running with a sandbox elsewhere fails instead (see `SandboxConfig`)
 #[derive(Debug, Clone, Copy, Default)]
 pub struct ExecutionLimits {
-    /// Time limit in milliseconds (None means no limit)
+    /// Wall clock time limit in milliseconds (None means no limit)
     pub time_limit_ms: Option<u64>,
+    /// CPU time limit in milliseconds (None means no limit)
+    pub cpu_time_limit_ms: Option<u64>,
     /// Memory limit in bytes (None means no limit)
     pub memory_limit_bytes: Option<u64>,
+    /// Output limit on stdout in bytes (None means no limit)
+    pub output_limit_bytes: Option<u64>,
+    /// Limit on the number of processes and threads alive at once (None means no limit)
+    pub process_limit: Option<u64>,
+    /// Sandbox to run untrusted programs in (None means no sandbox)
+    pub sandbox: Option<SandboxConfig>,
 }
 
 impl ExecutionLimits {
@@ -30,45 +63,154 @@ impl ExecutionLimits {
         Self::default()
     }
 
-    /// Set time limit in milliseconds
+    /// Set wall clock time limit in milliseconds
+    ///
+    /// Counts time spent waiting (sleeping, blocked on input, descheduled on a loaded machine), so
+    /// it is best used as a generous idle limit alongside a CPU time limit.
     pub fn with_time_limit(mut self, time_limit_ms: u64) -> Self {
         self.time_limit_ms = Some(time_limit_ms);
         self
     }
 
+    /// Set CPU time limit in milliseconds
+    ///
+    /// Counts user and system CPU time of the program (and every process it starts, with the
+    /// cgroups v2 backend), so it is unaffected by how loaded the machine is. A program which
+    /// never uses the CPU (e.g. waiting for input) is only stopped by the wall clock time limit.
+    pub fn with_cpu_time_limit(mut self, cpu_time_limit_ms: u64) -> Self {
+        self.cpu_time_limit_ms = Some(cpu_time_limit_ms);
+        self
+    }
+
     /// Set memory limit in bytes
     ///
     /// Memory limits are enforced on all platforms:
-    /// - Linux: Native OS enforcement via `setrlimit(RLIMIT_AS)`
+    /// - Linux: cgroups v2 `memory.max` (resident memory, so runtimes reserving large virtual
+    ///   ranges like the JVM are fine), or native OS enforcement via `setrlimit(RLIMIT_AS)` as
+    ///   a fallback
     /// - macOS/Windows: Active RSS monitoring via `sysinfo` — polls every 100ms and kills the
     ///   process if it exceeds the limit
     pub fn with_memory_limit(mut self, memory_limit_bytes: u64) -> Self {
         self.memory_limit_bytes = Some(memory_limit_bytes);
         self
     }
+
+    /// Set limit on the number of processes and threads alive at once
+    ///
+    /// Only enforced on Linux with cgroups v2 (`pids.max`).
+    pub fn with_process_limit(mut self, process_limit: u64) -> Self {
+        self.process_limit = Some(process_limit);
+        self
+    }
+
+    /// Set output limit on stdout in bytes
+    ///
+    /// Once exceeded, the rest of the output is discarded and the process is killed.
+    pub fn with_output_limit(mut self, output_limit_bytes: u64) -> Self {
+        self.output_limit_bytes = Some(output_limit_bytes);
+        self
+    }
+
+    /// Run programs inside the sandbox
+    ///
+    /// Only used for running programs, compilation is never sandboxed.
+    pub fn with_sandbox(mut self, sandbox: SandboxConfig) -> Self {
+        self.sandbox = Some(sandbox);
+        self
+    }
 }
 
-fn program_exists(program: &str) -> Result<std::path::PathBuf, which::Error> {
-    which(program)
+/// Classification of how a program execution ended
+///
+/// Limits take precedence over the exit status, as a process killed for exceeding a limit
+/// (or failing to allocate under `RLIMIT_AS`) also exits with a failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionStatus {
+    /// Exited with code 0 within all limits
+    Success,
+    /// Killed for exceeding the wall clock time limit
+    TimeLimitExceeded,
+    /// Used more CPU time than the CPU time limit
+    CpuTimeLimitExceeded,
+    /// Exceeded the memory limit, either killed by the memory monitor or failed to allocate
+    MemoryLimitExceeded,
+    /// Killed for writing more than the output limit
+    OutputLimitExceeded,
+    /// Terminated by the signal, e.g. 11 for SIGSEGV
+    Signaled(i32),
+    /// Exited with the non-zero exit code
+    NonZeroExit(i32),
+}
+
+/// Mechanism used to enforce and measure the resources of an execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceBackend {
+    /// A dedicated cgroups v2 cgroup (Linux), covering every process the program starts
+    CgroupV2,
+    /// `setrlimit` and `wait4` resource usage (Linux without usable cgroups v2)
+    Rlimit,
+    /// Polling the memory usage of the process (macOS and Windows)
+    Monitor,
+    /// A warm worker shared by many runs, which only measures the wall clock time (see
+    /// `ToolchainConfig::with_workers`)
+    Worker,
+}
+
+/// Outcome of a single program execution
+///
+/// A program which ran to completion (or was killed) is reported here, whatever its exit status.
+/// Use `is_success` to check whether it exited cleanly.
+///
+/// # Platform Support
+///
+/// - `signal`, `cpu_time` and `peak_memory_bytes` are only available on Unix, where the child is
+///   reaped with `wait4`, and are `None` elsewhere. Async runs report `cpu_time` and
+///   `peak_memory_bytes` on Linux only.
+/// - `peak_processes` is only available with the cgroups v2 backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionResult {
+    /// Exit code of the process (None if it was terminated by a signal)
+    pub exit_code: Option<i32>,
+    /// Signal which terminated the process, e.g. 11 for SIGSEGV (None if it exited normally)
+    pub signal: Option<i32>,
+    /// Captured standard output, as raw bytes since programs may print invalid UTF-8
+    pub stdout: Vec<u8>,
+    /// Captured standard error, as raw bytes
+    pub stderr: Vec<u8>,
+    /// Wall clock time from spawning the process until it was reaped
+    pub wall_time: Duration,
+    /// User and system CPU time consumed by the process (and every process it started, with the
+    /// cgroups v2 backend)
+    pub cpu_time: Option<Duration>,
+    /// Peak resident set size of the process in bytes
+    pub peak_memory_bytes: Option<u64>,
+    /// Peak number of processes and threads alive at once
+    pub peak_processes: Option<u64>,
+    /// How the execution ended, taking the limits into account
+    pub status: ExecutionStatus,
+    /// Mechanism which enforced the limits and measured the resources
+    pub resource_backend: ResourceBackend,
 }
 
-fn run_program_common(output: Output, program: &str, args: &[&str]) -> io::Result<String> {
-    if output.status.code() != Some(0) {
-        return Err(io::Error::other(format!(
-            "Process `{} {}` failed to run successfully!\nStatus Code: {}\nOutput: {}\nError: {}",
-            program,
-            args.join(" "),
-            output.status,
-            String::from_utf8_lossy(&output.stdout),
-            String::from_utf8_lossy(&output.stderr)
-        )));
+impl ExecutionResult {
+    /// Returns true if the process exited with code 0 within all limits
+    pub fn is_success(&self) -> bool {
+        self.status == ExecutionStatus::Success
+    }
+
+    /// Standard output as text, with invalid UTF-8 replaced by `U+FFFD`
+    pub fn stdout_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.stdout)
     }
 
-    let stdout_content = String::from_utf8(output.stdout)
-        .map_err(|non_utf8| String::from_utf8_lossy(non_utf8.as_bytes()).into_owned())
-        .expect("Found invalid UTF-8");
+    /// Standard error as text, with invalid UTF-8 replaced by `U+FFFD`
+    pub fn stderr_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.stderr)
+    }
+}
 
-    Ok(stdout_content)
+pub(crate) fn program_exists(program: &str) -> Result<std::path::PathBuf, which::Error> {
+    which(program)
 }
 
 pub(crate) fn run_program_with_input(
@@ -76,7 +218,172 @@ pub(crate) fn run_program_with_input(
     args: &Vec<&str>,
     stdin_content: &str,
     limits: &ExecutionLimits,
-) -> io::Result<String> {
+) -> io::Result<ExecutionResult> {
+    execute(program, args, Some(stdin_content), limits)
+}
+
+/// Same as `run_program_with_input`, without blocking the async runtime.
+///
+/// The program is spawned and waited for by tokio. Dropping the future (e.g. when it loses a
+/// `tokio::select!` or times out) cancels the run, killing the program along with every process it
+/// started.
+pub(crate) async fn run_program_with_input_async(
+    program: &str,
+    args: &[&str],
+    stdin_content: &str,
+    limits: &ExecutionLimits,
+) -> io::Result<ExecutionResult> {
+    let (child, mut monitor) = prepare_command(program, args, limits)?.spawn_async()?;
+    let mut program = AsyncProgram { child };
+    let stdin = program.child.stdin.take();
+    let stdout = program.child.stdout.take();
+    let stderr = program.child.stderr.take();
+
+    let output_limit_exceeded = monitor.output_limit_exceeded.clone();
+    let output_limit_reached = Notify::new();
+    let wait_limits = WaitLimits {
+        time_limit: limits.time_limit_ms.map(Duration::from_millis),
+        cpu_time_limit: limits.cpu_time_limit_ms.map(Duration::from_millis),
+        output_limit_exceeded: &output_limit_exceeded,
+    };
+    let stderr_limit_exceeded = AtomicBool::new(false);
+
+    let (stdout, stderr, finished) = tokio::join!(
+        async {
+            let stdout =
+                read_pipe_async(stdout, limits.output_limit_bytes, &output_limit_exceeded).await;
+            if output_limit_exceeded.load(Ordering::Relaxed) {
+                output_limit_reached.notify_one();
+            }
+            stdout
+        },
+        read_pipe_async(stderr, None, &stderr_limit_exceeded),
+        async {
+            let exit = wait_for_exit_async(
+                &mut program.child,
+                &wait_limits,
+                &output_limit_reached,
+                write_stdin_async(stdin, stdin_content),
+                |pid| monitor.sample_cpu_time(pid),
+            )
+            .await?;
+            io::Result::Ok(monitor.finish(exit))
+        },
+    );
+    Ok(finished?.into_result(stdout, stderr))
+}
+
+/// Spawns the program, feeds it the stdin content (if any) and waits for it, enforcing the limits.
+///
+/// Only failures to launch or talk to the process are returned as errors, how the process itself
+/// ended is described by the `ExecutionResult`.
+fn execute(
+    program: &str,
+    args: &[&str],
+    stdin_content: Option<&str>,
+    limits: &ExecutionLimits,
+) -> io::Result<ExecutionResult> {
+    let mut running = spawn_program(program, args, limits)?;
+    running.capture_stdout();
+
+    // Written from its own thread, as a program that doesn't read its input would otherwise block
+    // the write (and with it the limits) once the pipe is full. Stdin is closed once written.
+    let stdin_writer = running
+        .child
+        .stdin
+        .take()
+        .zip(stdin_content)
+        .map(|(stdin, stdin_content)| spawn_stdin_writer(stdin, stdin_content.to_owned()));
+
+    let result = running.wait()?;
+
+    // The program is gone, so the writer is done unless a process it left behind holds stdin
+    if let Some(stdin_writer) = stdin_writer
+        && stdin_writer.is_finished()
+    {
+        stdin_writer
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("Writing stdin panicked")))?;
+    }
+    Ok(result)
+}
+
+/// Writes the content to stdin and closes it. The program closing its stdin early (e.g. exiting
+/// without reading everything) isn't an error, the rest of the input is left unread.
+fn spawn_stdin_writer(mut stdin: ChildStdin, content: String) -> JoinHandle<io::Result<()>> {
+    thread::spawn(move || match stdin.write_all(content.as_bytes()) {
+        Err(err) if err.kind() != io::ErrorKind::BrokenPipe => Err(err),
+        _ => Ok(()),
+    })
+}
+
+/// Same as `spawn_stdin_writer`, on the async runtime
+async fn write_stdin_async(
+    stdin: Option<tokio::process::ChildStdin>,
+    content: &str,
+) -> io::Result<()> {
+    let Some(mut stdin) = stdin else {
+        return Ok(());
+    };
+    match stdin.write_all(content.as_bytes()).await {
+        Err(err) if err.kind() != io::ErrorKind::BrokenPipe => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Runs the solution and the interactor at once, each one's stdout feeding the other's stdin, and
+/// waits for both, enforcing their own limits.
+///
+/// Everything either program writes is passed to its callback as it is relayed, in order. Once a
+/// program exits, the other one sees the end of its input.
+pub(crate) fn run_interactive(
+    solution: (&str, &[&str], &ExecutionLimits),
+    interactor: (&str, &[&str], &ExecutionLimits),
+    on_solution_output: impl FnMut(&[u8]) + Send + 'static,
+    on_interactor_output: impl FnMut(&[u8]) + Send + 'static,
+) -> io::Result<(ExecutionResult, ExecutionResult)> {
+    let (solution_program, solution_args, solution_limits) = solution;
+    let (interactor_program, interactor_args, interactor_limits) = interactor;
+
+    let mut solution = spawn_program(solution_program, solution_args, solution_limits)?;
+    let mut interactor = match spawn_program(interactor_program, interactor_args, interactor_limits)
+    {
+        Ok(interactor) => interactor,
+        Err(err) => {
+            let _ = solution.child.kill();
+            let _ = solution.wait();
+            return Err(err);
+        }
+    };
+
+    let solution_stdin = solution.child.stdin.take();
+    let interactor_stdin = interactor.child.stdin.take();
+    solution.relay_stdout(interactor_stdin, on_solution_output);
+    interactor.relay_stdout(solution_stdin, on_interactor_output);
+
+    thread::scope(|scope| {
+        let solution_wait = scope.spawn(move || solution.wait());
+        let interactor_result = interactor.wait();
+        let solution_result = solution_wait
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("Waiting for the solution panicked")));
+
+        Ok((solution_result?, interactor_result?))
+    })
+}
+
+/// The command running a program, set up with the limits which apply before it starts
+struct PreparedCommand {
+    command: Command,
+    monitor: ProgramMonitor,
+}
+
+/// Sets up the command running the program with piped stdio, in a process group of its own
+fn prepare_command(
+    program: &str,
+    args: &[&str],
+    limits: &ExecutionLimits,
+) -> io::Result<PreparedCommand> {
     if let Err(err) = program_exists(program) {
         return Err(io::Error::other(err));
     }
@@ -88,94 +395,977 @@ pub(crate) fn run_program_with_input(
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
-    // Apply memory limit via setrlimit on Linux only.
-    // macOS is excluded: RLIMIT_AS limits virtual address space, but runtimes like Python
-    // pre-map large virtual ranges at startup, making this limit unreliable on macOS.
+    // Set up first, so that the processes the program starts can be killed along with it
+    #[cfg(unix)]
+    start_process_group(&mut command);
+
+    // Prefer a cgroup, which limits resident memory and accounts for every process started
+    #[cfg(target_os = "linux")]
+    let cgroup = Cgroup::create(limits);
     #[cfg(target_os = "linux")]
-    if let Some(memory_limit) = limits.memory_limit_bytes {
-        apply_memory_limit(&mut command, memory_limit);
+    let resource_backend = match &cgroup {
+        Some(cgroup) => {
+            cgroup.add_to_command(&mut command)?;
+            ResourceBackend::CgroupV2
+        }
+        None => {
+            // Apply memory limit via setrlimit on Linux only.
+            // macOS is excluded: RLIMIT_AS limits virtual address space, but runtimes like Python
+            // pre-map large virtual ranges at startup, making this limit unreliable on macOS.
+            if let Some(memory_limit) = limits.memory_limit_bytes {
+                apply_memory_limit(&mut command, memory_limit);
+            }
+            ResourceBackend::Rlimit
+        }
+    };
+    #[cfg(not(target_os = "linux"))]
+    let resource_backend = ResourceBackend::Monitor;
+
+    #[cfg(unix)]
+    if let Some(cpu_time_limit) = limits.cpu_time_limit_ms {
+        apply_cpu_time_limit(&mut command, cpu_time_limit);
     }
 
-    let mut child = command.spawn()?;
+    // Joined after the cgroup, which must be entered before the sandbox's namespaces
+    if let Some(sandbox_config) = &limits.sandbox {
+        sandbox::apply_sandbox(&mut command, sandbox_config)?;
+    }
+
+    let monitor = ProgramMonitor {
+        limits: *limits,
+        start_time: Instant::now(),
+        resource_backend,
+        #[cfg(target_os = "linux")]
+        cgroup,
+        #[cfg(not(target_os = "linux"))]
+        memory_monitor: None,
+        output_limit_exceeded: Arc::new(AtomicBool::new(false)),
+    };
+    Ok(PreparedCommand { command, monitor })
+}
+
+impl PreparedCommand {
+    /// Spawns the program, along with the monitor of its resources
+    fn spawn(self) -> io::Result<(Child, ProgramMonitor)> {
+        let PreparedCommand {
+            mut command,
+            mut monitor,
+        } = self;
 
-    // Active memory monitor for macOS and Windows (Linux uses native setrlimit instead)
+        monitor.start_time = Instant::now();
+        let child = command.spawn()?;
+        monitor.watch_memory(child.id());
+        Ok((child, monitor))
+    }
+
+    /// Same as `spawn`, with the child's pipes and exit driven by the async runtime
+    fn spawn_async(self) -> io::Result<(tokio::process::Child, ProgramMonitor)> {
+        let PreparedCommand {
+            command,
+            mut monitor,
+        } = self;
+        let mut command = tokio::process::Command::from(command);
+        // The rest of its process group is killed along with it by `AsyncProgram`
+        command.kill_on_drop(true);
+
+        monitor.start_time = Instant::now();
+        let child = command.spawn()?;
+        if let Some(pid) = child.id() {
+            monitor.watch_memory(pid);
+        }
+        Ok((child, monitor))
+    }
+}
+
+/// The resources of a spawned program, from which its limits are enforced and its result reported
+struct ProgramMonitor {
+    limits: ExecutionLimits,
+    start_time: Instant,
+    resource_backend: ResourceBackend,
+    #[cfg(target_os = "linux")]
+    cgroup: Option<Cgroup>,
     #[cfg(not(target_os = "linux"))]
-    let memory_monitor = if let Some(memory_limit) = limits.memory_limit_bytes {
-        Some(start_memory_monitor(child.id(), memory_limit))
-    } else {
+    memory_monitor: Option<MemoryMonitor>,
+    output_limit_exceeded: Arc<AtomicBool>,
+}
+
+impl ProgramMonitor {
+    /// Starts the active memory monitor for macOS and Windows (Linux uses native setrlimit instead)
+    #[cfg(not(target_os = "linux"))]
+    fn watch_memory(&mut self, pid: u32) {
+        self.memory_monitor = self
+            .limits
+            .memory_limit_bytes
+            .map(|memory_limit| start_memory_monitor(pid, memory_limit));
+    }
+
+    #[cfg(target_os = "linux")]
+    fn watch_memory(&mut self, _pid: u32) {}
+
+    /// The CPU time of the program while it runs, to stop it as soon as it exceeds the limit
+    #[cfg(target_os = "linux")]
+    fn sample_cpu_time(&self, pid: u32) -> Option<Duration> {
+        match &self.cgroup {
+            Some(cgroup) => cgroup.cpu_time(),
+            None => process_cpu_time(pid),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn sample_cpu_time(&self, _pid: u32) -> Option<Duration> {
         None
+    }
+
+    /// Stops monitoring the program once it exited, along with every process it left behind
+    fn finish(&mut self, exit: ChildExit) -> FinishedProgram {
+        let wall_time = self.start_time.elapsed();
+
+        #[cfg(not(target_os = "linux"))]
+        let killed_for_memory = self.memory_monitor.take().is_some_and(stop_memory_monitor);
+        #[cfg(not(target_os = "linux"))]
+        let (cgroup_cpu_time, cgroup_peak_memory, peak_processes) = (None, None, None);
+
+        #[cfg(target_os = "linux")]
+        let (killed_for_memory, cgroup_cpu_time, cgroup_peak_memory, peak_processes) =
+            match &self.cgroup {
+                Some(cgroup) => {
+                    // Leftover processes would keep the pipes open, and the cgroup busy
+                    cgroup.kill_all();
+                    let stats = cgroup.stats();
+                    (
+                        stats.oom_killed,
+                        stats.cpu_time,
+                        stats.peak_memory_bytes,
+                        stats.peak_processes,
+                    )
+                }
+                None => (false, None, None, None),
+            };
+
+        // The cgroup covers every process the program started, unlike wait4
+        let exit = ChildExit {
+            cpu_time: cgroup_cpu_time.or(exit.cpu_time),
+            peak_memory_bytes: cgroup_peak_memory.or(exit.peak_memory_bytes),
+            ..exit
+        };
+
+        FinishedProgram {
+            exit,
+            wall_time,
+            killed_for_memory,
+            peak_processes,
+            limits: self.limits,
+            resource_backend: self.resource_backend,
+            output_limit_exceeded: self.output_limit_exceeded.clone(),
+        }
+    }
+}
+
+/// A program which exited, described once its output is read
+struct FinishedProgram {
+    exit: ChildExit,
+    wall_time: Duration,
+    killed_for_memory: bool,
+    peak_processes: Option<u64>,
+    limits: ExecutionLimits,
+    resource_backend: ResourceBackend,
+    output_limit_exceeded: Arc<AtomicBool>,
+}
+
+impl FinishedProgram {
+    fn into_result(self, stdout: Vec<u8>, stderr: Vec<u8>) -> ExecutionResult {
+        let status = classify_exit(
+            &self.exit,
+            &String::from_utf8_lossy(&stderr),
+            &self.limits,
+            self.output_limit_exceeded.load(Ordering::Relaxed),
+            self.killed_for_memory,
+        );
+
+        ExecutionResult {
+            exit_code: self.exit.exit_code,
+            signal: self.exit.signal,
+            stdout,
+            stderr,
+            wall_time: self.wall_time,
+            cpu_time: self.exit.cpu_time,
+            peak_memory_bytes: self.exit.peak_memory_bytes,
+            peak_processes: self.peak_processes,
+            status,
+            resource_backend: self.resource_backend,
+        }
+    }
+}
+
+/// A spawned program, whose limits are enforced once waited for
+struct RunningProgram {
+    child: Child,
+    monitor: ProgramMonitor,
+    stdout_reader: Option<JoinHandle<Vec<u8>>>,
+    stderr_reader: Option<JoinHandle<Vec<u8>>>,
+    /// Whether the program was waited for, after which it is no longer killed once dropped
+    reaped: bool,
+}
+
+impl Drop for RunningProgram {
+    /// Kills and reaps a program that was never waited for (e.g. when setting up its input failed),
+    /// so that it neither keeps running nor is left as a zombie
+    fn drop(&mut self) {
+        if !self.reaped {
+            kill_process_tree(self.child.id());
+            let _ = self.child.wait();
+        }
+    }
+}
+
+/// A program spawned on the async runtime, killed along with every process it started when its run
+/// is cancelled. Tokio reaps it in the background once killed.
+struct AsyncProgram {
+    child: tokio::process::Child,
+}
+
+impl Drop for AsyncProgram {
+    fn drop(&mut self) {
+        // The pid is only known until the program is reaped
+        if let Some(pid) = self.child.id() {
+            kill_process_tree(pid);
+        }
+    }
+}
+
+/// Spawns the program with piped stdio, applying the limits which are set up before it starts.
+///
+/// Its stderr is drained right away, while its stdin and stdout are left to the caller.
+fn spawn_program(
+    program: &str,
+    args: &[&str],
+    limits: &ExecutionLimits,
+) -> io::Result<RunningProgram> {
+    let (mut child, monitor) = prepare_command(program, args, limits)?.spawn()?;
+
+    // Drain the pipes while waiting, so a chatty process can't block on a full pipe
+    let stderr_reader = child
+        .stderr
+        .take()
+        .map(|stderr| spawn_pipe_reader(stderr, None, Arc::new(AtomicBool::new(false))));
+
+    Ok(RunningProgram {
+        child,
+        monitor,
+        stdout_reader: None,
+        stderr_reader,
+        reaped: false,
+    })
+}
+
+impl RunningProgram {
+    /// Drains stdout into the result
+    fn capture_stdout(&mut self) {
+        self.stdout_reader = self.child.stdout.take().map(|stdout| {
+            spawn_pipe_reader(
+                stdout,
+                self.monitor.limits.output_limit_bytes,
+                self.monitor.output_limit_exceeded.clone(),
+            )
+        });
+    }
+
+    /// Drains stdout into the result, forwarding it to the other program's stdin as it comes
+    fn relay_stdout(
+        &mut self,
+        forward_to: Option<ChildStdin>,
+        on_output: impl FnMut(&[u8]) + Send + 'static,
+    ) {
+        self.stdout_reader = self.child.stdout.take().map(|stdout| {
+            spawn_pipe_relay(
+                stdout,
+                forward_to,
+                on_output,
+                self.monitor.limits.output_limit_bytes,
+                self.monitor.output_limit_exceeded.clone(),
+            )
+        });
+    }
+
+    /// Waits for the program to exit (or kills it once over a limit), and describes how it ended
+    fn wait(mut self) -> io::Result<ExecutionResult> {
+        let pid = self.child.id();
+        let monitor = &self.monitor;
+        let wait_limits = WaitLimits {
+            time_limit: monitor.limits.time_limit_ms.map(Duration::from_millis),
+            cpu_time_limit: monitor.limits.cpu_time_limit_ms.map(Duration::from_millis),
+            output_limit_exceeded: &monitor.output_limit_exceeded,
+        };
+        let exit = wait_for_exit(&mut self.child, &wait_limits, || {
+            monitor.sample_cpu_time(pid)
+        })?;
+        self.reaped = true;
+
+        let finished = self.monitor.finish(exit);
+        let stdout = join_pipe_reader(self.stdout_reader.take());
+        let stderr = join_pipe_reader(self.stderr_reader.take());
+        Ok(finished.into_result(stdout, stderr))
+    }
+}
+
+/// Messages runtimes print when an allocation fails, which under `RLIMIT_AS` means the memory
+/// limit was hit
+const OUT_OF_MEMORY_MARKERS: [&str; 6] = [
+    "MemoryError",               // Python
+    "std::bad_alloc",            // C++
+    "memory allocation of",      // Rust
+    "OutOfMemoryError",          // Java
+    "heap out of memory",        // JavaScript
+    "failed to allocate memory", // Ruby
+];
+
+fn classify_exit(
+    exit: &ChildExit,
+    stderr: &str,
+    limits: &ExecutionLimits,
+    output_limit_exceeded: bool,
+    killed_for_memory: bool,
+) -> ExecutionStatus {
+    // Checked first, a program over the CPU time limit has usually hit the wall clock limit too
+    if let Some(cpu_time_limit) = limits.cpu_time_limit_ms
+        && exit
+            .cpu_time
+            .is_some_and(|cpu_time| cpu_time >= Duration::from_millis(cpu_time_limit))
+    {
+        return ExecutionStatus::CpuTimeLimitExceeded;
+    }
+    if exit.timed_out {
+        return ExecutionStatus::TimeLimitExceeded;
+    }
+    if output_limit_exceeded {
+        return ExecutionStatus::OutputLimitExceeded;
+    }
+
+    let has_failed = exit.exit_code != Some(0);
+    if let Some(memory_limit) = limits.memory_limit_bytes
+        && has_failed
+        && (killed_for_memory
+            || exit
+                .peak_memory_bytes
+                .is_some_and(|peak| peak >= memory_limit)
+            || OUT_OF_MEMORY_MARKERS
+                .iter()
+                .any(|marker| stderr.contains(marker)))
+    {
+        return ExecutionStatus::MemoryLimitExceeded;
+    }
+
+    match (exit.exit_code, exit.signal) {
+        (Some(0), _) => ExecutionStatus::Success,
+        (Some(code), _) => ExecutionStatus::NonZeroExit(code),
+        (None, Some(signal)) => ExecutionStatus::Signaled(signal),
+        // Killed without a reported signal (only possible off Unix)
+        (None, None) => ExecutionStatus::NonZeroExit(-1),
+    }
+}
+
+/// Reads the pipe to the end, or until the limit is exceeded, in which case the flag is raised
+/// and the pipe is closed
+fn spawn_pipe_reader(
+    mut pipe: impl Read + Send + 'static,
+    limit_bytes: Option<u64>,
+    limit_exceeded: Arc<AtomicBool>,
+) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let Some(limit_bytes) = limit_bytes else {
+            let _ = pipe.read_to_end(&mut buf);
+            return buf;
+        };
+
+        // Read one byte past the limit, to tell an output of exactly the limit apart
+        let _ = (&mut pipe).take(limit_bytes + 1).read_to_end(&mut buf);
+        if buf.len() as u64 > limit_bytes {
+            buf.truncate(limit_bytes as usize);
+            limit_exceeded.store(true, Ordering::Relaxed);
+        }
+        buf
+    })
+}
+
+/// Reads the pipe like `spawn_pipe_reader`, while writing everything read to `forward_to` (if
+/// any) and passing it to `on_output`. Forwarding stops once the other end is closed.
+fn spawn_pipe_relay(
+    mut pipe: impl Read + Send + 'static,
+    forward_to: Option<impl Write + Send + 'static>,
+    mut on_output: impl FnMut(&[u8]) + Send + 'static,
+    limit_bytes: Option<u64>,
+    limit_exceeded: Arc<AtomicBool>,
+) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut forward_to = forward_to;
+        let mut buf = Vec::new();
+        let mut chunk = [0; 8192];
+
+        loop {
+            let read = match pipe.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            };
+            let data = &chunk[..read];
+
+            if let Some(limit_bytes) = limit_bytes
+                && (buf.len() + read) as u64 > limit_bytes
+            {
+                buf.extend_from_slice(&data[..limit_bytes as usize - buf.len()]);
+                limit_exceeded.store(true, Ordering::Relaxed);
+                break;
+            }
+            buf.extend_from_slice(data);
+            on_output(data);
+
+            // Flushed right away, the other program is waiting for it
+            if let Some(writer) = forward_to.as_mut()
+                && writer
+                    .write_all(data)
+                    .and_then(|()| writer.flush())
+                    .is_err()
+            {
+                forward_to = None;
+            }
+        }
+        buf
+    })
+}
+
+fn join_pipe_reader(reader: Option<JoinHandle<Vec<u8>>>) -> Vec<u8> {
+    reader
+        .and_then(|reader| reader.join().ok())
+        .unwrap_or_default()
+}
+
+/// Same as `spawn_pipe_reader`, on the async runtime
+async fn read_pipe_async(
+    pipe: Option<impl AsyncRead + Unpin>,
+    limit_bytes: Option<u64>,
+    limit_exceeded: &AtomicBool,
+) -> Vec<u8> {
+    let mut buf = Vec::new();
+    let Some(mut pipe) = pipe else {
+        return buf;
+    };
+    let Some(limit_bytes) = limit_bytes else {
+        let _ = pipe.read_to_end(&mut buf).await;
+        return buf;
+    };
+
+    // Read one byte past the limit, to tell an output of exactly the limit apart
+    let _ = (&mut pipe)
+        .take(limit_bytes + 1)
+        .read_to_end(&mut buf)
+        .await;
+    if buf.len() as u64 > limit_bytes {
+        buf.truncate(limit_bytes as usize);
+        limit_exceeded.store(true, Ordering::Relaxed);
+    }
+    buf
+}
+
+/// Limits enforced while waiting for a child process
+struct WaitLimits<'a> {
+    time_limit: Option<Duration>,
+    cpu_time_limit: Option<Duration>,
+    output_limit_exceeded: &'a AtomicBool,
+}
+
+/// How often the CPU time of a running child is sampled
+const CPU_TIME_SAMPLE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How a child process ended, along with its resource usage where the platform reports it
+struct ChildExit {
+    exit_code: Option<i32>,
+    signal: Option<i32>,
+    cpu_time: Option<Duration>,
+    peak_memory_bytes: Option<u64>,
+    timed_out: bool,
+}
+
+// On Unix the child is reaped with wait4 instead of std's waitpid, as only wait4 reports the
+// resource usage (CPU time, peak RSS) of that particular child.
+#[cfg(unix)]
+fn wait_for_exit(
+    child: &mut Child,
+    limits: &WaitLimits,
+    sample_cpu_time: impl Fn() -> Option<Duration>,
+) -> io::Result<ChildExit> {
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+
+    let pid = child.id() as libc::pid_t;
+    let deadline = limits
+        .time_limit
+        .map(|time_limit| Instant::now() + time_limit);
+    let mut next_cpu_time_sample = Instant::now();
+    let mut timed_out = false;
+    let mut is_killed = false;
+
+    let (status, usage) = loop {
+        if is_killed || has_exited(pid)? {
+            // Processes left behind in the group would keep the output pipes open. They are
+            // killed before the child is reaped, while its pid cannot be reused yet.
+            kill_process_group(pid as u32);
+            break reap(pid)?;
+        }
+
+        // Still running
+        let now = Instant::now();
+        if deadline.is_some_and(|deadline| now >= deadline) {
+            kill_process_tree(child.id());
+            timed_out = true;
+            is_killed = true;
+        } else if limits.output_limit_exceeded.load(Ordering::Relaxed) {
+            kill_process_tree(child.id());
+            is_killed = true;
+        } else if let Some(cpu_time_limit) = limits.cpu_time_limit
+            && now >= next_cpu_time_sample
+            && sample_cpu_time().is_some_and(|cpu_time| cpu_time >= cpu_time_limit)
+        {
+            // Reported from the CPU time measured once reaped
+            kill_process_tree(child.id());
+            is_killed = true;
+        } else {
+            if now >= next_cpu_time_sample {
+                next_cpu_time_sample = now + CPU_TIME_SAMPLE_INTERVAL;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
     };
 
-    if let Some(mut stdin) = child.stdin.take() {
-        // Close stdin to finish and avoid indefinite blocking
-        stdin.write_all(stdin_content.as_ref())?; // drop would happen here
+    Ok(ChildExit {
+        timed_out,
+        ..ChildExit::from_usage(ExitStatus::from_raw(status), &usage)
+    })
+}
+
+#[cfg(unix)]
+impl ChildExit {
+    /// How the child ended, along with the resource usage reported by `wait4` (or `waitid`)
+    fn from_usage(status: std::process::ExitStatus, usage: &libc::rusage) -> Self {
+        use std::os::unix::process::ExitStatusExt;
+
+        let cpu_time = timeval_to_duration(usage.ru_utime) + timeval_to_duration(usage.ru_stime);
+
+        // ru_maxrss is reported in bytes on macOS, and in kilobytes elsewhere
+        #[cfg(target_os = "macos")]
+        let peak_memory_bytes = usage.ru_maxrss as u64;
+        #[cfg(not(target_os = "macos"))]
+        let peak_memory_bytes = usage.ru_maxrss as u64 * 1024;
+
+        ChildExit {
+            exit_code: status.code(),
+            signal: status.signal(),
+            cpu_time: Some(cpu_time),
+            peak_memory_bytes: Some(peak_memory_bytes),
+            timed_out: false,
+        }
     }
+}
 
-    // Handle timeout if specified
-    let output = if let Some(time_limit_ms) = limits.time_limit_ms {
-        let timeout = Duration::from_millis(time_limit_ms);
-        match child.wait_timeout(timeout)? {
-            Some(status) => {
-                // Process exited before timeout
-                #[cfg(not(target_os = "linux"))]
-                if let Some(monitor) = memory_monitor {
-                    stop_memory_monitor(monitor);
-                }
+/// Same as `wait_for_exit` on the async runtime, writing stdin while the child runs. The output
+/// limit is only checked once `output_limit_reached` is notified.
+async fn wait_for_exit_async(
+    child: &mut tokio::process::Child,
+    limits: &WaitLimits<'_>,
+    output_limit_reached: &Notify,
+    stdin_writer: impl Future<Output = io::Result<()>>,
+    sample_cpu_time: impl Fn(u32) -> Option<Duration>,
+) -> io::Result<ChildExit> {
+    let pid = child
+        .id()
+        .ok_or_else(|| io::Error::other("The program was already waited for"))?;
+    let deadline = async {
+        match limits.time_limit {
+            Some(time_limit) => tokio::time::sleep(time_limit).await,
+            None => std::future::pending().await,
+        }
+    };
+    let mut cpu_time_samples = tokio::time::interval(CPU_TIME_SAMPLE_INTERVAL);
+    let mut stdin_result = None;
+    let mut timed_out = false;
+    let mut is_killed = false;
+
+    tokio::pin!(deadline, stdin_writer);
+    let reaped = reap_async(child, pid);
+    tokio::pin!(reaped);
 
-                let stdout = child
-                    .stdout
-                    .take()
-                    .map(|mut s| {
-                        let mut buf = Vec::new();
-                        let _ = std::io::Read::read_to_end(&mut s, &mut buf);
-                        buf
-                    })
-                    .unwrap_or_default();
-
-                let stderr = child
-                    .stderr
-                    .take()
-                    .map(|mut s| {
-                        let mut buf = Vec::new();
-                        let _ = std::io::Read::read_to_end(&mut s, &mut buf);
-                        buf
-                    })
-                    .unwrap_or_default();
-
-                Output {
-                    status,
-                    stdout,
-                    stderr,
+    let exit = loop {
+        tokio::select! {
+            exit = &mut reaped => match exit {
+                Ok(exit) => break exit,
+                // Killed, as its output is read until it exits
+                Err(err) => {
+                    kill_process_tree(pid);
+                    return Err(err);
                 }
+            },
+            result = &mut stdin_writer, if stdin_result.is_none() => stdin_result = Some(result),
+            () = &mut deadline, if !is_killed => {
+                kill_process_tree(pid);
+                timed_out = true;
+                is_killed = true;
             }
-            None => {
-                // Timeout occurred, kill the process
-                #[cfg(not(target_os = "linux"))]
-                if let Some(monitor) = memory_monitor {
-                    stop_memory_monitor(monitor);
+            () = output_limit_reached.notified(), if !is_killed => {
+                kill_process_tree(pid);
+                is_killed = true;
+            }
+            _ = cpu_time_samples.tick(), if !is_killed && limits.cpu_time_limit.is_some() => {
+                if let Some(cpu_time_limit) = limits.cpu_time_limit
+                    && sample_cpu_time(pid).is_some_and(|cpu_time| cpu_time >= cpu_time_limit)
+                {
+                    // Reported from the CPU time measured once exited
+                    kill_process_tree(pid);
+                    is_killed = true;
                 }
-
-                let _ = child.kill();
-                return Err(io::Error::other(format!(
-                    "Process `{} {}` exceeded time limit of {} ms",
-                    program,
-                    args.join(" "),
-                    time_limit_ms
-                )));
             }
         }
-    } else {
-        let output = child.wait_with_output()?;
+    };
 
-        #[cfg(not(target_os = "linux"))]
-        if let Some(monitor) = memory_monitor {
-            stop_memory_monitor(monitor);
+    // The program is gone, so the stdin writer is done unless a process it left behind holds stdin
+    stdin_result.transpose()?;
+    Ok(ChildExit { timed_out, ..exit })
+}
+
+/// Waits for the child to exit and reaps it, along with its resource usage.
+///
+/// The usage is read with `waitid(WNOWAIT)` before tokio reaps the child, as tokio's own wait only
+/// reports the exit status.
+#[cfg(target_os = "linux")]
+async fn reap_async(child: &mut tokio::process::Child, pid: u32) -> io::Result<ChildExit> {
+    use std::os::fd::{FromRawFd, OwnedFd};
+    use tokio::io::Interest;
+    use tokio::io::unix::AsyncFd;
+
+    // A pidfd becomes readable once the process exits (pidfds need Linux 5.3, polled otherwise)
+    let pidfd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+    if pidfd >= 0 {
+        let pidfd = unsafe { OwnedFd::from_raw_fd(pidfd as i32) };
+        let pidfd = AsyncFd::with_interest(pidfd, Interest::READABLE)?;
+        let _ = pidfd.readable().await?;
+    }
+
+    let usage = loop {
+        if let Some(usage) = exited_child_usage(pid)? {
+            break usage;
         }
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    };
+
+    // Leftover processes would keep the pipes open. Killed before the child is reaped, as its
+    // process group could otherwise be reused.
+    kill_process_group(pid);
 
-        output
+    let status = child.wait().await?;
+    Ok(ChildExit::from_usage(status, &usage))
+}
+
+/// CPU time and peak memory are not reported here, tokio only reports the exit status
+#[cfg(not(target_os = "linux"))]
+async fn reap_async(child: &mut tokio::process::Child, _pid: u32) -> io::Result<ChildExit> {
+    let status = child.wait().await?;
+
+    #[cfg(unix)]
+    let signal = std::os::unix::process::ExitStatusExt::signal(&status);
+    #[cfg(not(unix))]
+    let signal = None;
+
+    Ok(ChildExit {
+        exit_code: status.code(),
+        signal,
+        cpu_time: None,
+        peak_memory_bytes: None,
+        timed_out: false,
+    })
+}
+
+/// The resource usage of the child once it exited, leaving it to be reaped, or None while it runs
+#[cfg(target_os = "linux")]
+fn exited_child_usage(pid: u32) -> io::Result<Option<libc::rusage>> {
+    let mut info = std::mem::MaybeUninit::<libc::siginfo_t>::zeroed();
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::zeroed();
+
+    loop {
+        // Unlike the libc wrapper, the system call also reports the resource usage
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_waitid,
+                libc::P_PID,
+                pid as libc::pid_t,
+                info.as_mut_ptr(),
+                libc::WEXITED | libc::WNOWAIT | libc::WNOHANG,
+                usage.as_mut_ptr(),
+            )
+        };
+        if ret == 0 {
+            break;
+        }
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+
+    // The pid is left zeroed while the child is still running
+    let info = unsafe { info.assume_init() };
+    if unsafe { info.si_pid() } == 0 {
+        return Ok(None);
+    }
+    Ok(Some(unsafe { usage.assume_init() }))
+}
+
+#[cfg(unix)]
+fn timeval_to_duration(time: libc::timeval) -> Duration {
+    Duration::from_secs(time.tv_sec as u64) + Duration::from_micros(time.tv_usec as u64)
+}
+
+// Checks whether the child exited, leaving it waitable
+#[cfg(unix)]
+fn has_exited(pid: libc::pid_t) -> io::Result<bool> {
+    loop {
+        let mut info = std::mem::MaybeUninit::<libc::siginfo_t>::zeroed();
+        let ret = unsafe {
+            libc::waitid(
+                libc::P_PID,
+                pid as libc::id_t,
+                info.as_mut_ptr(),
+                libc::WEXITED | libc::WNOHANG | libc::WNOWAIT,
+            )
+        };
+
+        if ret == 0 {
+            // si_pid stays zero while the child is still running
+            return Ok(unsafe { info.assume_init().si_pid() } == pid);
+        }
+
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+}
+
+// Blocks until the child exits and reaps it
+#[cfg(unix)]
+fn reap(pid: libc::pid_t) -> io::Result<(libc::c_int, libc::rusage)> {
+    loop {
+        let mut status: libc::c_int = 0;
+        let mut usage = std::mem::MaybeUninit::<libc::rusage>::zeroed();
+        let ret = unsafe { libc::wait4(pid, &mut status, 0, usage.as_mut_ptr()) };
+
+        if ret == pid {
+            return Ok((status, unsafe { usage.assume_init() }));
+        }
+
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+}
+
+// CPU time is neither sampled nor reported here, so CPU time limits are not enforced
+#[cfg(not(unix))]
+fn wait_for_exit(
+    child: &mut Child,
+    limits: &WaitLimits,
+    _sample_cpu_time: impl Fn() -> Option<Duration>,
+) -> io::Result<ChildExit> {
+    use wait_timeout::ChildExt;
+
+    let deadline = limits
+        .time_limit
+        .map(|time_limit| Instant::now() + time_limit);
+
+    // Wake up periodically to check the output limit, until the deadline (if any)
+    let (status, timed_out) = loop {
+        let poll_interval = match deadline {
+            Some(deadline) => deadline
+                .saturating_duration_since(Instant::now())
+                .min(Duration::from_millis(10)),
+            None => Duration::from_millis(10),
+        };
+
+        if let Some(status) = child.wait_timeout(poll_interval)? {
+            break (status, false);
+        }
+
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            // Timeout occurred, kill the process
+            kill_process_tree(child.id());
+            break (child.wait()?, true);
+        }
+
+        if limits.output_limit_exceeded.load(Ordering::Relaxed) {
+            kill_process_tree(child.id());
+            break (child.wait()?, false);
+        }
     };
 
-    run_program_common(output, program, args)
+    Ok(ChildExit {
+        exit_code: status.code(),
+        signal: None,
+        cpu_time: None,
+        peak_memory_bytes: None,
+        timed_out,
+    })
+}
+
+/// Kills the process along with every process it started, which would otherwise outlive it (and
+/// keep its pipes open). The process must not have been reaped yet.
+///
+/// Its process group covers the processes it started, even once they are reparented (e.g. after a
+/// double fork). Those which left the group (e.g. with `setsid`) are found among its descendants
+/// before killing it, as they are reparented once it dies.
+fn kill_process_tree(pid: u32) {
+    let descendants = descendant_pids(pid);
+    kill_process_group(pid);
+    kill_pid(pid);
+    for pid in descendants {
+        kill_pid(pid);
+    }
+}
+
+/// Makes the child the leader of a new process group, which is joined by the processes it starts
+#[cfg(unix)]
+fn start_process_group(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setpgid(0, 0) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Kills the process group led by the process
+#[cfg(unix)]
+fn kill_process_group(pid: u32) {
+    unsafe {
+        libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(_pid: u32) {}
+
+fn descendant_pids(root_pid: u32) -> Vec<u32> {
+    let parent_pids = parent_pids();
+    let mut descendants = Vec::new();
+    let mut parents = vec![root_pid];
+
+    while let Some(parent) = parents.pop() {
+        for &(pid, parent_pid) in &parent_pids {
+            if parent_pid == parent && pid != root_pid && !descendants.contains(&pid) {
+                descendants.push(pid);
+                parents.push(pid);
+            }
+        }
+    }
+    descendants
+}
+
+/// Every running process along with its parent, from `/proc/<pid>/stat`
+#[cfg(target_os = "linux")]
+fn parent_pids() -> Vec<(u32, u32)> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid = entry.file_name().to_str()?.parse().ok()?;
+            let stat = std::fs::read_to_string(entry.path().join("stat")).ok()?;
+            // The parent pid is the field after the state, which follows the command name
+            let parent_pid = stat.rsplit_once(')')?.1.split_whitespace().nth(1)?;
+            Some((pid, parent_pid.parse().ok()?))
+        })
+        .collect()
+}
+
+/// Every running process along with its parent
+#[cfg(not(target_os = "linux"))]
+fn parent_pids() -> Vec<(u32, u32)> {
+    use sysinfo::{ProcessesToUpdate, System};
+
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::All, true);
+    sys.processes()
+        .iter()
+        .filter_map(|(pid, process)| Some((pid.as_u32(), process.parent()?.as_u32())))
+        .collect()
+}
+
+#[cfg(unix)]
+fn kill_pid(pid: u32) {
+    unsafe {
+        libc::kill(pid as libc::pid_t, libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_pid(pid: u32) {
+    use sysinfo::{Pid, ProcessesToUpdate, System};
+
+    let pid = Pid::from_u32(pid);
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+    if let Some(process) = sys.process(pid) {
+        process.kill();
+    }
+}
+
+/// Reads the user and system CPU time of a running process from `/proc/<pid>/stat`
+#[cfg(target_os = "linux")]
+fn process_cpu_time(pid: u32) -> Option<Duration> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+
+    // The command name may contain spaces, the fields after it start with the state (field 3),
+    // followed by utime and stime at fields 14 and 15, in clock ticks
+    let mut fields = stat.rsplit_once(')')?.1.split_whitespace().skip(11);
+    let utime: u64 = fields.next()?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+
+    let ticks_per_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks_per_second <= 0 {
+        return None;
+    }
+    Some(Duration::from_secs_f64(
+        (utime + stime) as f64 / ticks_per_second as f64,
+    ))
+}
+
+/// Sets `RLIMIT_CPU`, which kills the process (SIGXCPU, then SIGKILL a second later) once it
+/// uses the limit rounded up to whole seconds, in case it isn't stopped sooner
+#[cfg(unix)]
+fn apply_cpu_time_limit(command: &mut Command, cpu_time_limit_ms: u64) {
+    use std::os::unix::process::CommandExt;
+
+    let soft_limit_secs = cpu_time_limit_ms.div_ceil(1000).max(1);
+
+    unsafe {
+        command.pre_exec(move || {
+            let limit = libc::rlimit {
+                rlim_cur: soft_limit_secs as libc::rlim_t,
+                rlim_max: (soft_limit_secs + 1) as libc::rlim_t,
+            };
+
+            if libc::setrlimit(libc::RLIMIT_CPU, &limit) != 0 {
+                eprintln!("Warning: Failed to set CPU time limit");
+            }
+
+            Ok(())
+        });
+    }
 }
 
 #[cfg(target_os = "linux")]
@@ -210,8 +1400,8 @@ fn apply_memory_limit(command: &mut Command, memory_limit_bytes: u64) {
 //   - Windows: RSS via sysinfo (best available cross-platform approximation)
 #[cfg(not(target_os = "linux"))]
 struct MemoryMonitor {
-    should_stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
-    handle: Option<std::thread::JoinHandle<()>>,
+    should_stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<bool>>,
 }
 
 /// Reads the physical memory footprint of a process on macOS using
@@ -297,10 +1487,6 @@ fn get_phys_footprint(pid: u32) -> Option<u64> {
 
 #[cfg(not(target_os = "linux"))]
 fn start_memory_monitor(pid: u32, memory_limit_bytes: u64) -> MemoryMonitor {
-    use std::sync::Arc;
-    use std::sync::atomic::{AtomicBool, Ordering};
-    use std::thread;
-
     let should_stop = Arc::new(AtomicBool::new(false));
     let should_stop_clone = should_stop.clone();
 
@@ -348,11 +1534,11 @@ fn start_memory_monitor(pid: u32, memory_limit_bytes: u64) -> MemoryMonitor {
                         "Process {} terminated: memory usage {} bytes exceeded limit of {} bytes",
                         pid, usage, memory_limit_bytes
                     );
-                    break;
+                    return true;
                 }
                 None => {
                     // Process has already exited
-                    break;
+                    return false;
                 }
                 _ => {}
             }
@@ -360,6 +1546,8 @@ fn start_memory_monitor(pid: u32, memory_limit_bytes: u64) -> MemoryMonitor {
             // Poll every 5ms — balances CPU overhead vs. overshoot window
             thread::sleep(Duration::from_millis(5));
         }
+
+        false
     });
 
     MemoryMonitor {
@@ -368,129 +1556,34 @@ fn start_memory_monitor(pid: u32, memory_limit_bytes: u64) -> MemoryMonitor {
     }
 }
 
+/// Stops the monitor, returning true if it killed the process for exceeding the memory limit
 #[cfg(not(target_os = "linux"))]
-fn stop_memory_monitor(mut monitor: MemoryMonitor) {
-    use std::sync::atomic::Ordering;
-
+fn stop_memory_monitor(mut monitor: MemoryMonitor) -> bool {
     monitor.should_stop.store(true, Ordering::Relaxed);
 
-    if let Some(handle) = monitor.handle.take() {
-        let _ = handle.join();
-    }
+    monitor
+        .handle
+        .take()
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or(false)
 }
 
 /// Adapted with modifications from GNU Make Project
 /// * `source_code_path` : Path of source code
 /// * `compiled_artifact_path` : The name of compiled artifact, generally file-stem name of `source_code_path`
 ///   Returns true if file needs to be recompiled
+///
+/// Like make, compares modification times, as creation (birth) time isn't available on every
+/// filesystem.
 pub(crate) fn remake(
     source_code_path: &Path,
     compiled_artifact_path: &Path,
 ) -> Result<bool, io::Error> {
     if compiled_artifact_path.exists() {
         let source_modified_time = source_code_path.metadata()?.modified()?;
-        let compiled_artifact_creation_time = compiled_artifact_path.metadata()?.created()?;
+        let compiled_artifact_modified_time = compiled_artifact_path.metadata()?.modified()?;
 
-        return Ok(source_modified_time > compiled_artifact_creation_time);
+        return Ok(source_modified_time > compiled_artifact_modified_time);
     }
     Ok(true)
 }
-
-pub(crate) fn run_program(
-    program: &str,
-    args: &Vec<&str>,
-    limits: &ExecutionLimits,
-) -> io::Result<String> {
-    if let Err(err) = program_exists(program) {
-        return Err(io::Error::other(err));
-    }
-
-    let mut command = Command::new(program);
-    command
-        .args(args)
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
-
-    // Apply memory limit via setrlimit on Linux only (see run_program_with_input for rationale)
-    #[cfg(target_os = "linux")]
-    if let Some(memory_limit) = limits.memory_limit_bytes {
-        apply_memory_limit(&mut command, memory_limit);
-    }
-
-    let mut child = command.spawn()?;
-
-    // Active memory monitor for macOS and Windows
-    #[cfg(not(target_os = "linux"))]
-    let memory_monitor = if let Some(memory_limit) = limits.memory_limit_bytes {
-        Some(start_memory_monitor(child.id(), memory_limit))
-    } else {
-        None
-    };
-
-    // Handle timeout if specified
-    let output = if let Some(time_limit_ms) = limits.time_limit_ms {
-        let timeout = Duration::from_millis(time_limit_ms);
-        match child.wait_timeout(timeout)? {
-            Some(status) => {
-                // Process exited before timeout
-                #[cfg(not(target_os = "linux"))]
-                if let Some(monitor) = memory_monitor {
-                    stop_memory_monitor(monitor);
-                }
-
-                let stdout = child
-                    .stdout
-                    .take()
-                    .map(|mut s| {
-                        let mut buf = Vec::new();
-                        let _ = std::io::Read::read_to_end(&mut s, &mut buf);
-                        buf
-                    })
-                    .unwrap_or_default();
-
-                let stderr = child
-                    .stderr
-                    .take()
-                    .map(|mut s| {
-                        let mut buf = Vec::new();
-                        let _ = std::io::Read::read_to_end(&mut s, &mut buf);
-                        buf
-                    })
-                    .unwrap_or_default();
-
-                Output {
-                    status,
-                    stdout,
-                    stderr,
-                }
-            }
-            None => {
-                // Timeout occurred, kill the process
-                #[cfg(not(target_os = "linux"))]
-                if let Some(monitor) = memory_monitor {
-                    stop_memory_monitor(monitor);
-                }
-
-                let _ = child.kill();
-                return Err(io::Error::other(format!(
-                    "Process `{} {}` exceeded time limit of {} ms",
-                    program,
-                    args.join(" "),
-                    time_limit_ms
-                )));
-            }
-        }
-    } else {
-        let output = child.wait_with_output()?;
-
-        #[cfg(not(target_os = "linux"))]
-        if let Some(monitor) = memory_monitor {
-            stop_memory_monitor(monitor);
-        }
-
-        output
-    };
-
-    run_program_common(output, program, args)
-}
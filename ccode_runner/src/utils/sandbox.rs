@@ -0,0 +1,736 @@
+//! Linux sandbox for running untrusted programs
+//!
+//! The sandboxed program runs with:
+//! - User, mount, PID, network, IPC and UTS namespaces, so it can't see or signal other processes,
+//!   reach the network, or act as the host user (it runs as `nobody` inside the user namespace)
+//! - No supplementary groups, when the runner is allowed to drop them (e.g. as root)
+//! - A minimal, read-only root filesystem, holding only the system directories (`/usr`, `/lib`,
+//!   ...), the toolchain running the program and the program's own directory. Its `/proc` only
+//!   shows the sandbox's processes, and is left empty where the host's `/proc` is partly hidden
+//!   (e.g. in Docker containers), which breaks runtimes locating themselves through it (e.g. Java)
+//! - Only the `PATH`, locale and time zone variables of the runner's environment
+//! - A seccomp filter denying new processes, sockets (apart from connected pairs) and privileged
+//!   syscalls (mounting, namespaces, ptrace, BPF, io_uring, kernel modules, ...)
+//! - Thread (`RLIMIT_NPROC`) and file size (`RLIMIT_FSIZE`) limits
+//!
+//! As the program is PID 1 of its PID namespace, signals it raises on itself (e.g. `abort()`) are
+//! ignored, and it is usually terminated by the fallback of its runtime instead.
+//!
+//! # Platform Support
+//!
+//! Linux 5.12+ on x86_64 and aarch64 only. Requesting a sandbox elsewhere fails the execution,
+//! rather than silently running the program unsandboxed.
+
+use std::io;
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Configuration of the sandbox for running untrusted programs
+#[derive(Debug, Clone, Copy)]
+pub struct SandboxConfig {
+    /// Maximum number of threads of the program (new processes are never allowed)
+    ///
+    /// Enforced with `RLIMIT_NPROC`, which the kernel doesn't apply when the host user is root
+    pub max_threads: u64,
+    /// Maximum size in bytes of a file written by the program
+    pub max_file_size_bytes: u64,
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            max_threads: 64,
+            max_file_size_bytes: 0,
+        }
+    }
+}
+
+impl SandboxConfig {
+    /// Create a sandbox configuration with the default limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set maximum number of threads
+    pub fn with_max_threads(mut self, max_threads: u64) -> Self {
+        self.max_threads = max_threads;
+        self
+    }
+
+    /// Set maximum file size in bytes
+    pub fn with_max_file_size(mut self, max_file_size_bytes: u64) -> Self {
+        self.max_file_size_bytes = max_file_size_bytes;
+        self
+    }
+
+    /// Check that programs can run inside the sandbox on this system, by running `true` in it
+    ///
+    /// The sandbox needs unprivileged user namespaces, which some systems disable (e.g. the
+    /// `kernel.unprivileged_userns_clone` sysctl) and some container runtimes block (e.g. Docker's
+    /// default seccomp profile).
+    pub fn check_available(&self) -> io::Result<()> {
+        let mut command = Command::new("true");
+        apply_sandbox(&mut command, self)?;
+
+        let status = command.status()?;
+        if !status.success() {
+            return Err(io::Error::other(format!(
+                "Program in the sandbox failed with {status}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+pub(crate) fn apply_sandbox(_command: &mut Command, _config: &SandboxConfig) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Sandbox is only supported on Linux (x86_64 and aarch64)",
+    ))
+}
+
+/// Sets up the command to run inside the sandbox.
+///
+/// Everything the child needs is prepared here, as the `pre_exec` hook runs after `fork` in a
+/// possibly multi-threaded parent and must not allocate.
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub(crate) fn apply_sandbox(command: &mut Command, config: &SandboxConfig) -> io::Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    let (uid, gid) = unsafe { (libc::geteuid(), libc::getegid()) };
+    let uid_map = format!("{SANDBOX_ID} {uid} 1");
+    let gid_map = format!("{SANDBOX_ID} {gid} 1");
+    let root = linux::RootLayout::new(&root_paths(command))?;
+    let filter = linux::build_seccomp_filter();
+    let config = *config;
+
+    restrict_environment(command);
+
+    unsafe {
+        command.pre_exec(move || {
+            // Only possible with the privileges the runner has outside the user namespace
+            linux::drop_supplementary_groups()?;
+            linux::enter_namespaces(uid_map.as_bytes(), gid_map.as_bytes())?;
+            linux::set_rlimit(libc::RLIMIT_CORE, 0)?;
+
+            // Returns only in the forked child, which is PID 1 of the new PID namespace
+            linux::fork_into_pid_namespace()?;
+
+            // In the child, so that its /proc shows the processes of the new PID namespace
+            linux::enter_root(&root)?;
+            linux::make_filesystem_read_only()?;
+
+            // Account for the intermediate process, which shares the user namespace
+            linux::set_rlimit(libc::RLIMIT_NPROC, config.max_threads.saturating_add(1))?;
+            linux::set_rlimit(libc::RLIMIT_FSIZE, config.max_file_size_bytes)?;
+            linux::install_seccomp_filter(&filter)
+        });
+    }
+
+    Ok(())
+}
+
+/// User and group ID of the program inside the user namespace (`nobody`), so it holds no
+/// capabilities once exec'd
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+const SANDBOX_ID: u32 = 65534;
+
+/// Variables of the runner's environment passed on to the program, the others may hold secrets
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+const ENVIRONMENT: &[&str] = &["PATH", "LANG", "LC_ALL", "LC_CTYPE", "TZ"];
+
+/// Host paths bound into the sandbox's root, if they exist. Most of `/etc` stays hidden.
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+const SYSTEM_PATHS: &[&str] = &[
+    "/usr",
+    "/bin",
+    "/sbin",
+    "/lib",
+    "/lib32",
+    "/lib64",
+    "/libx32",
+    "/opt",
+    "/etc/alternatives",
+    "/etc/ld.so.cache",
+    "/etc/ld.so.conf",
+    "/etc/ld.so.conf.d",
+    "/etc/localtime",
+    "/dev/null",
+    "/dev/zero",
+    "/dev/random",
+    "/dev/urandom",
+];
+
+/// Replaces the inherited environment with the variables in `ENVIRONMENT`, keeping the ones set
+/// on the command itself
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+fn restrict_environment(command: &mut Command) {
+    let explicit: Vec<_> = command
+        .get_envs()
+        .filter_map(|(name, value)| Some((name.to_owned(), value?.to_owned())))
+        .collect();
+
+    command.env_clear();
+    for name in ENVIRONMENT {
+        if let Some(value) = std::env::var_os(name) {
+            command.env(name, value);
+        }
+    }
+    command.envs(explicit);
+}
+
+/// Host paths the program needs: the system's, the toolchain running it (the parent of the `bin`
+/// directory holding the interpreter, e.g. a JDK) and the files it is run with (e.g. the
+/// executable, or the script passed to an interpreter)
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+fn root_paths(command: &Command) -> Vec<PathBuf> {
+    let mut paths: Vec<_> = SYSTEM_PATHS.iter().map(PathBuf::from).collect();
+
+    // Configuration of Debian's JDKs, which their `conf` directories link to
+    if let Ok(entries) = std::fs::read_dir("/etc") {
+        paths.extend(
+            entries
+                .flatten()
+                .filter(|entry| entry.file_name().to_string_lossy().starts_with("java"))
+                .map(|entry| entry.path()),
+        );
+    }
+
+    if let Ok(program) = which::which(command.get_program()) {
+        paths.extend(program.parent().map(Path::to_path_buf));
+        if let Ok(program) = program.canonicalize()
+            && let Some(dir) = program.parent()
+        {
+            match dir.parent() {
+                Some(toolchain) if dir.ends_with("bin") => paths.push(toolchain.to_path_buf()),
+                _ => paths.push(dir.to_path_buf()),
+            }
+        }
+    }
+
+    for path in std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(Path::new)
+    {
+        if !path.is_absolute() {
+            continue;
+        }
+        if let Ok(path) = path.canonicalize() {
+            match path.parent() {
+                Some(parent) if !path.is_dir() => paths.push(parent.to_path_buf()),
+                _ => paths.push(path),
+            }
+        }
+    }
+
+    paths
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod linux {
+    use std::collections::BTreeSet;
+    use std::ffi::{CStr, CString, OsStr};
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::{Path, PathBuf};
+
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: u32 = 0xC000_003E; // AUDIT_ARCH_X86_64
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: u32 = 0xC000_00B7; // AUDIT_ARCH_AARCH64
+
+    /// Offsets into `struct seccomp_data`
+    const SECCOMP_DATA_NR: u32 = 0;
+    const SECCOMP_DATA_ARCH: u32 = 4;
+    const SECCOMP_DATA_ARG0_LOW: u32 = 16; // Lower half of args[0], on little endian
+
+    /// Syscalls which can escape or weaken the sandbox, or tamper with the host
+    const DENIED_SYSCALLS: &[libc::c_long] = &[
+        libc::SYS_mount,
+        libc::SYS_umount2,
+        libc::SYS_pivot_root,
+        libc::SYS_chroot,
+        libc::SYS_unshare,
+        libc::SYS_setns,
+        libc::SYS_ptrace,
+        libc::SYS_process_vm_readv,
+        libc::SYS_process_vm_writev,
+        libc::SYS_keyctl,
+        libc::SYS_add_key,
+        libc::SYS_request_key,
+        libc::SYS_bpf,
+        libc::SYS_perf_event_open,
+        libc::SYS_userfaultfd,
+        libc::SYS_io_uring_setup,
+        libc::SYS_io_uring_enter,
+        libc::SYS_io_uring_register,
+        libc::SYS_kexec_load,
+        libc::SYS_kexec_file_load,
+        libc::SYS_init_module,
+        libc::SYS_finit_module,
+        libc::SYS_delete_module,
+        libc::SYS_reboot,
+        libc::SYS_swapon,
+        libc::SYS_swapoff,
+        libc::SYS_acct,
+        libc::SYS_settimeofday,
+        libc::SYS_clock_settime,
+        libc::SYS_clock_adjtime,
+        libc::SYS_adjtimex,
+        libc::SYS_sethostname,
+        libc::SYS_setdomainname,
+        libc::SYS_open_by_handle_at,
+        libc::SYS_name_to_handle_at,
+        libc::SYS_fsopen,
+        libc::SYS_fsconfig,
+        libc::SYS_fsmount,
+        libc::SYS_fspick,
+        libc::SYS_move_mount,
+        libc::SYS_open_tree,
+        libc::SYS_mount_setattr,
+        libc::SYS_quotactl,
+        #[cfg(target_arch = "x86_64")]
+        libc::SYS_iopl,
+        #[cfg(target_arch = "x86_64")]
+        libc::SYS_ioperm,
+        #[cfg(target_arch = "x86_64")]
+        libc::SYS_uselib,
+    ];
+
+    /// Syscalls which only create new processes
+    #[cfg(target_arch = "x86_64")]
+    const PROCESS_SYSCALLS: &[libc::c_long] = &[libc::SYS_fork, libc::SYS_vfork];
+    #[cfg(target_arch = "aarch64")]
+    const PROCESS_SYSCALLS: &[libc::c_long] = &[];
+
+    fn cvt(ret: libc::c_int) -> io::Result<libc::c_int> {
+        if ret == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(ret)
+        }
+    }
+
+    fn write_file(path: &CStr, content: &[u8]) -> io::Result<()> {
+        unsafe {
+            let fd = cvt(libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC))?;
+            let written = libc::write(fd, content.as_ptr().cast(), content.len());
+            libc::close(fd);
+
+            if written != content.len() as isize {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn enter_namespaces(uid_map: &[u8], gid_map: &[u8]) -> io::Result<()> {
+        unsafe {
+            cvt(libc::unshare(
+                libc::CLONE_NEWUSER
+                    | libc::CLONE_NEWNS
+                    | libc::CLONE_NEWPID
+                    | libc::CLONE_NEWNET
+                    | libc::CLONE_NEWIPC
+                    | libc::CLONE_NEWUTS,
+            ))?;
+        }
+
+        // setgroups must be denied before an unprivileged process can write the gid_map
+        write_file(c"/proc/self/setgroups", b"deny")?;
+        write_file(c"/proc/self/uid_map", uid_map)?;
+        write_file(c"/proc/self/gid_map", gid_map)
+    }
+
+    /// Where the sandbox's root is mounted before pivoting into it, any existing directory does
+    const NEW_ROOT: &CStr = c"/tmp";
+    /// Where the host's root is reachable from the sandbox's root, until it is detached
+    const OLD_ROOT: &CStr = c"/.host";
+
+    enum MountKind {
+        Directory,
+        File,
+        Symlink(CString),
+    }
+
+    struct Mount {
+        kind: MountKind,
+        /// Path in the host's root, seen from the sandbox's root
+        source: CString,
+        target: CString,
+    }
+
+    /// Contents of the sandbox's root, prepared before forking
+    pub(super) struct RootLayout {
+        /// Parents come before their children
+        directories: Vec<CString>,
+        mounts: Vec<Mount>,
+    }
+
+    fn c_path(path: &Path) -> io::Result<CString> {
+        CString::new(path.as_os_str().as_bytes())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+    }
+
+    impl RootLayout {
+        /// Lays out the given host paths, binding each one at the same place in the new root,
+        /// or recreating it if it is a symbolic link. Missing paths are skipped.
+        pub(super) fn new(paths: &[PathBuf]) -> io::Result<Self> {
+            let mut paths: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+            paths.sort();
+
+            let mut directories = BTreeSet::new();
+            let mut mounted: Vec<&Path> = Vec::new();
+            let mut mounts = Vec::new();
+
+            for path in paths {
+                // Covered by a mount already, whose (host) directories must not be written to
+                if mounted.iter().any(|mounted| path.starts_with(mounted)) {
+                    continue;
+                }
+                let Ok(metadata) = path.symlink_metadata() else {
+                    continue;
+                };
+
+                let kind = if metadata.is_symlink() {
+                    MountKind::Symlink(c_path(&std::fs::read_link(path)?)?)
+                } else if metadata.is_dir() {
+                    directories.insert(path.to_path_buf());
+                    MountKind::Directory
+                } else {
+                    MountKind::File
+                };
+                directories.extend(
+                    path.ancestors()
+                        .skip(1)
+                        .filter(|ancestor| ancestor.parent().is_some())
+                        .map(Path::to_path_buf),
+                );
+
+                let source = Path::new(OsStr::from_bytes(OLD_ROOT.to_bytes()))
+                    .join(path.strip_prefix("/").unwrap_or(path));
+                mounts.push(Mount {
+                    kind,
+                    source: c_path(&source)?,
+                    target: c_path(path)?,
+                });
+                mounted.push(path);
+            }
+
+            directories.insert(PathBuf::from("/proc"));
+
+            Ok(Self {
+                directories: directories
+                    .iter()
+                    .map(|directory| c_path(directory))
+                    .collect::<io::Result<_>>()?,
+                mounts,
+            })
+        }
+    }
+
+    /// Pivots into a new (tmpfs) root holding the paths of the layout, detaching the host's root
+    pub(super) fn enter_root(root: &RootLayout) -> io::Result<()> {
+        unsafe {
+            // Keep the changes below to this mount namespace
+            cvt(libc::mount(
+                std::ptr::null(),
+                c"/".as_ptr(),
+                std::ptr::null(),
+                libc::MS_REC | libc::MS_PRIVATE,
+                std::ptr::null(),
+            ))?;
+            cvt(libc::mount(
+                c"tmpfs".as_ptr(),
+                NEW_ROOT.as_ptr(),
+                c"tmpfs".as_ptr(),
+                libc::MS_NOSUID | libc::MS_NODEV,
+                c"mode=0755".as_ptr().cast(),
+            ))?;
+
+            cvt(libc::chdir(NEW_ROOT.as_ptr()))?;
+            cvt(libc::mkdir(c"./.host".as_ptr(), 0o700))?;
+            let ret = libc::syscall(libc::SYS_pivot_root, c".".as_ptr(), c"./.host".as_ptr());
+            cvt(ret as libc::c_int)?;
+            // Otherwise the working directory would still be in the host's root
+            cvt(libc::chdir(c"/".as_ptr()))?;
+
+            for directory in &root.directories {
+                if libc::mkdir(directory.as_ptr(), 0o755) == -1
+                    && io::Error::last_os_error().raw_os_error() != Some(libc::EEXIST)
+                {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+
+            for mount in &root.mounts {
+                match &mount.kind {
+                    MountKind::Symlink(link) => {
+                        cvt(libc::symlink(link.as_ptr(), mount.target.as_ptr()))?;
+                        continue;
+                    }
+                    MountKind::File => {
+                        let fd = cvt(libc::open(
+                            mount.target.as_ptr(),
+                            libc::O_WRONLY | libc::O_CREAT | libc::O_CLOEXEC,
+                            0o644,
+                        ))?;
+                        libc::close(fd);
+                    }
+                    MountKind::Directory => {}
+                }
+
+                // Recursive, as mounts inherited from the host can't be bound without their
+                // submounts
+                cvt(libc::mount(
+                    mount.source.as_ptr(),
+                    mount.target.as_ptr(),
+                    std::ptr::null(),
+                    libc::MS_BIND | libc::MS_REC,
+                    std::ptr::null(),
+                ))?;
+            }
+
+            // Best effort, the kernel only allows it while the host's /proc is fully visible
+            libc::mount(
+                c"proc".as_ptr(),
+                c"/proc".as_ptr(),
+                c"proc".as_ptr(),
+                libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC,
+                std::ptr::null(),
+            );
+
+            cvt(libc::umount2(OLD_ROOT.as_ptr(), libc::MNT_DETACH))?;
+            cvt(libc::rmdir(OLD_ROOT.as_ptr()))?;
+        }
+        Ok(())
+    }
+
+    /// Drops the supplementary groups, which would keep granting access to what they own.
+    ///
+    /// An unprivileged runner can't change its groups, they stay (unmapped, as `nogroup`) then.
+    pub(super) fn drop_supplementary_groups() -> io::Result<()> {
+        if unsafe { libc::setgroups(0, std::ptr::null()) } == -1 {
+            let err = io::Error::last_os_error();
+            if err.raw_os_error() != Some(libc::EPERM) {
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn make_filesystem_read_only() -> io::Result<()> {
+        unsafe {
+            let attr = libc::mount_attr {
+                attr_set: libc::MOUNT_ATTR_RDONLY | libc::MOUNT_ATTR_NOSUID,
+                attr_clr: 0,
+                propagation: 0,
+                userns_fd: 0,
+            };
+            let ret = libc::syscall(
+                libc::SYS_mount_setattr,
+                libc::AT_FDCWD,
+                c"/".as_ptr(),
+                libc::AT_RECURSIVE,
+                &attr as *const libc::mount_attr,
+                std::mem::size_of::<libc::mount_attr>(),
+            );
+            cvt(ret as libc::c_int)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(target_env = "gnu")]
+    type RlimitResource = libc::__rlimit_resource_t;
+    #[cfg(not(target_env = "gnu"))]
+    type RlimitResource = libc::c_int;
+
+    pub(super) fn set_rlimit(resource: RlimitResource, limit: u64) -> io::Result<()> {
+        let limit = libc::rlimit {
+            rlim_cur: limit,
+            rlim_max: limit,
+        };
+        unsafe { cvt(libc::setrlimit(resource, &limit)) }.map(|_| ())
+    }
+
+    /// Forks, returning only in the child, which is PID 1 of the PID namespace entered before.
+    ///
+    /// The parent stays behind as an intermediate process, waiting for the child and exiting the
+    /// same way it did, so the exit status (and resource usage) reaches the runner unchanged.
+    pub(super) fn fork_into_pid_namespace() -> io::Result<()> {
+        let pid = unsafe { cvt(libc::fork())? };
+
+        if pid == 0 {
+            // Killing the intermediate process (e.g. on timeout) must take the program down too
+            unsafe { cvt(libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL))? };
+            return Ok(());
+        }
+
+        unsafe {
+            // Close the inherited fds, including the CLOEXEC pipe `Command::spawn` waits on,
+            // otherwise spawning would only return once the program has finished
+            if libc::syscall(libc::SYS_close_range, 3, libc::c_uint::MAX, 0) != 0 {
+                for fd in 3..1024 {
+                    libc::close(fd);
+                }
+            }
+
+            let mut status = 0;
+            loop {
+                if libc::waitpid(pid, &mut status, 0) == pid {
+                    break;
+                }
+                if io::Error::last_os_error().kind() != io::ErrorKind::Interrupted {
+                    libc::_exit(127);
+                }
+            }
+
+            if libc::WIFSIGNALED(status) {
+                let signal = libc::WTERMSIG(status);
+                let mut signals = std::mem::zeroed::<libc::sigset_t>();
+                libc::sigemptyset(&mut signals);
+                libc::sigaddset(&mut signals, signal);
+                libc::sigprocmask(libc::SIG_UNBLOCK, &signals, std::ptr::null_mut());
+                libc::signal(signal, libc::SIG_DFL);
+                libc::kill(libc::getpid(), signal);
+            }
+
+            libc::_exit(if libc::WIFEXITED(status) {
+                libc::WEXITSTATUS(status)
+            } else {
+                127
+            })
+        }
+    }
+
+    pub(super) fn install_seccomp_filter(filter: &[libc::sock_filter]) -> io::Result<()> {
+        let program = libc::sock_fprog {
+            len: filter.len() as libc::c_ushort,
+            filter: filter.as_ptr().cast_mut(),
+        };
+
+        unsafe {
+            cvt(libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0))?;
+            cvt(libc::prctl(
+                libc::PR_SET_SECCOMP,
+                libc::SECCOMP_MODE_FILTER,
+                &program as *const libc::sock_fprog,
+            ))?;
+        }
+        Ok(())
+    }
+
+    fn statement(code: u32, k: u32) -> libc::sock_filter {
+        libc::sock_filter {
+            code: code as u16,
+            jt: 0,
+            jf: 0,
+            k,
+        }
+    }
+
+    fn jump(code: u32, k: u32, jump_true: u8, jump_false: u8) -> libc::sock_filter {
+        libc::sock_filter {
+            code: (libc::BPF_JMP | code | libc::BPF_K) as u16,
+            jt: jump_true,
+            jf: jump_false,
+            k,
+        }
+    }
+
+    fn load(offset: u32) -> libc::sock_filter {
+        statement(libc::BPF_LD | libc::BPF_W | libc::BPF_ABS, offset)
+    }
+
+    fn jump_if_equal(value: u32, jump_true: u8, jump_false: u8) -> libc::sock_filter {
+        jump(libc::BPF_JEQ, value, jump_true, jump_false)
+    }
+
+    fn ret(action: u32) -> libc::sock_filter {
+        statement(libc::BPF_RET | libc::BPF_K, action)
+    }
+
+    fn ret_errno(errno: libc::c_int) -> libc::sock_filter {
+        ret(libc::SECCOMP_RET_ERRNO | (errno as u32 & libc::SECCOMP_RET_DATA))
+    }
+
+    pub(super) fn build_seccomp_filter() -> Vec<libc::sock_filter> {
+        let mut filter = vec![
+            // Syscall numbers are only meaningful for the native architecture
+            load(SECCOMP_DATA_ARCH),
+            jump_if_equal(AUDIT_ARCH, 1, 0),
+            ret(libc::SECCOMP_RET_KILL_PROCESS),
+            load(SECCOMP_DATA_NR),
+        ];
+
+        // x32 syscalls share the x86_64 audit arch, but are numbered from bit 30
+        #[cfg(target_arch = "x86_64")]
+        filter.extend([
+            jump(libc::BPF_JGE, 0x4000_0000, 0, 1),
+            ret(libc::SECCOMP_RET_KILL_PROCESS),
+        ]);
+
+        for &syscall in DENIED_SYSCALLS {
+            filter.extend([jump_if_equal(syscall as u32, 0, 1), ret_errno(libc::EPERM)]);
+        }
+
+        for &syscall in PROCESS_SYSCALLS {
+            filter.extend([jump_if_equal(syscall as u32, 0, 1), ret_errno(libc::EAGAIN)]);
+        }
+
+        // clone3 passes its flags in memory, which seccomp can't inspect, so runtimes are made to
+        // fall back to clone
+        filter.extend([
+            jump_if_equal(libc::SYS_clone3 as u32, 0, 1),
+            ret_errno(libc::ENOSYS),
+        ]);
+
+        // clone is allowed for threads only
+        filter.extend([
+            jump_if_equal(libc::SYS_clone as u32, 0, 4),
+            load(SECCOMP_DATA_ARG0_LOW),
+            jump(libc::BPF_JSET, libc::CLONE_THREAD as u32, 0, 1),
+            ret(libc::SECCOMP_RET_ALLOW),
+            ret_errno(libc::EAGAIN),
+        ]);
+
+        // The network namespace has no interfaces, but Unix sockets could still connect to host
+        // services, so only connected pairs (socketpair) are allowed
+        filter.extend([
+            jump_if_equal(libc::SYS_socket as u32, 0, 1),
+            ret_errno(libc::EACCES),
+        ]);
+
+        filter.push(ret(libc::SECCOMP_RET_ALLOW));
+        filter
+    }
+}
@@ -0,0 +1,10 @@
+using System;
+
+public class Program
+{
+    public static void Main()
+    {
+        int number = int.Parse(Console.ReadLine().Trim());
+        Console.WriteLine(number * number);
+    }
+}
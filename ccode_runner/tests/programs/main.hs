@@ -0,0 +1,4 @@
+main :: IO ()
+main = do
+  number <- readLn :: IO Int
+  print (number * number)
@@ -0,0 +1,4 @@
+fun main() {
+    val number = readln().trim().toInt()
+    println(number * number)
+}
@@ -0,0 +1,3 @@
+<?php
+$number = intval(trim(fgets(STDIN)));
+echo $number * $number, PHP_EOL;
@@ -0,0 +1,8 @@
+#include <iostream>
+#include "square.h"
+
+int main() {
+    long long n;
+    std::cin >> n;
+    std::cout << square(n) << std::endl;
+}
@@ -0,0 +1,3 @@
+#pragma once
+
+long long square(long long n);
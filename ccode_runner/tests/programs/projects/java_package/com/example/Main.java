@@ -0,0 +1,11 @@
+package com.example;
+
+import com.example.util.Square;
+import java.util.Scanner;
+
+public class Main {
+    public static void main(String[] args) {
+        Scanner scanner = new Scanner(System.in);
+        System.out.println(Square.of(scanner.nextLong()));
+    }
+}
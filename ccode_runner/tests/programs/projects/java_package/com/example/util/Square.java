@@ -0,0 +1,7 @@
+package com.example.util;
+
+public class Square {
+    public static long of(long n) {
+        return n * n;
+    }
+}
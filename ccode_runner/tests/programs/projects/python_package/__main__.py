@@ -0,0 +1,3 @@
+from solver.square import square
+
+print(square(int(input())))
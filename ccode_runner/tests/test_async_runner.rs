@@ -0,0 +1,179 @@
+use std::time::{Duration, Instant};
+
+use ccode_runner::lang_runner::{
+    language_name::LanguageName, program_store::ProgramStore, runner::Language,
+};
+use ccode_runner::{ExecutionLimits, ExecutionStatus};
+
+#[tokio::test]
+async fn test_async_run_matches_sync_run() {
+    let lang = Language::new_from_text(
+        "n = int(input())\nprint(n * n)",
+        LanguageName::Python,
+        false,
+    )
+    .unwrap();
+
+    let async_result = lang.run_program_code_async("12").await.unwrap();
+    let sync_result = lang.run_program_code("12").unwrap();
+
+    assert!(async_result.is_success());
+    assert_eq!(async_result.stdout, sync_result.stdout);
+    assert_eq!(async_result.stdout_lossy(), "144\n");
+
+    #[cfg(target_os = "linux")]
+    {
+        assert!(async_result.cpu_time.is_some());
+        assert!(async_result.peak_memory_bytes.is_some_and(|peak| peak > 0));
+    }
+}
+
+#[tokio::test]
+async fn test_async_run_time_limit_exceeded() {
+    let lang = Language::new_from_text_with_limits(
+        "while True:\n    pass",
+        LanguageName::Python,
+        false,
+        ExecutionLimits::new().with_time_limit(500),
+    )
+    .unwrap();
+
+    let result = lang.run_program_code_async("").await.unwrap();
+
+    assert_eq!(result.status, ExecutionStatus::TimeLimitExceeded);
+    assert!(result.wall_time < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn test_async_run_output_limit_exceeded() {
+    let lang = Language::new_from_text_with_limits(
+        "while True:\n    print('spam')",
+        LanguageName::Python,
+        false,
+        ExecutionLimits::new()
+            .with_time_limit(5000)
+            .with_output_limit(1000),
+    )
+    .unwrap();
+
+    let result = lang.run_program_code_async("").await.unwrap();
+
+    assert_eq!(result.status, ExecutionStatus::OutputLimitExceeded);
+    assert_eq!(result.stdout.len(), 1000);
+}
+
+#[tokio::test]
+async fn test_async_program_store_checks_both_programs() {
+    let store = ProgramStore::new_from_text(
+        "print(sum(map(int, input().split())))",
+        "a, b = map(int, input().split())\nprint(a - b)",
+        LanguageName::Python,
+        LanguageName::Python,
+        false,
+    )
+    .unwrap();
+
+    let (matched, expected, actual) = store
+        .run_codes_and_compare_output_async("5 0")
+        .await
+        .unwrap();
+    assert!(matched);
+    assert_eq!((expected.as_str(), actual.as_str()), ("5\n", "5\n"));
+
+    let (matched, _, _) = store
+        .run_codes_and_compare_output_async("5 3")
+        .await
+        .unwrap();
+    assert!(!matched);
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_dropping_async_run_kills_process_tree() {
+    // Starts a grandchild sleeping for a minute, reporting its pid in a file
+    let source = r#"
+import subprocess, sys, time
+
+pid_file = input()
+sleeper = subprocess.Popen(["sleep", "60"])
+with open(pid_file, "w") as file:
+    file.write(str(sleeper.pid))
+time.sleep(60)
+"#;
+    let lang = Language::new_from_text(source, LanguageName::Python, false).unwrap();
+    let pid_file = tempfile::NamedTempFile::new().unwrap();
+    let pid_file_path = pid_file.path().to_str().unwrap().to_string();
+
+    let start_time = Instant::now();
+    let run = tokio::time::timeout(
+        Duration::from_secs(2),
+        lang.run_program_code_async(&pid_file_path),
+    )
+    .await;
+    assert!(run.is_err(), "The program should still be running");
+
+    let sleeper_pid = std::fs::read_to_string(&pid_file_path).unwrap();
+    assert!(!sleeper_pid.is_empty());
+
+    // Killed processes may linger as zombies until reaped by init
+    let is_alive = || {
+        std::fs::read_to_string(format!("/proc/{sleeper_pid}/stat")).is_ok_and(|stat| {
+            !stat
+                .rsplit_once(')')
+                .unwrap()
+                .1
+                .trim_start()
+                .starts_with('Z')
+        })
+    };
+    while is_alive() && start_time.elapsed() < Duration::from_secs(10) {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(!is_alive(), "The grandchild should be killed on cancel");
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_dropping_async_run_kills_orphaned_processes() {
+    // The sleeper's parent exits right away, so it is no longer a descendant of the program
+    let source = r#"
+import subprocess, time
+
+pid_file = input()
+subprocess.run(["sh", "-c", f"sleep 60 > /dev/null & echo $! > {pid_file}"])
+time.sleep(60)
+"#;
+    let lang = Language::new_from_text(source, LanguageName::Python, false).unwrap();
+    let pid_file = tempfile::NamedTempFile::new().unwrap();
+    let pid_file_path = pid_file.path().to_str().unwrap().to_string();
+
+    let run = tokio::time::timeout(
+        Duration::from_secs(2),
+        lang.run_program_code_async(&pid_file_path),
+    )
+    .await;
+    assert!(run.is_err(), "The program should still be running");
+
+    let sleeper_pid = std::fs::read_to_string(&pid_file_path).unwrap();
+    let sleeper_pid = sleeper_pid.trim();
+    assert!(!sleeper_pid.is_empty());
+
+    let start_time = Instant::now();
+    let is_alive = || {
+        std::fs::read_to_string(format!("/proc/{sleeper_pid}/stat")).is_ok_and(|stat| {
+            !stat
+                .rsplit_once(')')
+                .unwrap()
+                .1
+                .trim_start()
+                .starts_with('Z')
+        })
+    };
+    while is_alive() && start_time.elapsed() < Duration::from_secs(10) {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(
+        !is_alive(),
+        "The orphaned process should be killed on cancel"
+    );
+}
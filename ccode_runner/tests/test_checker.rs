@@ -0,0 +1,186 @@
+use ccode_runner::lang_runner::{
+    checker::{CheckerKind, CheckerVerdict, FloatChecker, TokenChecker, UnorderedLinesChecker},
+    language_name::LanguageName,
+    program_store::ProgramStore,
+    runner_error_types::RunnerErrorType,
+};
+use ccode_runner::{ExecutionLimits, SandboxConfig};
+
+/// Accepts any permutation of 1..=n, printed one number per line
+const SPECIAL_JUDGE: &str = r#"
+import sys
+
+with open(sys.argv[1]) as input_file:
+    n = int(input_file.read())
+with open(sys.argv[2]) as output_file:
+    output = sorted(int(token) for token in output_file.read().split())
+
+if output != list(range(1, n + 1)):
+    print("not a permutation", file=sys.stderr)
+    sys.exit(1)
+"#;
+
+fn python_store(correct_text: &str, test_text: &str) -> ProgramStore {
+    ProgramStore::new_from_text(
+        correct_text,
+        test_text,
+        LanguageName::Python,
+        LanguageName::Python,
+        false,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_default_checker_rejects_different_layout() {
+    let store = python_store("print(1, 2)", "print(1)\nprint(2)");
+
+    let (matched, _, _) = store.run_codes_and_compare_output("").unwrap();
+    assert!(!matched);
+}
+
+#[test]
+fn test_token_checker_accepts_different_layout() {
+    let store =
+        python_store("print(1, 2)", "print(1)\nprint(2)").with_checker(Box::new(TokenChecker));
+
+    let (matched, _, _) = store.run_codes_and_compare_output("").unwrap();
+    assert!(matched);
+}
+
+#[test]
+fn test_float_checker_reports_mismatch() {
+    let store =
+        python_store("print(1 / 3)", "print(0.34)").with_checker(Box::new(FloatChecker::default()));
+
+    let (verdict, _, _) = store.run_codes_and_check_output("").unwrap();
+    assert_eq!(
+        verdict,
+        CheckerVerdict::WrongAnswer(String::from(
+            "Token 1 differs, expected `0.3333333333333333`, found `0.34`"
+        ))
+    );
+}
+
+#[test]
+fn test_unordered_lines_checker_accepts_any_order() {
+    let store = python_store("print('a b')\nprint('c')", "print('c')\nprint('a b')")
+        .with_checker(Box::new(UnorderedLinesChecker));
+
+    let (matched, _, _) = store.run_codes_and_compare_output("").unwrap();
+    assert!(matched);
+}
+
+#[test]
+fn test_special_judge_accepts_multiple_valid_answers() {
+    let checker = CheckerKind::SpecialJudge {
+        code: SPECIAL_JUDGE.to_string(),
+        language: LanguageName::Python,
+    }
+    .build(ExecutionLimits::default())
+    .unwrap();
+    let store = python_store(
+        "n = int(input())\nfor i in range(1, n + 1): print(i)",
+        "n = int(input())\nfor i in range(n, 0, -1): print(i)",
+    )
+    .with_checker(checker);
+
+    let (verdict, expected, actual) = store.run_codes_and_check_output("3").unwrap();
+    assert_eq!(verdict, CheckerVerdict::Accepted);
+    assert_ne!(expected, actual);
+}
+
+#[test]
+fn test_special_judge_rejects_wrong_answer_with_message() {
+    let checker = CheckerKind::SpecialJudge {
+        code: SPECIAL_JUDGE.to_string(),
+        language: LanguageName::Python,
+    }
+    .build(ExecutionLimits::default())
+    .unwrap();
+    let store = python_store(
+        "n = int(input())\nfor i in range(1, n + 1): print(i)",
+        "n = int(input())\nfor i in range(n): print(1)",
+    )
+    .with_checker(checker);
+
+    let (verdict, _, _) = store.run_codes_and_check_output("3").unwrap();
+    assert_eq!(
+        verdict,
+        CheckerVerdict::WrongAnswer(String::from("not a permutation"))
+    );
+}
+
+#[test]
+fn test_special_judge_failure_is_an_error() {
+    let checker = CheckerKind::SpecialJudge {
+        code: String::from("import sys\nsys.exit(3)"),
+        language: LanguageName::Python,
+    }
+    .build(ExecutionLimits::default())
+    .unwrap();
+    let store = python_store("print(1)", "print(1)").with_checker(checker);
+
+    let err = store.run_codes_and_check_output("").unwrap_err();
+    assert!(matches!(*err, RunnerErrorType::CheckerFailed(_)));
+}
+
+#[tokio::test]
+async fn test_special_judge_runs_on_the_async_runner() {
+    let checker = CheckerKind::SpecialJudge {
+        code: SPECIAL_JUDGE.to_string(),
+        language: LanguageName::Python,
+    }
+    .build(ExecutionLimits::default())
+    .unwrap();
+    let store = python_store(
+        "n = int(input())\nfor i in range(1, n + 1): print(i)",
+        "n = int(input())\nfor i in range(n): print(1)",
+    )
+    .with_checker(checker);
+
+    let (verdict, _, _) = store.run_codes_and_check_output_async("3").await.unwrap();
+    assert_eq!(
+        verdict,
+        CheckerVerdict::WrongAnswer(String::from("not a permutation"))
+    );
+}
+
+#[test]
+fn test_special_judge_runs_with_the_given_limits() {
+    let checker = CheckerKind::SpecialJudge {
+        code: String::from("while True:\n    pass"),
+        language: LanguageName::Python,
+    }
+    .build(ExecutionLimits::new().with_time_limit(500))
+    .unwrap();
+    let store = python_store("print(1)", "print(1)").with_checker(checker);
+
+    let err = store.run_codes_and_check_output("").unwrap_err();
+    assert!(
+        matches!(*err, RunnerErrorType::CheckerFailed(ref reason) if reason.starts_with("TimeLimitExceeded"))
+    );
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_special_judge_runs_in_the_sandbox() {
+    // Writing a file is denied by the sandbox's read-only filesystem
+    let judge = format!(
+        "import sys\nopen({:?}, 'w').write('escaped')",
+        std::env::temp_dir()
+            .join("cpast_judge_escape")
+            .display()
+            .to_string()
+    );
+    let checker = CheckerKind::SpecialJudge {
+        code: judge,
+        language: LanguageName::Python,
+    }
+    .build(ExecutionLimits::new().with_sandbox(SandboxConfig::new()))
+    .unwrap();
+    let store = python_store("print(1)", "print(1)").with_checker(checker);
+
+    let err = store.run_codes_and_check_output("").unwrap_err();
+    assert!(matches!(*err, RunnerErrorType::CheckerFailed(_)));
+}
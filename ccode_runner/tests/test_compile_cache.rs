@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::Path;
+
+use ccode_runner::ExecutionLimits;
+use ccode_runner::lang_runner::{
+    compile_cache::CompileCache, language_name::LanguageName, runner::Language,
+    toolchain::ToolchainConfig,
+};
+
+const SQUARE_PROGRAM: &str = r#"
+#include <iostream>
+int main() {
+    long long n;
+    std::cin >> n;
+    std::cout << n * n << std::endl;
+}
+"#;
+
+fn cached_program(source: &str, toolchains: &ToolchainConfig) -> Language {
+    Language::new_from_text_with_toolchain(
+        source,
+        LanguageName::Cpp,
+        false,
+        ExecutionLimits::default(),
+        toolchains,
+    )
+    .unwrap()
+}
+
+fn cache_entries(cache_dir: &Path) -> usize {
+    fs::read_dir(cache_dir).unwrap().count()
+}
+
+#[test]
+fn test_compile_cache_reuses_program_compiled_from_same_code() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let toolchains =
+        ToolchainConfig::default().with_compile_cache(CompileCache::new(cache_dir.path()));
+
+    // The cached program outlives the temporary directory of the first run
+    let first = cached_program(SQUARE_PROGRAM, &toolchains);
+    assert_eq!(first.run_program_code("7").unwrap().stdout_lossy(), "49\n");
+    drop(first);
+
+    let second = cached_program(SQUARE_PROGRAM, &toolchains);
+    assert_eq!(second.run_program_code("8").unwrap().stdout_lossy(), "64\n");
+    assert_eq!(cache_entries(cache_dir.path()), 1);
+}
+
+#[test]
+fn test_compile_cache_recompiles_changed_code_and_flags() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let toolchains =
+        ToolchainConfig::default().with_compile_cache(CompileCache::new(cache_dir.path()));
+
+    cached_program(SQUARE_PROGRAM, &toolchains);
+    let changed = cached_program(&SQUARE_PROGRAM.replace("n * n", "n + n"), &toolchains);
+    assert_eq!(
+        changed.run_program_code("7").unwrap().stdout_lossy(),
+        "14\n"
+    );
+    assert_eq!(cache_entries(cache_dir.path()), 2);
+
+    let flagged = ToolchainConfig::from_toml_str(
+        r#"
+        [cpp]
+        compile = [{ program = "g++", args = ["-O0", "-o", "{output}", "{source}"] }]
+        "#,
+    )
+    .unwrap()
+    .with_compile_cache(CompileCache::new(cache_dir.path()));
+    cached_program(SQUARE_PROGRAM, &flagged);
+    assert_eq!(cache_entries(cache_dir.path()), 3);
+}
@@ -0,0 +1,67 @@
+use ccode_runner::ExecutionLimits;
+use ccode_runner::lang_runner::{
+    diagnostics::DiagnosticSeverity, language_name::LanguageName, runner::Language,
+    runner_error_types::RunnerErrorType, toolchain::ToolchainConfig,
+};
+
+#[test]
+fn test_compile_error_carries_compiler_diagnostics() {
+    let source = "#include <iostream>\nint main() {\n    std::cout << x;\n}\n";
+
+    let err = Language::new_from_text(source, LanguageName::Cpp, false).unwrap_err();
+    let RunnerErrorType::CompilationError(_, attempts) = *err else {
+        panic!("Expected a compilation error, got {err}");
+    };
+
+    let gxx = attempts
+        .iter()
+        .find(|attempt| attempt.compiler == "g++")
+        .unwrap();
+    assert!(gxx.launched);
+    assert!(gxx.stderr.contains("'x' was not declared"));
+
+    let error = gxx.errors().next().unwrap();
+    assert_eq!(error.severity, DiagnosticSeverity::Error);
+    assert_eq!(error.line, 3);
+    assert_eq!(error.column, Some(18));
+    assert!(error.file.ends_with("source.cpp"));
+}
+
+#[test]
+fn test_compile_error_records_compilers_that_could_not_start() {
+    let toolchains = ToolchainConfig::from_toml_str(
+        r#"
+        [rust]
+        compile = [
+            { program = "cpast-missing-rustc", args = ["{source}"] },
+            { program = "rustc", args = ["-o", "{output}", "{source}"] },
+        ]
+        "#,
+    )
+    .unwrap();
+
+    let err = Language::new_from_text_with_toolchain(
+        "fn main() {
+    let x: u32 = \"x\";
+}
+",
+        LanguageName::Rust,
+        false,
+        ExecutionLimits::default(),
+        &toolchains,
+    )
+    .unwrap_err();
+    let RunnerErrorType::CompilationError(_, attempts) = *err else {
+        panic!("Expected a compilation error, got {err}");
+    };
+
+    assert_eq!(attempts.len(), 2);
+    assert!(!attempts[0].launched);
+    assert!(attempts[0].diagnostics.is_empty());
+
+    assert_eq!(attempts[1].compiler, "rustc");
+    assert!(attempts[1].launched);
+    let error = attempts[1].errors().next().unwrap();
+    assert_eq!(error.message, "mismatched types");
+    assert_eq!((error.line, error.column), (2, Some(18)));
+}
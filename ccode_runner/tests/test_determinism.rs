@@ -0,0 +1,102 @@
+use ccode_runner::ExecutionLimits;
+use ccode_runner::lang_runner::checker::{FloatChecker, TextChecker};
+use ccode_runner::lang_runner::determinism::{check_determinism, check_determinism_async};
+use ccode_runner::lang_runner::runner_error_types::RunnerErrorType;
+use ccode_runner::lang_runner::{
+    language_name::LanguageName, runner::Language, toolchain::ToolchainConfig,
+};
+
+fn python(source: &str) -> Language {
+    Language::new_from_text(source, LanguageName::Python, false).unwrap()
+}
+
+#[test]
+fn test_deterministic_program_has_one_outcome() {
+    let program = python("n = int(input())\nprint(n * 2)");
+
+    let report = check_determinism(&program, "21\n", 4, &TextChecker).unwrap();
+    assert!(report.is_deterministic());
+    assert_eq!(report.runs(), 4);
+    assert_eq!(report.outcomes[0].outcome.as_deref().unwrap(), "42\n");
+}
+
+#[test]
+fn test_time_seeded_program_is_flaky() {
+    let program = python("import time\nprint(time.time_ns())");
+
+    let report = check_determinism(&program, "", 3, &TextChecker).unwrap();
+    assert!(!report.is_deterministic());
+    assert_eq!(report.runs(), 3);
+}
+
+#[test]
+fn test_outputs_accepted_by_checker_are_the_same_outcome() {
+    // Differences below the tolerance aren't flakiness
+    let program = python("import random\nprint(1 + random.random() * 1e-9)");
+
+    let report = check_determinism(&program, "", 3, &FloatChecker::new(1e-6, 1e-6)).unwrap();
+    assert!(report.is_deterministic());
+}
+
+#[test]
+fn test_failures_are_grouped_by_kind() {
+    // Exits with a different code on each run, which is still the same runtime error
+    let program = python("import sys, time\nsys.exit(1 + time.time_ns() % 100)");
+
+    let report = check_determinism(&program, "", 3, &TextChecker).unwrap();
+    assert!(report.is_deterministic());
+    assert!(matches!(
+        report.outcomes[0].outcome.as_ref().map_err(|err| &**err),
+        Err(RunnerErrorType::RuntimeError(_))
+    ));
+}
+
+#[test]
+fn test_sometimes_failing_program_is_flaky() {
+    // Fails on every other run, counting its runs in a file
+    let dir = tempfile::tempdir().unwrap();
+    let counter = dir.path().join("runs");
+    let program = python(&format!(
+        "with open({counter:?}, 'a+') as f:\n    f.write('.')\n    f.seek(0)\n    runs = len(f.read())\nif runs % 2 == 0:\n    raise SystemExit(1)\nprint('ok')"
+    ));
+
+    let report = check_determinism(&program, "", 4, &TextChecker).unwrap();
+    assert!(!report.is_deterministic());
+    assert_eq!(report.outcomes[0].runs, 2);
+    assert_eq!(report.outcomes[0].outcome.as_deref().unwrap(), "ok\n");
+    assert!(report.outcomes[1].outcome.is_err());
+}
+
+#[test]
+fn test_runs_bypass_workers() {
+    // Python draws the hash seed once per process, so a warm worker would hide this
+    let program = Language::new_from_text_with_toolchain(
+        "print(hash('cpast') % 1000003)",
+        LanguageName::Python,
+        false,
+        ExecutionLimits::default(),
+        &ToolchainConfig::default().with_workers(1),
+    )
+    .unwrap();
+
+    let report = check_determinism(&program, "", 5, &TextChecker).unwrap();
+    assert!(!report.is_deterministic());
+}
+
+#[tokio::test]
+async fn test_async_check_matches_sync_check() {
+    let program = python("import random\nprint(random.random())");
+
+    let report = check_determinism_async(&program, "", 3, &TextChecker)
+        .await
+        .unwrap();
+    assert_eq!(report.runs(), 3);
+    assert!(!report.is_deterministic());
+
+    let program = python("print(input()[::-1])");
+    let report = check_determinism_async(&program, "abc\n", 3, &TextChecker)
+        .await
+        .unwrap();
+    assert!(report.is_deterministic());
+    assert_eq!(report.outcomes[0].outcome.as_deref().unwrap(), "cba\n");
+}
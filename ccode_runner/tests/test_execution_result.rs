@@ -0,0 +1,467 @@
+use ccode_runner::lang_runner::{
+    language_name::LanguageName,
+    program_store::{ProgramRole, ProgramStore},
+    runner::Language,
+    runner_error_types::RunnerErrorType,
+};
+use ccode_runner::{ExecutionLimits, ExecutionStatus, ResourceBackend};
+use std::time::Duration;
+
+#[test]
+fn test_execution_result_successful_run() {
+    let program_text = r#"
+#include <stdio.h>
+
+int main() {
+    int n;
+    scanf("%d", &n);
+    printf("%d\n", n * 2);
+    fprintf(stderr, "done");
+    return 0;
+}
+"#;
+
+    let program = Language::new_from_text(program_text, LanguageName::C, false).unwrap();
+    let result = program.run_program_code("21").unwrap();
+
+    assert!(result.is_success());
+    assert_eq!(result.exit_code, Some(0));
+    assert_eq!(result.signal, None);
+    assert_eq!(result.stdout_lossy(), "42\n");
+    assert_eq!(result.stderr_lossy(), "done");
+    assert_eq!(result.status, ExecutionStatus::Success);
+
+    #[cfg(unix)]
+    {
+        assert!(result.cpu_time.is_some());
+        assert!(result.peak_memory_bytes.is_some_and(|peak| peak > 0));
+    }
+}
+
+#[test]
+fn test_execution_result_reports_resource_backend() {
+    let program_text = r#"
+#include <stdio.h>
+
+int main() {
+    printf("ok\n");
+    return 0;
+}
+"#;
+
+    let limits = ExecutionLimits::new()
+        .with_memory_limit(256 * 1024 * 1024)
+        .with_process_limit(16);
+    let program =
+        Language::new_from_text_with_limits(program_text, LanguageName::C, false, limits).unwrap();
+    let result = program.run_program_code("").unwrap();
+
+    assert!(result.is_success());
+    assert_eq!(result.stdout_lossy(), "ok\n");
+
+    #[cfg(target_os = "linux")]
+    match result.resource_backend {
+        ResourceBackend::CgroupV2 => assert!(result.peak_processes.is_some()),
+        ResourceBackend::Rlimit => assert_eq!(result.peak_processes, None),
+        ResourceBackend::Monitor => panic!("Linux never polls memory usage"),
+        ResourceBackend::Worker => panic!("Programs run in fresh processes by default"),
+    }
+    #[cfg(not(target_os = "linux"))]
+    assert_eq!(result.resource_backend, ResourceBackend::Monitor);
+}
+
+#[test]
+fn test_execution_result_non_zero_exit_code() {
+    let program_text = r#"
+#include <stdio.h>
+
+int main() {
+    printf("partial");
+    return 3;
+}
+"#;
+
+    let program = Language::new_from_text(program_text, LanguageName::C, false).unwrap();
+    let result = program.run_program_code("").unwrap();
+
+    assert!(!result.is_success());
+    assert_eq!(result.exit_code, Some(3));
+    assert_eq!(result.signal, None);
+    assert_eq!(result.status, ExecutionStatus::NonZeroExit(3));
+    assert_eq!(result.stdout_lossy(), "partial");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_execution_result_terminated_by_signal() {
+    let program_text = r#"
+#include <signal.h>
+
+int main() {
+    raise(SIGSEGV);
+    return 0;
+}
+"#;
+
+    let program = Language::new_from_text(program_text, LanguageName::C, false).unwrap();
+    let result = program.run_program_code("").unwrap();
+
+    assert!(!result.is_success());
+    assert_eq!(result.exit_code, None);
+    assert_eq!(result.signal, Some(11)); // SIGSEGV
+    assert_eq!(result.status, ExecutionStatus::Signaled(11));
+}
+
+#[test]
+fn test_execution_result_timed_out() {
+    let program_text = r#"
+while True:
+    pass
+"#;
+
+    let limits = ExecutionLimits::new().with_time_limit(500);
+    let program =
+        Language::new_from_text_with_limits(program_text, LanguageName::Python, false, limits)
+            .unwrap();
+    let result = program.run_program_code("").unwrap();
+
+    assert_eq!(result.status, ExecutionStatus::TimeLimitExceeded);
+    assert!(!result.is_success());
+    assert!(result.wall_time.as_millis() >= 500);
+}
+
+#[test]
+fn test_program_store_reports_runtime_error() {
+    let program_text = r#"
+int main() {
+    return 3;
+}
+"#;
+
+    let program = ProgramStore::new_from_text(
+        program_text,
+        program_text,
+        LanguageName::C,
+        LanguageName::C,
+        false,
+    )
+    .unwrap();
+
+    let err = program.run_codes_and_compare_output("").unwrap_err();
+    assert_eq!(err.program_role(), Some(ProgramRole::Correct));
+    match err.program_error() {
+        RunnerErrorType::RuntimeError(result) => assert_eq!(result.exit_code, Some(3)),
+        err => panic!("Expected RuntimeError, got: {err}"),
+    }
+}
+
+#[test]
+fn test_execution_result_output_limit_exceeded() {
+    let program_text = r#"
+while True:
+    print("spam")
+"#;
+
+    let limits = ExecutionLimits::new()
+        .with_time_limit(5000)
+        .with_output_limit(1024);
+    let program =
+        Language::new_from_text_with_limits(program_text, LanguageName::Python, false, limits)
+            .unwrap();
+    let result = program.run_program_code("").unwrap();
+
+    assert_eq!(result.status, ExecutionStatus::OutputLimitExceeded);
+    assert_eq!(result.stdout.len(), 1024);
+}
+
+#[test]
+fn test_execution_result_output_within_limit() {
+    let program_text = r#"
+print("a" * 1023)
+"#;
+
+    let limits = ExecutionLimits::new().with_output_limit(1024);
+    let program =
+        Language::new_from_text_with_limits(program_text, LanguageName::Python, false, limits)
+            .unwrap();
+    let result = program.run_program_code("").unwrap();
+
+    assert_eq!(result.status, ExecutionStatus::Success);
+}
+
+#[test]
+#[cfg(unix)] // Linux: cgroup memory.max or RLIMIT_AS; macOS: phys_footprint monitor
+fn test_program_store_reports_memory_limit_exceeded() {
+    let program_text = r#"
+data = [0] * (50 * 1024 * 1024)
+print(len(data))
+"#;
+
+    let limits = ExecutionLimits::new()
+        .with_time_limit(5000)
+        .with_memory_limit(64 * 1024 * 1024);
+    let program = ProgramStore::new_from_text_with_limits(
+        program_text,
+        program_text,
+        LanguageName::Python,
+        LanguageName::Python,
+        false,
+        limits,
+    )
+    .unwrap();
+
+    match program
+        .run_codes_and_compare_output("")
+        .unwrap_err()
+        .program_error()
+    {
+        RunnerErrorType::MemoryLimitExceeded(limit) => assert_eq!(*limit, 64 * 1024 * 1024),
+        err => panic!("Expected MemoryLimitExceeded, got: {err}"),
+    }
+}
+
+#[test]
+fn test_program_store_reports_time_limit_exceeded() {
+    let program_text = r#"
+while True:
+    pass
+"#;
+
+    let limits = ExecutionLimits::new().with_time_limit(500);
+    let program = ProgramStore::new_from_text_with_limits(
+        program_text,
+        program_text,
+        LanguageName::Python,
+        LanguageName::Python,
+        false,
+        limits,
+    )
+    .unwrap();
+
+    match program
+        .run_codes_and_compare_output("")
+        .unwrap_err()
+        .program_error()
+    {
+        RunnerErrorType::TimeLimitExceeded(limit) => assert_eq!(*limit, 500),
+        err => panic!("Expected TimeLimitExceeded, got: {err}"),
+    }
+}
+
+#[test]
+#[cfg(unix)]
+fn test_execution_result_cpu_time_limit_exceeded() {
+    let program_text = r#"
+int main() {
+    volatile unsigned long counter = 0;
+    while (1) {
+        counter++;
+    }
+    return 0;
+}
+"#;
+
+    let limits = ExecutionLimits::new()
+        .with_time_limit(10000)
+        .with_cpu_time_limit(300);
+    let program =
+        Language::new_from_text_with_limits(program_text, LanguageName::C, false, limits).unwrap();
+    let result = program.run_program_code("").unwrap();
+
+    assert_eq!(result.status, ExecutionStatus::CpuTimeLimitExceeded);
+    assert!(
+        result
+            .cpu_time
+            .is_some_and(|cpu_time| cpu_time >= Duration::from_millis(300))
+    );
+    // Stopped well before the wall clock limit
+    assert!(result.wall_time < Duration::from_millis(5000));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_execution_result_idle_program_hits_wall_time_limit() {
+    let program_text = r#"
+import time
+time.sleep(10)
+"#;
+
+    let limits = ExecutionLimits::new()
+        .with_time_limit(500)
+        .with_cpu_time_limit(5000);
+    let program =
+        Language::new_from_text_with_limits(program_text, LanguageName::Python, false, limits)
+            .unwrap();
+    let result = program.run_program_code("").unwrap();
+
+    assert_eq!(result.status, ExecutionStatus::TimeLimitExceeded);
+    assert!(
+        result
+            .cpu_time
+            .is_some_and(|cpu_time| cpu_time < Duration::from_millis(5000))
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn test_program_store_reports_cpu_time_limit_exceeded() {
+    let program_text = r#"
+while True:
+    pass
+"#;
+
+    let limits = ExecutionLimits::new()
+        .with_time_limit(10000)
+        .with_cpu_time_limit(300);
+    let program = ProgramStore::new_from_text_with_limits(
+        program_text,
+        program_text,
+        LanguageName::Python,
+        LanguageName::Python,
+        false,
+        limits,
+    )
+    .unwrap();
+
+    let err = program.run_codes_and_compare_output("").unwrap_err();
+    assert!(matches!(
+        err.program_error(),
+        RunnerErrorType::CpuTimeLimitExceeded(300)
+    ));
+}
+
+#[test]
+fn test_execution_result_keeps_invalid_utf8_output() {
+    let program_text = r#"
+#include <stdio.h>
+
+int main() {
+    printf("ok\xff\xfe\n");
+    fprintf(stderr, "\x80");
+    return 0;
+}
+"#;
+
+    let program = Language::new_from_text(program_text, LanguageName::C, false).unwrap();
+    let result = program.run_program_code("").unwrap();
+
+    assert!(result.is_success());
+    assert_eq!(result.stdout, b"ok\xff\xfe\n");
+    assert_eq!(result.stdout_lossy(), "ok\u{FFFD}\u{FFFD}\n");
+    assert_eq!(result.stderr_lossy(), "\u{FFFD}");
+}
+
+#[test]
+fn test_program_store_compares_invalid_utf8_output() {
+    let program_text = r#"
+#include <stdio.h>
+
+int main() {
+    printf("\xff\n");
+    return 0;
+}
+"#;
+
+    let program = ProgramStore::new_from_text(
+        program_text,
+        program_text,
+        LanguageName::C,
+        LanguageName::C,
+        false,
+    )
+    .unwrap();
+
+    let (matched, correct_output, _) = program.run_codes_and_compare_output("").unwrap();
+    assert!(matched);
+    assert_eq!(correct_output, "\u{FFFD}\n");
+}
+
+#[test]
+fn test_execution_result_time_limit_with_unread_input() {
+    // Never reads its input, which is larger than the pipe buffer
+    let program_text = r#"
+int main() {
+    while (1) {}
+    return 0;
+}
+"#;
+
+    let limits = ExecutionLimits::new().with_time_limit(500);
+    let program =
+        Language::new_from_text_with_limits(program_text, LanguageName::C, false, limits).unwrap();
+    let result = program.run_program_code(&"1 ".repeat(1024 * 1024)).unwrap();
+
+    assert_eq!(result.status, ExecutionStatus::TimeLimitExceeded);
+    assert!(result.wall_time < Duration::from_secs(5));
+}
+
+#[test]
+fn test_execution_result_program_closing_stdin_early() {
+    let program_text = r#"
+#include <stdio.h>
+
+int main() {
+    int n;
+    scanf("%d", &n);
+    fclose(stdin);
+    printf("%d\n", n);
+    return 0;
+}
+"#;
+
+    let program = Language::new_from_text(program_text, LanguageName::C, false).unwrap();
+    let result = program.run_program_code(&"7 ".repeat(1024 * 1024)).unwrap();
+
+    assert!(result.is_success());
+    assert_eq!(result.stdout_lossy(), "7\n");
+}
+
+#[test]
+fn test_program_store_tags_errors_with_failing_program() {
+    let program = ProgramStore::new_from_text(
+        "print(input())",
+        "import sys\nsys.exit(2)",
+        LanguageName::Python,
+        LanguageName::Python,
+        false,
+    )
+    .unwrap();
+
+    let err = program.run_codes_and_compare_output("1").unwrap_err();
+    assert_eq!(err.program_role(), Some(ProgramRole::Test));
+    assert!(matches!(
+        err.program_error(),
+        RunnerErrorType::RuntimeError(result) if result.exit_code == Some(2)
+    ));
+    assert!(err.to_string().contains("The test program failed"));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_execution_result_background_process_does_not_block_output() {
+    // The forked sleeper keeps stdout open after the program itself exited
+    let program_text = r#"
+#include <stdio.h>
+#include <unistd.h>
+
+int main() {
+    printf("done\n");
+    fflush(stdout);
+    if (fork() == 0) {
+        sleep(1000);
+    }
+    return 0;
+}
+"#;
+
+    let limits = ExecutionLimits::new().with_time_limit(1000);
+    let program =
+        Language::new_from_text_with_limits(program_text, LanguageName::C, false, limits).unwrap();
+
+    let start = std::time::Instant::now();
+    let result = program.run_program_code("").unwrap();
+
+    assert_eq!(result.status, ExecutionStatus::Success);
+    assert_eq!(result.stdout_lossy(), "done\n");
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
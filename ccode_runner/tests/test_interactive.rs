@@ -0,0 +1,158 @@
+use ccode_runner::ExecutionLimits;
+use ccode_runner::ExecutionStatus;
+use ccode_runner::lang_runner::{
+    interactive::{InteractionVerdict, InteractiveStore, TranscriptDirection},
+    language_name::LanguageName,
+    runner::Language,
+};
+
+/// Reads the hidden number from the input file, answers guesses with `<`, `>` or `=`
+const GUESS_INTERACTOR: &str = r#"
+import sys
+
+with open(sys.argv[1]) as input_file:
+    hidden = int(input_file.read())
+
+for _ in range(20):
+    line = sys.stdin.readline()
+    if not line:
+        print("unexpected EOF", file=sys.stderr)
+        sys.exit(1)
+    guess = int(line)
+    if guess == hidden:
+        print("=", flush=True)
+        sys.exit(0)
+    print("<" if hidden < guess else ">", flush=True)
+
+print("too many guesses", file=sys.stderr)
+sys.exit(1)
+"#;
+
+const BINARY_SEARCH_SOLUTION: &str = r#"
+low, high = 1, 100
+while True:
+    mid = (low + high) // 2
+    print(mid, flush=True)
+    reply = input()
+    if reply == "=":
+        break
+    if reply == "<":
+        high = mid - 1
+    else:
+        low = mid + 1
+"#;
+
+#[test]
+fn test_interactive_accepts_binary_search() {
+    let store = InteractiveStore::new_from_text(
+        BINARY_SEARCH_SOLUTION,
+        GUESS_INTERACTOR,
+        LanguageName::Python,
+        LanguageName::Python,
+        false,
+    )
+    .unwrap();
+
+    let result = store.run_interaction("37").unwrap();
+
+    assert_eq!(result.verdict, InteractionVerdict::Accepted);
+    assert!(result.solution.is_success());
+    assert!(result.interactor.is_success());
+
+    let first = &result.transcript.entries[0];
+    assert_eq!(first.direction, TranscriptDirection::SolutionToInteractor);
+    assert_eq!(first.data, "50\n");
+    let last = result.transcript.entries.last().unwrap();
+    assert_eq!(last.direction, TranscriptDirection::InteractorToSolution);
+    assert_eq!(last.data, "=\n");
+    assert!(result.transcript.to_string().contains("> 37\n"));
+}
+
+#[test]
+fn test_interactive_reports_wrong_answer() {
+    let solution = r#"
+for guess in range(1, 101, 10):
+    print(guess, flush=True)
+    if input() == "=":
+        break
+"#;
+    let store = InteractiveStore::new_from_text(
+        solution,
+        GUESS_INTERACTOR,
+        LanguageName::Python,
+        LanguageName::Python,
+        false,
+    )
+    .unwrap();
+
+    let result = store.run_interaction("37").unwrap();
+
+    assert_eq!(
+        result.verdict,
+        InteractionVerdict::WrongAnswer(String::from("unexpected EOF"))
+    );
+}
+
+#[test]
+fn test_interactive_solution_time_limit_exceeded() {
+    let solution = r#"
+import time
+time.sleep(10)
+"#;
+    let store = InteractiveStore::new_from_text_with_limits(
+        solution,
+        GUESS_INTERACTOR,
+        LanguageName::Python,
+        LanguageName::Python,
+        false,
+        ExecutionLimits::new().with_time_limit(500),
+        ExecutionLimits::new().with_time_limit(5000),
+    )
+    .unwrap();
+
+    let result = store.run_interaction("37").unwrap();
+
+    assert_eq!(
+        result.verdict,
+        InteractionVerdict::SolutionFailed(ExecutionStatus::TimeLimitExceeded)
+    );
+    assert!(result.transcript.entries.is_empty());
+}
+
+#[test]
+fn test_interactive_interactor_reads_reference_answer() {
+    // Asks for the sum of the input numbers, expecting the answer of the reference solution
+    let interactor = r#"
+import sys
+
+with open(sys.argv[1]) as input_file:
+    numbers = input_file.read()
+with open(sys.argv[3]) as answer_file:
+    answer = answer_file.read().strip()
+
+print(numbers.strip(), flush=True)
+if sys.stdin.readline().strip() != answer:
+    print("wrong sum", file=sys.stderr)
+    sys.exit(1)
+"#;
+    let reference = Language::new_from_text(
+        "print(sum(map(int, input().split())))",
+        LanguageName::Python,
+        false,
+    )
+    .unwrap();
+    let store = InteractiveStore::new_from_text(
+        "print(sum(map(int, input().split())))",
+        interactor,
+        LanguageName::Python,
+        LanguageName::Python,
+        false,
+    )
+    .unwrap()
+    .with_reference(reference);
+
+    let result = store.run_interaction("1 2 3").unwrap();
+
+    assert_eq!(result.verdict, InteractionVerdict::Accepted);
+    assert_eq!(result.solution.stdout_lossy(), "6\n");
+}
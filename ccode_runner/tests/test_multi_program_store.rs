@@ -0,0 +1,165 @@
+use ccode_runner::lang_runner::{
+    language_name::LanguageName,
+    multi_program_store::{MultiProgramStore, ProgramVerdict, ReferenceStrategy},
+    runner::Language,
+    runner_error_types::RunnerErrorType,
+};
+
+const SUM_PROGRAM: &str = "a, b = map(int, input().split())\nprint(a + b)";
+const SUM_LOOP_PROGRAM: &str =
+    "total = 0\nfor n in input().split():\n    total += int(n)\nprint(total)";
+const PRODUCT_PROGRAM: &str = "a, b = map(int, input().split())\nprint(a * b)";
+const CRASHING_PROGRAM: &str = "raise SystemExit(3)";
+
+fn programs(sources: &[(&str, &str)]) -> Vec<(String, Language)> {
+    sources
+        .iter()
+        .map(|(name, source)| {
+            (
+                name.to_string(),
+                Language::new_from_text(source, LanguageName::Python, false).unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_oracle_reports_disagreeing_programs() {
+    let store = MultiProgramStore::new_from_languages(
+        programs(&[
+            ("brute", SUM_LOOP_PROGRAM),
+            ("intended", SUM_PROGRAM),
+            ("candidate", PRODUCT_PROGRAM),
+            ("crashing", CRASHING_PROGRAM),
+        ]),
+        ReferenceStrategy::Oracle(0),
+    )
+    .unwrap();
+
+    let report = store.run_codes_and_compare_outputs("2 2").unwrap();
+    assert_eq!(report.reference, Some(0));
+    assert_eq!(report.reference_output(), Some("4\n"));
+    assert_eq!(
+        report
+            .disagreeing()
+            .map(|result| result.name.as_str())
+            .collect::<Vec<_>>(),
+        ["crashing"]
+    );
+    assert!(matches!(
+        report.results[3].verdict,
+        ProgramVerdict::Failed(ref err) if matches!(**err, RunnerErrorType::RuntimeError(_))
+    ));
+
+    let report = store.run_codes_and_compare_outputs("2 3").unwrap();
+    assert_eq!(
+        report
+            .disagreeing()
+            .map(|result| result.name.as_str())
+            .collect::<Vec<_>>(),
+        ["candidate", "crashing"]
+    );
+    assert!(matches!(
+        report.results[2].verdict,
+        ProgramVerdict::WrongAnswer(_)
+    ));
+    assert_eq!(report.results[2].output.as_deref(), Some("6\n"));
+}
+
+#[test]
+fn test_failing_oracle_is_an_error() {
+    let store = MultiProgramStore::new_from_languages(
+        programs(&[("crashing", CRASHING_PROGRAM), ("intended", SUM_PROGRAM)]),
+        ReferenceStrategy::Oracle(0),
+    )
+    .unwrap();
+
+    let err = store.run_codes_and_compare_outputs("2 3").unwrap_err();
+    assert!(matches!(*err, RunnerErrorType::RuntimeError(_)));
+}
+
+#[test]
+fn test_majority_vote_outweighs_program_listed_first() {
+    let store = MultiProgramStore::new_from_languages(
+        programs(&[
+            ("candidate", PRODUCT_PROGRAM),
+            ("brute", SUM_LOOP_PROGRAM),
+            ("crashing", CRASHING_PROGRAM),
+            ("intended", SUM_PROGRAM),
+        ]),
+        ReferenceStrategy::Majority,
+    )
+    .unwrap();
+
+    let report = store.run_codes_and_compare_outputs("2 3").unwrap();
+    assert_eq!(report.reference, Some(1));
+    assert_eq!(report.reference_output(), Some("5\n"));
+    assert!(report.results[1].verdict.is_accepted());
+    assert!(report.results[3].verdict.is_accepted());
+    assert!(matches!(
+        report.results[0].verdict,
+        ProgramVerdict::WrongAnswer(_)
+    ));
+    assert!(matches!(
+        report.results[2].verdict,
+        ProgramVerdict::Failed(_)
+    ));
+
+    let store = MultiProgramStore::new_from_languages(
+        programs(&[("candidate", PRODUCT_PROGRAM), ("brute", SUM_LOOP_PROGRAM)]),
+        ReferenceStrategy::Majority,
+    )
+    .unwrap();
+    // 2 + 2 = 2 * 2
+    let report = store.run_codes_and_compare_outputs("2 2").unwrap();
+    assert!(report.all_agree());
+    // Ties go to the program listed first
+    let report = store.run_codes_and_compare_outputs("2 3").unwrap();
+    assert_eq!(report.reference, Some(0));
+    assert_eq!(report.disagreeing().count(), 1);
+}
+
+#[tokio::test]
+async fn test_async_comparison_matches_sync_comparison() {
+    let store = MultiProgramStore::new_from_languages(
+        programs(&[
+            ("brute", SUM_LOOP_PROGRAM),
+            ("intended", SUM_PROGRAM),
+            ("candidate", PRODUCT_PROGRAM),
+        ]),
+        ReferenceStrategy::Majority,
+    )
+    .unwrap();
+
+    let async_report = store
+        .run_codes_and_compare_outputs_async("4 5")
+        .await
+        .unwrap();
+    let sync_report = store.run_codes_and_compare_outputs("4 5").unwrap();
+
+    assert_eq!(async_report.reference, sync_report.reference);
+    for (async_result, sync_result) in async_report.results.iter().zip(&sync_report.results) {
+        assert_eq!(async_result.output, sync_result.output);
+        assert_eq!(
+            async_result.verdict.is_accepted(),
+            sync_result.verdict.is_accepted()
+        );
+    }
+}
+
+#[test]
+fn test_invalid_program_sets_are_rejected() {
+    let err = MultiProgramStore::new_from_languages(
+        programs(&[("intended", SUM_PROGRAM)]),
+        ReferenceStrategy::Majority,
+    )
+    .unwrap_err();
+    assert!(matches!(*err, RunnerErrorType::InvalidProgramSet(_)));
+
+    let err = MultiProgramStore::new_from_languages(
+        programs(&[("brute", SUM_LOOP_PROGRAM), ("intended", SUM_PROGRAM)]),
+        ReferenceStrategy::Oracle(2),
+    )
+    .unwrap_err();
+    assert!(matches!(*err, RunnerErrorType::InvalidProgramSet(_)));
+}
@@ -0,0 +1,54 @@
+use std::path::{Path, PathBuf};
+
+use ccode_runner::lang_runner::{
+    language_name::LanguageName, program_store::ProgramStore, runner::Language,
+    runner_error_types::RunnerErrorType,
+};
+
+const SQUARE_PROGRAM: &str = "n = int(input())\nprint(n * n)";
+
+fn project_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/programs/projects")
+        .join(name)
+}
+
+/// Compares the project against a single file solution
+fn assert_project_squares(name: &str) {
+    let correct = Language::new_from_text(SQUARE_PROGRAM, LanguageName::Python, false).unwrap();
+    let project = Language::new(&project_path(name), true).unwrap();
+    let program = ProgramStore::new_from_language(correct, project).unwrap();
+
+    let (matched, expected, actual) = program
+        .run_codes_and_compare_output("12")
+        .expect("Failed to run project");
+
+    assert!(matched, "{name}: expected {expected}, got {actual}");
+    assert_eq!(actual.trim_end(), "144");
+}
+
+#[test]
+fn test_run_cpp_project_with_local_headers() {
+    assert_project_squares("cpp_headers");
+}
+
+#[test]
+fn test_run_python_package() {
+    assert_project_squares("python_package");
+}
+
+#[test]
+fn test_run_java_package() {
+    assert_project_squares("java_package");
+}
+
+#[test]
+fn test_run_cargo_project() {
+    assert_project_squares("cargo_project");
+}
+
+#[test]
+fn test_directory_without_entry_point_is_rejected() {
+    let err = Language::new(&project_path("java_package/com/example/util"), false).unwrap_err();
+    assert!(matches!(*err, RunnerErrorType::InvalidProject(_, _)));
+}
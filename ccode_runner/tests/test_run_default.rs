@@ -114,3 +114,83 @@ fn test_run_java_program() {
     assert_eq!(expected.trim_end(), "100.0");
     assert_eq!(actual.trim_end(), "100.0");
 }
+
+#[test]
+fn test_run_go_program() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let file_path = Path::new(&manifest_dir).join("tests/programs/main.go");
+
+    let program = ProgramStore::new(&file_path, &file_path, false).unwrap();
+
+    let (matched, expected, actual) = program
+        .run_codes_and_compare_output("10")
+        .expect("Failed to run program");
+
+    assert!(matched);
+    assert_eq!(expected.trim_end(), "100");
+    assert_eq!(actual.trim_end(), "100");
+}
+
+#[test]
+fn test_run_kotlin_program() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let file_path = Path::new(&manifest_dir).join("tests/programs/main.kt");
+
+    let program = ProgramStore::new(&file_path, &file_path, false).unwrap();
+
+    let (matched, expected, actual) = program
+        .run_codes_and_compare_output("10\n")
+        .expect("Failed to run program");
+
+    assert!(matched);
+    assert_eq!(expected.trim_end(), "100");
+    assert_eq!(actual.trim_end(), "100");
+}
+
+#[test]
+fn test_run_csharp_program() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let file_path = Path::new(&manifest_dir).join("tests/programs/main.cs");
+
+    let program = ProgramStore::new(&file_path, &file_path, false).unwrap();
+
+    let (matched, expected, actual) = program
+        .run_codes_and_compare_output("10\n")
+        .expect("Failed to run program");
+
+    assert!(matched);
+    assert_eq!(expected.trim_end(), "100");
+    assert_eq!(actual.trim_end(), "100");
+}
+
+#[test]
+fn test_run_haskell_program() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let file_path = Path::new(&manifest_dir).join("tests/programs/main.hs");
+
+    let program = ProgramStore::new(&file_path, &file_path, false).unwrap();
+
+    let (matched, expected, actual) = program
+        .run_codes_and_compare_output("10\n")
+        .expect("Failed to run program");
+
+    assert!(matched);
+    assert_eq!(expected.trim_end(), "100");
+    assert_eq!(actual.trim_end(), "100");
+}
+
+#[test]
+fn test_run_php_program() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let file_path = Path::new(&manifest_dir).join("tests/programs/main.php");
+
+    let program = ProgramStore::new(&file_path, &file_path, false).unwrap();
+
+    let (matched, expected, actual) = program
+        .run_codes_and_compare_output("10\n")
+        .expect("Failed to run program");
+
+    assert!(matched);
+    assert_eq!(expected.trim_end(), "100");
+    assert_eq!(actual.trim_end(), "100");
+}
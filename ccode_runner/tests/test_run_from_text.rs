@@ -109,6 +109,71 @@ public class Main {
     run_test(program_text, program_text, LanguageName::Java);
 }
 
+#[test]
+fn test_run_go_program() {
+    let program_text = r#"
+package main
+
+import "fmt"
+
+func main() {
+    var n int
+    fmt.Scan(&n)
+    fmt.Print(n)
+}
+    "#;
+    run_test(program_text, program_text, LanguageName::Go);
+}
+
+#[test]
+fn test_run_kotlin_program() {
+    let program_text = r#"
+fun main() {
+    val n = readln().trim().toInt()
+    print(n)
+}
+    "#;
+    run_test(program_text, program_text, LanguageName::Kotlin);
+}
+
+#[test]
+fn test_run_csharp_program() {
+    let program_text = r#"
+using System;
+
+public class Program
+{
+    public static void Main()
+    {
+        int n = int.Parse(Console.ReadLine().Trim());
+        Console.Write(n);
+    }
+}
+    "#;
+    run_test(program_text, program_text, LanguageName::CSharp);
+}
+
+#[test]
+fn test_run_haskell_program() {
+    let program_text = r#"
+main :: IO ()
+main = do
+  n <- readLn :: IO Int
+  putStr (show n)
+    "#;
+    run_test(program_text, program_text, LanguageName::Haskell);
+}
+
+#[test]
+fn test_run_php_program() {
+    let program_text = r#"
+<?php
+$n = intval(trim(fgets(STDIN)));
+echo $n;
+    "#;
+    run_test(program_text, program_text, LanguageName::Php);
+}
+
 fn run_test(correct_program: &str, test_program: &str, lang: LanguageName) {
     let program =
         ProgramStore::new_from_text(correct_program, test_program, lang.clone(), lang, false)
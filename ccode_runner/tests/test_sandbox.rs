@@ -0,0 +1,263 @@
+#![cfg(target_os = "linux")]
+
+use ccode_runner::lang_runner::{language_name::LanguageName, runner::Language};
+use ccode_runner::{ExecutionLimits, ExecutionResult, ExecutionStatus, SandboxConfig};
+
+fn run_sandboxed(program_text: &str, stdin_content: &str) -> ExecutionResult {
+    let limits = ExecutionLimits::new()
+        .with_time_limit(5000)
+        .with_sandbox(SandboxConfig::new());
+
+    let program =
+        Language::new_from_text_with_limits(program_text, LanguageName::C, false, limits).unwrap();
+    program.run_program_code(stdin_content).unwrap()
+}
+
+#[test]
+fn test_sandbox_runs_program() {
+    let program_text = r#"
+#include <stdio.h>
+
+int main() {
+    int n;
+    scanf("%d", &n);
+    printf("%d", n * 2);
+    return 0;
+}
+"#;
+
+    let result = run_sandboxed(program_text, "21");
+
+    assert_eq!(result.status, ExecutionStatus::Success);
+    assert_eq!(result.stdout_lossy(), "42");
+}
+
+#[test]
+fn test_sandbox_blocks_fork_bomb() {
+    let program_text = r#"
+#include <stdio.h>
+#include <unistd.h>
+
+int main() {
+    int forked = 0;
+    for (int i = 0; i < 1000; i++) {
+        pid_t pid = fork();
+        if (pid == 0) {
+            while (1) {
+                fork();
+            }
+        }
+        if (pid > 0) {
+            forked++;
+        }
+    }
+    printf("%d", forked);
+    return 0;
+}
+"#;
+
+    let result = run_sandboxed(program_text, "");
+
+    assert_eq!(result.status, ExecutionStatus::Success);
+    assert_eq!(result.stdout_lossy(), "0");
+}
+
+#[test]
+fn test_sandbox_blocks_network_access() {
+    let program_text = r#"
+#include <arpa/inet.h>
+#include <netinet/in.h>
+#include <stdio.h>
+#include <sys/socket.h>
+#include <unistd.h>
+
+int main() {
+    int fd = socket(AF_INET, SOCK_STREAM, 0);
+    if (fd < 0) {
+        printf("blocked");
+        return 0;
+    }
+
+    struct sockaddr_in address = {0};
+    address.sin_family = AF_INET;
+    address.sin_port = htons(80);
+    inet_pton(AF_INET, "1.1.1.1", &address.sin_addr);
+
+    if (connect(fd, (struct sockaddr *)&address, sizeof(address)) < 0) {
+        printf("blocked");
+    } else {
+        printf("connected");
+    }
+    close(fd);
+    return 0;
+}
+"#;
+
+    let result = run_sandboxed(program_text, "");
+
+    assert_eq!(result.status, ExecutionStatus::Success);
+    assert_eq!(result.stdout_lossy(), "blocked");
+}
+
+#[test]
+fn test_sandbox_blocks_file_writes() {
+    let target =
+        std::env::temp_dir().join(format!("ccode_runner_sandbox_write_{}", std::process::id()));
+    let program_text = r#"
+#include <stdio.h>
+
+int main() {
+    FILE *file = fopen("TARGET", "w");
+    if (file == NULL) {
+        printf("blocked");
+        return 0;
+    }
+    fputs("escaped", file);
+    fclose(file);
+    printf("written");
+    return 0;
+}
+"#
+    .replace("TARGET", target.to_str().unwrap());
+
+    let result = run_sandboxed(&program_text, "");
+
+    assert_eq!(result.status, ExecutionStatus::Success);
+    assert_eq!(result.stdout_lossy(), "blocked");
+    assert!(!target.exists());
+}
+
+#[test]
+fn test_sandbox_is_killed_on_time_limit() {
+    let program_text = r#"
+int main() {
+    while (1) {
+    }
+}
+"#;
+
+    let limits = ExecutionLimits::new()
+        .with_time_limit(500)
+        .with_sandbox(SandboxConfig::new());
+    let program =
+        Language::new_from_text_with_limits(program_text, LanguageName::C, false, limits).unwrap();
+    let result = program.run_program_code("").unwrap();
+
+    assert_eq!(result.status, ExecutionStatus::TimeLimitExceeded);
+}
+
+#[test]
+fn test_sandbox_clears_environment() {
+    // Set by cargo for the test, standing in for a secret of the runner
+    assert!(std::env::var_os("CARGO_MANIFEST_DIR").is_some());
+    let program_text = r#"
+#include <stdio.h>
+#include <stdlib.h>
+
+int main() {
+    printf("%s ", getenv("PATH") != NULL ? "path" : "no-path");
+    printf("%s", getenv("CARGO_MANIFEST_DIR") != NULL ? "leaked" : "cleared");
+    return 0;
+}
+"#;
+
+    let result = run_sandboxed(program_text, "");
+
+    assert_eq!(result.status, ExecutionStatus::Success);
+    assert_eq!(result.stdout_lossy(), "path cleared");
+}
+
+#[test]
+fn test_sandbox_hides_host_files() {
+    let secret = std::env::temp_dir().join(format!(
+        "ccode_runner_sandbox_secret_{}",
+        std::process::id()
+    ));
+    std::fs::write(&secret, "secret").unwrap();
+    let program_text = r#"
+#include <stdio.h>
+
+int main() {
+    printf("%s ", fopen("SECRET", "r") == NULL ? "hidden" : "visible");
+    printf("%s", fopen("/etc/hostname", "r") == NULL ? "hidden" : "visible");
+    return 0;
+}
+"#
+    .replace("SECRET", secret.to_str().unwrap());
+
+    let result = run_sandboxed(&program_text, "");
+    std::fs::remove_file(&secret).unwrap();
+
+    assert_eq!(result.status, ExecutionStatus::Success);
+    assert_eq!(result.stdout_lossy(), "hidden hidden");
+}
+
+#[test]
+fn test_sandbox_blocks_unix_sockets() {
+    let socket_path = std::env::temp_dir().join(format!(
+        "ccode_runner_sandbox_socket_{}",
+        std::process::id()
+    ));
+    let listener = std::os::unix::net::UnixListener::bind(&socket_path).unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let program_text = r#"
+#include <stdio.h>
+#include <string.h>
+#include <sys/socket.h>
+#include <sys/un.h>
+#include <unistd.h>
+
+int main() {
+    int fd = socket(AF_UNIX, SOCK_STREAM, 0);
+    if (fd < 0) {
+        printf("blocked");
+        return 0;
+    }
+
+    struct sockaddr_un address = {0};
+    address.sun_family = AF_UNIX;
+    strncpy(address.sun_path, "SOCKET", sizeof(address.sun_path) - 1);
+
+    if (connect(fd, (struct sockaddr *)&address, sizeof(address)) < 0) {
+        printf("blocked");
+    } else {
+        printf("connected");
+    }
+    close(fd);
+    return 0;
+}
+"#
+    .replace("SOCKET", socket_path.to_str().unwrap());
+
+    let result = run_sandboxed(&program_text, "");
+    let accepted = listener.accept();
+    std::fs::remove_file(&socket_path).unwrap();
+
+    assert_eq!(result.status, ExecutionStatus::Success);
+    assert_eq!(result.stdout_lossy(), "blocked");
+    assert!(accepted.is_err());
+}
+
+#[test]
+fn test_sandbox_drops_supplementary_groups() {
+    // Only a privileged runner can drop its groups, which is given one to drop
+    if unsafe { libc::geteuid() } != 0 {
+        return;
+    }
+    let groups = [12345 as libc::gid_t];
+    assert_eq!(unsafe { libc::setgroups(1, groups.as_ptr()) }, 0);
+    let program_text = r#"
+#include <stdio.h>
+#include <unistd.h>
+
+int main() {
+    printf("%d", getgroups(0, NULL));
+    return 0;
+}
+"#;
+
+    let result = run_sandboxed(program_text, "");
+
+    assert_eq!(result.status, ExecutionStatus::Success);
+    assert_eq!(result.stdout_lossy(), "0");
+}
@@ -0,0 +1,85 @@
+use ccode_runner::ExecutionLimits;
+use ccode_runner::lang_runner::{
+    language_name::LanguageName,
+    program_store::ProgramStore,
+    runner_error_types::RunnerErrorType,
+    sanitizer::SanitizerKind,
+    toolchain::{BuildProfile, ToolchainConfig},
+};
+
+const CORRECT_PROGRAM: &str = "n = int(input())\nprint(n * 2 if n < 3 else 0)";
+
+/// Reads out of bounds for inputs of 3 or more, which usually goes unnoticed in release builds
+const OUT_OF_BOUNDS_PROGRAM: &str = r#"
+#include <iostream>
+#include <vector>
+int main() {
+    std::vector<int> doubled = {0, 2, 4};
+    int n;
+    std::cin >> n;
+    std::cout << doubled[n] << std::endl;
+}
+"#;
+
+fn program_store(test_text: &str, test_lang: LanguageName, profile: BuildProfile) -> ProgramStore {
+    ProgramStore::new_from_text_with_toolchain(
+        CORRECT_PROGRAM,
+        test_text,
+        LanguageName::Python,
+        test_lang,
+        false,
+        ExecutionLimits::default(),
+        &ToolchainConfig::default().with_profile(profile),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_debug_build_reports_out_of_bounds_access() {
+    let store = program_store(
+        OUT_OF_BOUNDS_PROGRAM,
+        LanguageName::Cpp,
+        BuildProfile::Debug,
+    );
+
+    let (matched, _, _) = store.run_codes_and_compare_output("2").unwrap();
+    assert!(matched);
+
+    let err = store.run_codes_and_compare_output("3").unwrap_err();
+    let RunnerErrorType::SanitizerError(_, report) = err.program_error() else {
+        panic!("Expected a sanitizer error, got {err}");
+    };
+    assert_eq!(report.kind, SanitizerKind::Address);
+    assert_eq!(report.error, "heap-buffer-overflow");
+    assert_eq!(report.location.as_ref().unwrap().line, 8);
+}
+
+#[test]
+fn test_debug_build_checks_rust_overflows() {
+    // `n * 100` overflows a u8 for 3, wrapping around to 44 in release builds, which happens to
+    // give the expected 0
+    let overflowing_program = r#"
+fn main() {
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line).unwrap();
+    let n: u8 = line.trim().parse().unwrap();
+    println!("{}", n * 100 / 100 * 2);
+}
+"#;
+
+    let release = program_store(
+        overflowing_program,
+        LanguageName::Rust,
+        BuildProfile::Release,
+    );
+    assert!(release.run_codes_and_compare_output("3").unwrap().0);
+
+    let debug = program_store(overflowing_program, LanguageName::Rust, BuildProfile::Debug);
+    let err = debug.run_codes_and_compare_output("3").unwrap_err();
+    let RunnerErrorType::SanitizerError(_, report) = err.program_error() else {
+        panic!("Expected a panic report, got {err}");
+    };
+    assert_eq!(report.kind, SanitizerKind::Panic);
+    assert_eq!(report.error, "attempt to multiply with overflow");
+    assert_eq!(report.location.as_ref().unwrap().line, 6);
+}
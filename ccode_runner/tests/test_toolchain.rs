@@ -0,0 +1,85 @@
+use ccode_runner::ExecutionLimits;
+use ccode_runner::lang_runner::{
+    language_name::LanguageName, program_store::ProgramStore, runner::Language,
+    runner_error_types::RunnerErrorType, toolchain::ToolchainConfig,
+};
+
+const ONLINE_JUDGE_PROGRAM: &str = r#"
+#include <stdio.h>
+
+int main() {
+#ifdef ONLINE_JUDGE
+    printf("judge\n");
+#else
+    printf("local\n");
+#endif
+    return 0;
+}
+"#;
+
+#[test]
+fn test_toolchain_compile_flags_are_used() {
+    let toolchains = ToolchainConfig::from_toml_str(
+        r#"
+[c]
+compile = [{ program = "gcc", args = ["-O2", "-DONLINE_JUDGE", "-o", "{output}", "{source}"] }]
+"#,
+    )
+    .unwrap();
+
+    let default_program =
+        Language::new_from_text(ONLINE_JUDGE_PROGRAM, LanguageName::C, false).unwrap();
+    let judge_program = Language::new_from_text_with_toolchain(
+        ONLINE_JUDGE_PROGRAM,
+        LanguageName::C,
+        false,
+        ExecutionLimits::default(),
+        &toolchains,
+    )
+    .unwrap();
+
+    assert_eq!(
+        default_program.run_program_code("").unwrap().stdout_lossy(),
+        "local\n"
+    );
+    assert_eq!(
+        judge_program.run_program_code("").unwrap().stdout_lossy(),
+        "judge\n"
+    );
+}
+
+#[test]
+fn test_toolchain_run_command_falls_back() {
+    let toolchains = ToolchainConfig::from_toml_str(
+        r#"
+[python]
+run = [
+    { program = "cpast-missing-python", args = ["{source}"] },
+    { program = "python3", args = ["-S", "{source}"] },
+]
+"#,
+    )
+    .unwrap();
+
+    let store = ProgramStore::new_from_text_with_toolchain(
+        "print(input())",
+        "print(input())",
+        LanguageName::Python,
+        LanguageName::Python,
+        false,
+        ExecutionLimits::default(),
+        &toolchains,
+    )
+    .unwrap();
+
+    let (matched, expected, _) = store.run_codes_and_compare_output("42").unwrap();
+    assert!(matched);
+    assert_eq!(expected, "42\n");
+}
+
+#[test]
+fn test_toolchain_config_rejects_unknown_language() {
+    let err =
+        ToolchainConfig::from_toml_str("[cobol]\nrun = [{ program = \"cobc\" }]").unwrap_err();
+    assert!(matches!(*err, RunnerErrorType::InvalidToolchainConfig(_)));
+}
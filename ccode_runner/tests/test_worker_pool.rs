@@ -0,0 +1,186 @@
+use ccode_runner::lang_runner::{
+    language_name::LanguageName, runner::Language, toolchain::ToolchainConfig,
+};
+use ccode_runner::{ExecutionLimits, ExecutionStatus, ResourceBackend};
+
+fn warm_program(source: &str, lang: LanguageName, limits: ExecutionLimits) -> Language {
+    Language::new_from_text_with_toolchain(
+        source,
+        lang,
+        false,
+        limits,
+        &ToolchainConfig::default().with_workers(1),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_python_runs_in_fresh_module_per_input() {
+    // Module state would pile up if the module was reused between inputs
+    let program = warm_program(
+        "import sys\nseen = []\nseen.append(int(input()))\nprint(len(seen), sum(map(int, sys.stdin.read().split())))",
+        LanguageName::Python,
+        ExecutionLimits::default(),
+    );
+
+    for (input, expected) in [("1\n2 3\n", "1 5\n"), ("4\n10\n", "1 10\n")] {
+        let result = program.run_program_code(input).unwrap();
+        assert!(result.is_success());
+        assert_eq!(result.resource_backend, ResourceBackend::Worker);
+        assert_eq!(result.stdout_lossy(), expected);
+    }
+}
+
+#[test]
+fn test_python_imports_modules_afresh_per_input() {
+    // The helper's counter would keep counting if the module was reused between inputs
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("helper.py"),
+        "counter = 0\n\ndef bump():\n    global counter\n    counter += 1\n    return counter\n",
+    )
+    .unwrap();
+    let main = dir.path().join("main.py");
+    std::fs::write(&main, "import helper\nprint(helper.bump())\n").unwrap();
+    let program = Language::new_from_custom_dest_with_toolchain(
+        &main,
+        None,
+        false,
+        ExecutionLimits::default(),
+        &ToolchainConfig::default().with_workers(1),
+    )
+    .unwrap();
+
+    for _ in 0..3 {
+        let result = program.run_program_code("").unwrap();
+        assert!(result.is_success());
+        assert_eq!(result.resource_backend, ResourceBackend::Worker);
+        assert_eq!(result.stdout_lossy(), "1\n");
+    }
+}
+
+#[test]
+fn test_python_exit_code_and_stderr() {
+    let program = warm_program(
+        "import sys\nprint('partial')\nprint('bad input', file=sys.stderr)\nsys.exit(3)",
+        LanguageName::Python,
+        ExecutionLimits::default(),
+    );
+
+    let result = program.run_program_code("").unwrap();
+    assert_eq!(result.status, ExecutionStatus::NonZeroExit(3));
+    assert_eq!(result.stdout_lossy(), "partial\n");
+    assert_eq!(result.stderr_lossy(), "bad input\n");
+    assert_eq!(result.resource_backend, ResourceBackend::Worker);
+}
+
+#[test]
+fn test_java_runs_in_fresh_class_loader_per_input() {
+    let program = warm_program(
+        r#"
+import java.util.Scanner;
+
+public class Main {
+    static int runs = 0;
+
+    public static void main(String[] args) {
+        long n = new Scanner(System.in).nextLong();
+        runs++;
+        System.out.println(n * n + " " + runs);
+    }
+}
+"#,
+        LanguageName::Java,
+        ExecutionLimits::default(),
+    );
+
+    for (input, expected) in [("3", "9 1\n"), ("12", "144 1\n")] {
+        let result = program.run_program_code(input).unwrap();
+        assert_eq!(result.resource_backend, ResourceBackend::Worker);
+        assert_eq!(result.stdout_lossy(), expected);
+    }
+}
+
+#[test]
+fn test_javascript_reads_input_either_way() {
+    let read_file = warm_program(
+        "const [a, b] = require('fs').readFileSync(0, 'utf8').split(' ').map(Number);\nconsole.log(a + b);",
+        LanguageName::Javascript,
+        ExecutionLimits::default(),
+    );
+    let result = read_file.run_program_code("2 3").unwrap();
+    assert_eq!(result.resource_backend, ResourceBackend::Worker);
+    assert_eq!(result.stdout_lossy(), "5\n");
+
+    let readline = warm_program(
+        "const rl = require('readline').createInterface({ input: process.stdin });\nrl.on('line', (line) => console.log(Number(line) * 2));",
+        LanguageName::Javascript,
+        ExecutionLimits::default(),
+    );
+    let result = readline.run_program_code("21\n").unwrap();
+    assert_eq!(result.resource_backend, ResourceBackend::Worker);
+    assert_eq!(result.stdout_lossy(), "42\n");
+}
+
+#[test]
+fn test_program_ending_the_worker_falls_back_to_fresh_processes() {
+    let program = warm_program(
+        "import os\nprint(int(input()) * 2, flush=True)\nos._exit(0)",
+        LanguageName::Python,
+        ExecutionLimits::default(),
+    );
+
+    for input in ["1", "2"] {
+        let result = program.run_program_code(input).unwrap();
+        assert!(result.is_success());
+        assert_ne!(result.resource_backend, ResourceBackend::Worker);
+        assert_eq!(
+            result.stdout_lossy().trim(),
+            (input.parse::<i32>().unwrap() * 2).to_string()
+        );
+    }
+}
+
+#[test]
+fn test_time_limit_kills_the_worker() {
+    let program = warm_program(
+        "n = int(input())\nwhile n == 0:\n    pass\nprint(n)",
+        LanguageName::Python,
+        ExecutionLimits::new().with_time_limit(500),
+    );
+
+    let result = program.run_program_code("0").unwrap();
+    assert_eq!(result.status, ExecutionStatus::TimeLimitExceeded);
+    assert_eq!(result.resource_backend, ResourceBackend::Worker);
+
+    // A new worker takes over
+    let result = program.run_program_code("7").unwrap();
+    assert!(result.is_success());
+    assert_eq!(result.stdout_lossy(), "7\n");
+}
+
+#[test]
+fn test_other_limits_need_fresh_processes() {
+    let program = warm_program(
+        "print(input())",
+        LanguageName::Python,
+        ExecutionLimits::new().with_memory_limit(512 * 1024 * 1024),
+    );
+
+    let result = program.run_program_code("hi").unwrap();
+    assert!(result.is_success());
+    assert_ne!(result.resource_backend, ResourceBackend::Worker);
+}
+
+#[tokio::test]
+async fn test_async_runs_use_workers() {
+    let program = warm_program(
+        "print(int(input()) + 1)",
+        LanguageName::Python,
+        ExecutionLimits::default(),
+    );
+
+    let result = program.run_program_code_async("41").await.unwrap();
+    assert_eq!(result.resource_backend, ResourceBackend::Worker);
+    assert_eq!(result.stdout_lossy(), "42\n");
+}
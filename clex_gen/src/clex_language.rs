@@ -5,6 +5,7 @@
 //! # Modules
 //!
 //! - `ast`: Defines the Abstract Syntax Tree (AST) for the `clex` language, representing the structure of code patterns.
+//! - `builder`: Provides a builder API for constructing the Abstract Syntax Tree (AST) programmatically in Rust.
 //! - `generator`: Implements the code generator responsible for producing code based on the `clex` language specification.
 //! - `lexer`: Provides lexical analysis capabilities for tokenizing input patterns in the `clex` language.
 //! - `parser`: Implements the parser for interpreting and structuring the `clex` language into an Abstract Syntax Tree (AST).
@@ -14,6 +15,7 @@
 //! providing a flexible way to define input patterns for code testing and analysis.
 //! For detailed usage and syntax rules, refer to the Grammar Rules for Clex Generator in README.
 pub mod ast;
+pub mod builder;
 pub mod clex_error_type;
 pub mod code_generator;
 pub mod lexer;
@@ -4,10 +4,13 @@
 //! # Types
 //!
 //! - `ClexLanguageAST`: The top-level AST type representing a program, consisting of a vector of `UnitExpression`.
-//! - `UnitExpression`: Enumerates different types of expressions within a program, including primitives, capturing groups, non-capturing groups, and an end-of-file marker.
-//! - `DataType`: Enumerates different data types that can be associated with expressions, such as integer, float, string, and character.
+//! - `UnitExpression`: Enumerates different types of expressions within a program, including primitives, capturing groups (of integers, string lengths and array lengths), non-capturing groups, and an end-of-file marker.
+//! - `DataType`: Enumerates different data types that can be associated with expressions, such as integer, float, string, ordered pair, unique point and interval.
 //! - `ReferenceType`: Enumerates different repetition types, including repetition by capturing group, repetition by count, and no repetition.
 //! - `PositiveReferenceType`: Same as `ReferenceType`, but guarantees positive value upon de-referencing.
+//! - `Distribution`: Enumerates the probability distributions numbers can be generated from, within their range.
+//!
+//! Each type implements `Display`, rendering it back to `clex` source, which parses to the same AST.
 //!
 //! The `ast` module provides a structured representation of the code patterns specified in the `clex` language,
 //! making it easier for other components of the `clex_language` module, such as the parser and generator, to process and manipulate the input patterns.
@@ -15,18 +18,19 @@
 //! # Example
 //!
 //! ```rust
-//! use clex_gen::clex_language::ast::{ClexLanguageAST, UnitExpression, DataType, ReferenceType, PositiveReferenceType};
+//! use clex_gen::clex_language::ast::{ClexLanguageAST, UnitExpression, DataType, Distribution, ReferenceType, PositiveReferenceType};
 //!
 //! // Define a simple program AST
 //! let program_ast = ClexLanguageAST {
 //!     expression: vec![
 //!         UnitExpression::Primitives {
-//!             data_type: DataType::Integer(ReferenceType::ByLiteral(0), ReferenceType::ByLiteral(100)),
+//!             data_type: DataType::Integer(ReferenceType::ByLiteral(0), ReferenceType::ByLiteral(100), Distribution::Uniform),
 //!             repetition: PositiveReferenceType::ByLiteral(1),
 //!         },
 //!         UnitExpression::CapturingGroup {
 //!             group_number: 1,
 //!             range: (PositiveReferenceType::ByLiteral(0), PositiveReferenceType::ByLiteral(10)),
+//!             distribution: Distribution::LogUniform,
 //!         },
 //!         UnitExpression::Eof,
 //!     ],
@@ -37,19 +41,22 @@
 
 // /// Represents a program consisting of a vector of `UnitExpression`.
 
+use std::fmt;
+
 //////////////////////////////////////////
 // CONSTANTS
 //////////////////////////////////////////
 pub const DEFAULT_MIN_STRING_SIZE: usize = 1;
 pub const DEFAULT_MAX_STRING_SIZE: usize = 12;
 pub const DEFAULT_CHARSET: CharacterSet = CharacterSet::AlphaNumeric;
+pub const DEFAULT_DISTRIBUTION: Distribution = Distribution::Uniform;
 pub const DEFAULT_QUANTIFIER_VALUE: u64 = 1;
 pub const DEFAULT_RANGE_MIN_VALUE: i64 = i32::MIN as i64;
 pub const DEFAULT_RANGE_MAX_VALUE: i64 = i32::MAX as i64;
 pub const DEFAULT_POSITIVE_RANGE_MIN_VALUE: u64 = u32::MIN as u64;
 pub const DEFAULT_POSITIVE_RANGE_MAX_VALUE: u64 = u32::MAX as u64;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ClexLanguageAST {
     pub expression: Vec<UnitExpression>,
 }
@@ -69,6 +76,20 @@ pub enum UnitExpression {
         // group_number won't exceed the value of total number of capturing group present before.
         group_number: u64,
         range: (PositiveReferenceType, PositiveReferenceType),
+        distribution: Distribution,
+    },
+    /// Capturing group unit expression which generates a string and captures its length.
+    StringCapturingGroup {
+        group_number: u64,
+        length: (PositiveReferenceType, PositiveReferenceType),
+        charset: CharacterSet,
+    },
+    /// Capturing group unit expression which generates nested expressions repeatedly and captures
+    /// the number of repetitions, i.e. the length of the generated array.
+    ArrayCapturingGroup {
+        group_number: u64,
+        nest_exp: Vec<UnitExpression>,
+        repetition: PositiveReferenceType,
     },
     /// Non-capturing group unit expression with nested expressions and repetition type.
     NonCapturingGroup {
@@ -82,12 +103,19 @@ pub enum UnitExpression {
 /// Represents the data type of unit expression.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DataType {
-    /// Integer data type with a specified minimum and maximum value (inclusive).
-    Integer(ReferenceType, ReferenceType),
-    /// Float data type with a specified minimum and maximum value (inclusive).
-    Float(ReferenceType, ReferenceType),
+    /// Integer data type with a specified minimum and maximum value (inclusive), and the distribution of values.
+    Integer(ReferenceType, ReferenceType, Distribution),
+    /// Float data type with a specified minimum and maximum value (inclusive), and the distribution of values.
+    Float(ReferenceType, ReferenceType, Distribution),
     /// String data type with specified minimum and maximum length.
     String(PositiveReferenceType, PositiveReferenceType, CharacterSet),
+    /// Ordered pair of integers `l r`, with minimum <= l <= r <= maximum.
+    OrderedPair(ReferenceType, ReferenceType),
+    /// Point with integer coordinates `x y` within the range, unique across the repetitions.
+    UniquePoint(ReferenceType, ReferenceType),
+    /// Interval of integers `l r` within the range, not overlapping with the other intervals
+    /// across the repetitions, which are generated in increasing order.
+    Interval(ReferenceType, ReferenceType),
 }
 
 /// Represents the repetition type of unit expression.
@@ -108,6 +136,25 @@ pub enum PositiveReferenceType {
     ByLiteral(u64),
 }
 
+/// Represent probability distribution of numbers within their range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distribution {
+    // DIST_UNIFORM, every value is equally likely
+    Uniform,
+    // DIST_LOG_UNIFORM, every order of magnitude (from the minimum) is equally likely
+    LogUniform,
+    // DIST_BIASED_MAX, maximum of three uniform values, so values near the maximum are more likely
+    BiasedMax,
+    // DIST_NORMAL, centered in the range with a standard deviation of a sixth of it, clamped to the range
+    Normal,
+}
+
+impl Distribution {
+    pub fn get_default_distribution() -> Distribution {
+        DEFAULT_DISTRIBUTION
+    }
+}
+
 /// Represent character set for string domain
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CharacterSet {
@@ -154,3 +201,263 @@ impl CharacterSet {
         }
     }
 }
+
+impl From<i64> for ReferenceType {
+    fn from(value: i64) -> Self {
+        ReferenceType::ByLiteral(value)
+    }
+}
+
+impl From<u64> for PositiveReferenceType {
+    fn from(value: u64) -> Self {
+        PositiveReferenceType::ByLiteral(value)
+    }
+}
+
+//////////////////////////////////////////
+// SCALING
+//////////////////////////////////////////
+
+impl ClexLanguageAST {
+    /// Returns the program with each capturing group fixed at `scale` of the way through its range,
+    /// from its minimum at `0.0` to its maximum at `1.0`, e.g. to generate inputs of increasing size.
+    ///
+    /// Integer and string length capturing groups are scaled, when both their bounds are literals.
+    /// Groups bounded by other groups follow the scaled values of those groups.
+    pub fn scale_capturing_groups(&self, scale: f64) -> ClexLanguageAST {
+        ClexLanguageAST {
+            expression: scale_expressions(&self.expression, scale.clamp(0.0, 1.0)),
+        }
+    }
+}
+
+fn scale_expressions(expressions: &[UnitExpression], scale: f64) -> Vec<UnitExpression> {
+    expressions
+        .iter()
+        .map(|expression| match expression {
+            UnitExpression::CapturingGroup {
+                group_number,
+                range,
+                distribution,
+            } => UnitExpression::CapturingGroup {
+                group_number: *group_number,
+                range: scale_range(range, scale),
+                distribution: *distribution,
+            },
+            UnitExpression::StringCapturingGroup {
+                group_number,
+                length,
+                charset,
+            } => UnitExpression::StringCapturingGroup {
+                group_number: *group_number,
+                length: scale_range(length, scale),
+                charset: charset.clone(),
+            },
+            UnitExpression::ArrayCapturingGroup {
+                group_number,
+                nest_exp,
+                repetition,
+            } => UnitExpression::ArrayCapturingGroup {
+                group_number: *group_number,
+                nest_exp: scale_expressions(nest_exp, scale),
+                repetition: repetition.clone(),
+            },
+            UnitExpression::NonCapturingGroup {
+                nest_exp,
+                repetition,
+            } => UnitExpression::NonCapturingGroup {
+                nest_exp: scale_expressions(nest_exp, scale),
+                repetition: repetition.clone(),
+            },
+            expression => expression.clone(),
+        })
+        .collect()
+}
+
+fn scale_range(
+    range: &(PositiveReferenceType, PositiveReferenceType),
+    scale: f64,
+) -> (PositiveReferenceType, PositiveReferenceType) {
+    match range {
+        (PositiveReferenceType::ByLiteral(min), PositiveReferenceType::ByLiteral(max))
+            if min <= max =>
+        {
+            let value = min + ((max - min) as f64 * scale).round() as u64;
+            (value.into(), value.into())
+        }
+        range => range.clone(),
+    }
+}
+
+//////////////////////////////////////////
+// RENDERING BACK TO CLEX
+//////////////////////////////////////////
+
+/// Renders the program back to `clex` source, which parses to the same AST.
+impl fmt::Display for ClexLanguageAST {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_expressions(f, &self.expression)
+    }
+}
+
+fn write_expressions(f: &mut fmt::Formatter, expressions: &[UnitExpression]) -> fmt::Result {
+    let mut is_first = true;
+    for expression in expressions {
+        if *expression == UnitExpression::Eof {
+            continue;
+        }
+        if !is_first {
+            write!(f, " ")?;
+        }
+        write!(f, "{expression}")?;
+        is_first = false;
+    }
+    Ok(())
+}
+
+fn write_quantifier(f: &mut fmt::Formatter, repetition: &PositiveReferenceType) -> fmt::Result {
+    if *repetition == PositiveReferenceType::ByLiteral(DEFAULT_QUANTIFIER_VALUE) {
+        Ok(())
+    } else {
+        write!(f, "{{{repetition}}}")
+    }
+}
+
+fn write_range(
+    f: &mut fmt::Formatter,
+    min: &impl fmt::Display,
+    max: &impl fmt::Display,
+    distribution: &Distribution,
+) -> fmt::Result {
+    if *distribution == DEFAULT_DISTRIBUTION {
+        write!(f, "[{min},{max}]")
+    } else {
+        write!(f, "[{min},{max},{distribution}]")
+    }
+}
+
+impl fmt::Display for UnitExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnitExpression::Primitives {
+                data_type,
+                repetition,
+            } => {
+                write!(f, "{data_type}")?;
+                write_quantifier(f, repetition)
+            }
+            UnitExpression::CapturingGroup {
+                range: (min, max),
+                distribution,
+                ..
+            } => {
+                write!(f, "(N")?;
+                write_range(f, min, max, distribution)?;
+                write!(f, ")")
+            }
+            UnitExpression::StringCapturingGroup {
+                length: (min_length, max_length),
+                charset,
+                ..
+            } => write!(f, "(S[{min_length},{max_length},{charset}])"),
+            UnitExpression::ArrayCapturingGroup {
+                nest_exp,
+                repetition,
+                ..
+            } => {
+                write!(f, "((?:")?;
+                write_expressions(f, nest_exp)?;
+                write!(f, "){{{repetition}}})")
+            }
+            UnitExpression::NonCapturingGroup {
+                nest_exp,
+                repetition,
+            } => {
+                write!(f, "(?:")?;
+                write_expressions(f, nest_exp)?;
+                write!(f, ")")?;
+                write_quantifier(f, repetition)
+            }
+            UnitExpression::Eof => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DataType::Integer(min, max, distribution) => {
+                write!(f, "N")?;
+                write_range(f, min, max, distribution)
+            }
+            DataType::Float(min, max, distribution) => {
+                write!(f, "F")?;
+                write_range(f, min, max, distribution)
+            }
+            DataType::String(min_length, max_length, charset) => {
+                write!(f, "S[{min_length},{max_length},{charset}]")
+            }
+            DataType::OrderedPair(min, max) => write!(f, "R[{min},{max}]"),
+            DataType::UniquePoint(min, max) => write!(f, "P[{min},{max}]"),
+            DataType::Interval(min, max) => write!(f, "I[{min},{max}]"),
+        }
+    }
+}
+
+impl fmt::Display for ReferenceType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReferenceType::ByGroup { group_number } => write!(f, "\\{group_number}"),
+            ReferenceType::ByLiteral(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl fmt::Display for PositiveReferenceType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PositiveReferenceType::ByGroup { group_number } => write!(f, "\\{group_number}"),
+            PositiveReferenceType::ByLiteral(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Distribution::Uniform => write!(f, "@DIST_UNIFORM@"),
+            Distribution::LogUniform => write!(f, "@DIST_LOG_UNIFORM@"),
+            Distribution::BiasedMax => write!(f, "@DIST_BIASED_MAX@"),
+            Distribution::Normal => write!(f, "@DIST_NORMAL@"),
+        }
+    }
+}
+
+impl fmt::Display for CharacterSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CharacterSet::Alphabet => write!(f, "@CH_ALPHA@"),
+            CharacterSet::Numeric => write!(f, "@CH_NUM@"),
+            CharacterSet::Newline => write!(f, "@CH_NEWLINE@"),
+            CharacterSet::AlphaNumeric => write!(f, "@CH_ALNUM@"),
+            CharacterSet::Uppercase => write!(f, "@CH_UPPER@"),
+            CharacterSet::LowerCase => write!(f, "@CH_LOWER@"),
+            CharacterSet::All => write!(f, "@CH_ALL@"),
+            CharacterSet::Custom(charset) => {
+                write!(f, "'")?;
+                for character in charset.chars() {
+                    match character {
+                        '\n' => write!(f, "\\n")?,
+                        '\t' => write!(f, "\\t")?,
+                        '\r' => write!(f, "\\r")?,
+                        '\0' => write!(f, "\\0")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\'' => write!(f, "\\'")?,
+                        _ => write!(f, "{character}")?,
+                    }
+                }
+                write!(f, "'")
+            }
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! The `builder` module provides an API for constructing a `ClexLanguageAST` programmatically in Rust,
+//! without writing (and escaping) `clex` source code.
+//!
+//! # Types
+//!
+//! - `ClexBuilder`: Builds the expressions of a program, one unit expression at a time.
+//! - `GroupRef`: Handle to a capturing group, which can be used wherever a reference is expected.
+//! - `PrimitiveHandle`: Handle to the last added primitive, to set its repetition.
+//! - `NumberHandle`: Handle to the last added integer or float, to also set its distribution.
+//!
+//! Capturing groups are numbered the same way as the parser does, so a built AST and the AST parsed
+//! from its `Display` output are identical.
+//!
+//! # Example
+//!
+//! ```rust
+//! use clex_gen::clex_language::ast::{CharacterSet, Distribution};
+//! use clex_gen::clex_language::builder::ClexBuilder;
+//! use clex_gen::clex_language::code_generator::Generator;
+//!
+//! let mut builder = ClexBuilder::new();
+//! let n = builder.capture_integer(1, 10);
+//! builder.repeat(n, |element| {
+//!     element
+//!         .integer(1, 1_000_000_000)
+//!         .distribution(Distribution::LogUniform);
+//! });
+//! builder.string(1, 5, CharacterSet::Uppercase).times(2);
+//!
+//! let ast = builder.build();
+//! assert_eq!(
+//!     ast.to_string(),
+//!     "(N[1,10]) (?:N[1,1000000000,@DIST_LOG_UNIFORM@]){\\1} S[1,5,@CH_UPPER@]{2}"
+//! );
+//!
+//! let testcase = Generator::from_ast(ast).generate_testcases().unwrap();
+//! ```
+
+use crate::clex_language::ast::{
+    CharacterSet, ClexLanguageAST, DataType, Distribution, PositiveReferenceType, ReferenceType,
+    UnitExpression,
+};
+
+/// Handle to a capturing group, which can be used wherever a reference is expected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroupRef {
+    group_number: u64,
+}
+
+impl GroupRef {
+    pub fn group_number(&self) -> u64 {
+        self.group_number
+    }
+}
+
+impl From<GroupRef> for ReferenceType {
+    fn from(group: GroupRef) -> Self {
+        ReferenceType::ByGroup {
+            group_number: group.group_number,
+        }
+    }
+}
+
+impl From<GroupRef> for PositiveReferenceType {
+    fn from(group: GroupRef) -> Self {
+        PositiveReferenceType::ByGroup {
+            group_number: group.group_number,
+        }
+    }
+}
+
+/// Builds the unit expressions of a `ClexLanguageAST`.
+#[derive(Debug, Default)]
+pub struct ClexBuilder {
+    expression: Vec<UnitExpression>,
+    current_group: u64, // for capturing groupCount, starts from 1.....
+}
+
+impl ClexBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an integer within the range (inclusive).
+    pub fn integer(
+        &mut self,
+        min: impl Into<ReferenceType>,
+        max: impl Into<ReferenceType>,
+    ) -> NumberHandle<'_> {
+        NumberHandle {
+            primitive: self.primitive(DataType::Integer(
+                min.into(),
+                max.into(),
+                Distribution::get_default_distribution(),
+            )),
+        }
+    }
+
+    /// Adds a float within the range (inclusive).
+    pub fn float(
+        &mut self,
+        min: impl Into<ReferenceType>,
+        max: impl Into<ReferenceType>,
+    ) -> NumberHandle<'_> {
+        NumberHandle {
+            primitive: self.primitive(DataType::Float(
+                min.into(),
+                max.into(),
+                Distribution::get_default_distribution(),
+            )),
+        }
+    }
+
+    /// Adds a string with length within the range (inclusive), made of characters from the charset.
+    pub fn string(
+        &mut self,
+        min_length: impl Into<PositiveReferenceType>,
+        max_length: impl Into<PositiveReferenceType>,
+        charset: CharacterSet,
+    ) -> PrimitiveHandle<'_> {
+        self.primitive(DataType::String(
+            min_length.into(),
+            max_length.into(),
+            charset,
+        ))
+    }
+
+    /// Adds an ordered pair `l r`, with min <= l <= r <= max.
+    pub fn ordered_pair(
+        &mut self,
+        min: impl Into<ReferenceType>,
+        max: impl Into<ReferenceType>,
+    ) -> PrimitiveHandle<'_> {
+        self.primitive(DataType::OrderedPair(min.into(), max.into()))
+    }
+
+    /// Adds a point `x y` within the range, unique across the repetitions of the primitive.
+    pub fn unique_point(
+        &mut self,
+        min: impl Into<ReferenceType>,
+        max: impl Into<ReferenceType>,
+    ) -> PrimitiveHandle<'_> {
+        self.primitive(DataType::UniquePoint(min.into(), max.into()))
+    }
+
+    /// Adds an interval `l r` within the range, not overlapping across the repetitions of the primitive.
+    pub fn interval(
+        &mut self,
+        min: impl Into<ReferenceType>,
+        max: impl Into<ReferenceType>,
+    ) -> PrimitiveHandle<'_> {
+        self.primitive(DataType::Interval(min.into(), max.into()))
+    }
+
+    /// Adds a capturing group of a non-negative integer within the range (inclusive).
+    pub fn capture_integer(
+        &mut self,
+        min: impl Into<PositiveReferenceType>,
+        max: impl Into<PositiveReferenceType>,
+    ) -> GroupRef {
+        self.capture_integer_with_distribution(min, max, Distribution::get_default_distribution())
+    }
+
+    /// Same as `capture_integer`, but with integers generated from the distribution.
+    pub fn capture_integer_with_distribution(
+        &mut self,
+        min: impl Into<PositiveReferenceType>,
+        max: impl Into<PositiveReferenceType>,
+        distribution: Distribution,
+    ) -> GroupRef {
+        let group = self.next_group();
+        self.expression.push(UnitExpression::CapturingGroup {
+            group_number: group.group_number,
+            range: (min.into(), max.into()),
+            distribution,
+        });
+        group
+    }
+
+    /// Adds a string like `string`, and captures its length.
+    pub fn capture_string_length(
+        &mut self,
+        min_length: impl Into<PositiveReferenceType>,
+        max_length: impl Into<PositiveReferenceType>,
+        charset: CharacterSet,
+    ) -> GroupRef {
+        let group = self.next_group();
+        self.expression.push(UnitExpression::StringCapturingGroup {
+            group_number: group.group_number,
+            length: (min_length.into(), max_length.into()),
+            charset,
+        });
+        group
+    }
+
+    /// Adds the expressions built in the closure, repeated by the count (like a non-capturing group).
+    pub fn repeat(
+        &mut self,
+        count: impl Into<PositiveReferenceType>,
+        build_nested: impl FnOnce(&mut ClexBuilder),
+    ) -> &mut Self {
+        let nest_exp = self.build_nested(build_nested);
+        self.expression.push(UnitExpression::NonCapturingGroup {
+            nest_exp,
+            repetition: count.into(),
+        });
+        self
+    }
+
+    /// Same as `repeat`, but also captures the number of repetitions, i.e. the array length.
+    pub fn capture_repeat(
+        &mut self,
+        count: impl Into<PositiveReferenceType>,
+        build_nested: impl FnOnce(&mut ClexBuilder),
+    ) -> GroupRef {
+        // Numbered before the nested groups, like its opening parenthesis comes first in clex
+        let group = self.next_group();
+        let nest_exp = self.build_nested(build_nested);
+        self.expression.push(UnitExpression::ArrayCapturingGroup {
+            group_number: group.group_number,
+            nest_exp,
+            repetition: count.into(),
+        });
+        group
+    }
+
+    /// Finishes building, returning the AST ready for `Generator::from_ast`.
+    pub fn build(mut self) -> ClexLanguageAST {
+        self.expression.push(UnitExpression::Eof);
+        ClexLanguageAST {
+            expression: self.expression,
+        }
+    }
+
+    fn primitive(&mut self, data_type: DataType) -> PrimitiveHandle<'_> {
+        self.expression.push(UnitExpression::Primitives {
+            data_type,
+            repetition: PositiveReferenceType::ByLiteral(
+                crate::clex_language::ast::DEFAULT_QUANTIFIER_VALUE,
+            ),
+        });
+
+        let expression = self.expression.len() - 1;
+        PrimitiveHandle {
+            expression: &mut self.expression[expression],
+        }
+    }
+
+    fn build_nested(&mut self, build_nested: impl FnOnce(&mut ClexBuilder)) -> Vec<UnitExpression> {
+        let mut nested = ClexBuilder {
+            expression: Vec::new(),
+            current_group: self.current_group,
+        };
+        build_nested(&mut nested);
+        self.current_group = nested.current_group;
+        nested.expression
+    }
+
+    fn next_group(&mut self) -> GroupRef {
+        self.current_group += 1;
+        GroupRef {
+            group_number: self.current_group,
+        }
+    }
+}
+
+/// Handle to the last added primitive, to set its repetition.
+///
+/// Only integers and floats take a distribution, through their `NumberHandle`:
+///
+/// ```compile_fail
+/// use clex_gen::clex_language::ast::{CharacterSet, Distribution};
+/// use clex_gen::clex_language::builder::ClexBuilder;
+///
+/// let mut builder = ClexBuilder::new();
+/// builder
+///     .string(1, 5, CharacterSet::Alphabet)
+///     .distribution(Distribution::LogUniform);
+/// ```
+#[derive(Debug)]
+pub struct PrimitiveHandle<'a> {
+    expression: &'a mut UnitExpression,
+}
+
+impl PrimitiveHandle<'_> {
+    /// Repeats the primitive by the count, given as a literal or a captured group.
+    pub fn times(self, count: impl Into<PositiveReferenceType>) -> Self {
+        if let UnitExpression::Primitives { repetition, .. } = self.expression {
+            *repetition = count.into();
+        }
+        self
+    }
+}
+
+/// Handle to the last added integer or float, to set its repetition and distribution.
+#[derive(Debug)]
+pub struct NumberHandle<'a> {
+    primitive: PrimitiveHandle<'a>,
+}
+
+impl NumberHandle<'_> {
+    /// Repeats the number by the count, given as a literal or a captured group.
+    pub fn times(self, count: impl Into<PositiveReferenceType>) -> Self {
+        NumberHandle {
+            primitive: self.primitive.times(count),
+        }
+    }
+
+    /// Sets the distribution the number is drawn from.
+    pub fn distribution(self, distribution: Distribution) -> Self {
+        if let UnitExpression::Primitives {
+            data_type: DataType::Integer(_, _, current) | DataType::Float(_, _, current),
+            ..
+        } = self.primitive.expression
+        {
+            *current = distribution;
+        }
+        self
+    }
+}
@@ -33,8 +33,10 @@ pub enum ClexErrorType {
     UnknownCharacter(ParentErrorType, Span, &'static str),
     /// Error when an @ symbol is not properly closed
     UnclosedAtSymbol(ParentErrorType, Span),
-    /// Error when an invalid character set is specified, all valid character sets are specified in <https://github.com/rootCircle/cpast_mono/blob/main/clex_gen/docs/CLEX_LANG_SPECS.md#character>
+    /// Error when an invalid character set or distribution is specified, all valid character sets are specified in <https://github.com/rootCircle/cpast_mono/blob/main/clex_gen/docs/CLEX_LANG_SPECS.md#character>
     InvalidCharacterSet(ParentErrorType, Span),
+    /// Error when a named group `(?<name>...)` has an invalid or unterminated name
+    InvalidGroupName(ParentErrorType, Span),
 
     // Parser Errors
     /// Error when a non-capturing group is missing closing parenthesis
@@ -57,12 +59,32 @@ pub enum ClexErrorType {
     UnexpectedToken(ParentErrorType, Span, TokenType),
     /// Error when unreachable code is executed
     UnreachableCodeReached(ParentErrorType, Span),
+    /// Error when a distribution is given for a data type which doesn't support it
+    DistributionNotSupported(ParentErrorType, Span),
+    /// Error when the third field of a range isn't a known distribution
+    InvalidDistribution(ParentErrorType, Span),
+    /// Error when two capturing groups share the same name
+    DuplicateGroupName(ParentErrorType, Span, String),
+    /// Error when referencing a group name that hasn't been defined earlier
+    UnknownGroupName(ParentErrorType, Span, String),
+    /// Error when a `let` definition is malformed or isn't at the top level
+    InvalidDefinition(ParentErrorType, Span),
+    /// Error when two definitions share the same name
+    DuplicateDefinition(ParentErrorType, Span, String),
+    /// Error when using a definition that hasn't been defined earlier
+    UnknownDefinition(ParentErrorType, Span, String),
+    /// Error when an imported file contains anything other than definitions
+    ExpressionInImportedFile(ParentErrorType, Span),
+    /// Error when a clex file can't be read, parsed or is imported circularly, with its path and reason
+    ImportError(ParentErrorType, Span, String, String),
 
     // Generator Errors
     /// Error when range values are invalid
     InvalidRangeValues(ParentErrorType, Span, i64, i64),
     /// Error when referencing an unknown group number
     UnknownGroupNumber(ParentErrorType, Span, u64),
+    /// Error when more distinct values are requested than the range can hold
+    NotEnoughDistinctValues(ParentErrorType, Span, u64),
 }
 
 impl fmt::Display for ClexErrorType {
@@ -146,28 +168,39 @@ impl ClexErrorType {
     fn get_error_message(&self) -> String {
         match self {
             ClexErrorType::UnclosedSingleQuotes(_, _) => "expected closing single quote (')\n  = help: strings must be enclosed in single quotes like 'text'".to_string(),
-            ClexErrorType::MissingColonAfterQuestionMark(_, _) => "expected colon (:) after question mark\n  = help: non-capturing groups use the syntax (?:...)".to_string(),
+            ClexErrorType::MissingColonAfterQuestionMark(_, _) => "expected colon (:) after question mark\n  = help: non-capturing groups use the syntax (?:...), named groups use (?<name>...)".to_string(),
             ClexErrorType::MissingNumberAfterNegativeSign(_, _) => "expected a number after negative sign (-)\n  = help: negative signs must be followed by digits, e.g., -5".to_string(),
             ClexErrorType::NumericParsingError(_, _) => "failed to parse number\n  = help: numbers must be valid integers within the valid range".to_string(),
             ClexErrorType::UnknownCharacter(_, _, c) => format!("unexpected character '{}'\n  = help: this character is not valid in clex syntax", c),
             ClexErrorType::UnclosedAtSymbol(_, _) => "unclosed @ symbol\n  = help: character sets must be enclosed in @ symbols like @CH_UPPER@".to_string(),
             ClexErrorType::MissingClosingParensNonCapturingGroup(_, _) => "expected closing parenthesis ')' in non-capturing group\n  = help: non-capturing groups must be closed like (?:N)".to_string(),
-            ClexErrorType::UnclosedParens(_, _) => "expected valid content after opening parenthesis\n  = help: use (N[min,max]) for capturing groups or (?:...) for non-capturing groups".to_string(),
+            ClexErrorType::UnclosedParens(_, _) => "expected valid content after opening parenthesis\n  = help: use (N[min,max]), (S[min,max,charset]) or ((?:...){count}) for capturing groups, optionally named like (?<name>N), or (?:...) for non-capturing groups".to_string(),
             ClexErrorType::InvalidTokenFound(_, _, token_type) => format!("unexpected token: {:?}\n  = help: this token is not valid at this position", token_type),
-            ClexErrorType::InvalidCharacterSet(_, _) => "invalid character set name\n  = help: valid character sets are: CH_UPPER, CH_LOWER, CH_ALL, CH_NUM, CH_ALPHA, CH_ALNUM, CH_NEWLINE\n  = example: @CH_UPPER@ for uppercase letters".to_string(),
+            ClexErrorType::InvalidGroupName(_, _) => "invalid group name\n  = help: named groups use the syntax (?<name>...) where name starts with a lowercase letter or underscore, e.g., (?<n>N[1,100])".to_string(),
+            ClexErrorType::InvalidCharacterSet(_, _) => "invalid character set or distribution name\n  = help: valid character sets are: CH_UPPER, CH_LOWER, CH_ALL, CH_NUM, CH_ALPHA, CH_ALNUM, CH_NEWLINE\n  = help: valid distributions are: DIST_UNIFORM, DIST_LOG_UNIFORM, DIST_BIASED_MAX, DIST_NORMAL\n  = example: @CH_UPPER@ for uppercase letters".to_string(),
 
             ClexErrorType::MissingCommaRangeExpression(_, _) => "expected comma in range expression\n  = help: ranges use the format [min,max], e.g., N[1,10]".to_string(),
             ClexErrorType::MissingSquareBracketsRangeExpression(_, _) => "expected closing square bracket (']') in range expression\n  = help: ranges must be closed like [1,10]".to_string(),
 
             ClexErrorType::NegativeGroupNumber(_, _) => "group number must be positive\n  = help: back-references like {\\1} must refer to a valid group number (1 or higher)".to_string(),
-            ClexErrorType::MissingGroupNumber(_, _) => "expected group number after '{\\'\n  = help: quantifiers use the format {\\N} where N is a group number, e.g., {\\1}".to_string(),
+            ClexErrorType::MissingGroupNumber(_, _) => "expected group number or name after '\\'\n  = help: back-references use a group number or name, e.g., {\\1}, {\\n} or {\\{n}}".to_string(),
             ClexErrorType::NegativeValueInPositiveReference(_, _) => "value cannot be negative in this context\n  = help: only positive numbers are allowed in ranges and repetitions".to_string(),
 
             ClexErrorType::UnexpectedToken(_, _, token_type) => format!("expected {:?} but found something else\n  = help: check your syntax at this position", token_type),
             ClexErrorType::UnreachableCodeReached(_, _) => "internal error: reached unreachable code\n  = note: this is a bug in the parser, please report it".to_string(),
+            ClexErrorType::DistributionNotSupported(_, _) => "distributions are only supported for N and F\n  = help: remove the distribution from the range, e.g., R[1,10]".to_string(),
+            ClexErrorType::InvalidDistribution(_, _) => "expected a distribution after ',' in range expression\n  = help: use one of @DIST_UNIFORM@, @DIST_LOG_UNIFORM@, @DIST_BIASED_MAX@ or @DIST_NORMAL@, e.g., N[1,10,@DIST_LOG_UNIFORM@]".to_string(),
+            ClexErrorType::DuplicateGroupName(_, _, name) => format!("group name '{}' is already defined\n  = help: each named group must have a unique name", name),
+            ClexErrorType::InvalidDefinition(_, _) => "expected a definition like `let name = <expression>`\n  = help: definitions must be at the top level, and their names start with a lowercase letter or underscore".to_string(),
+            ClexErrorType::DuplicateDefinition(_, _, name) => format!("'{}' is already defined\n  = help: each definition must have a unique name, including the ones imported", name),
+            ClexErrorType::UnknownDefinition(_, _, name) => format!("use of undefined definition '{}'\n  = help: define it earlier with `let {} = ...` or import a file defining it", name, name),
+            ClexErrorType::ExpressionInImportedFile(_, _) => "imported files may only contain definitions\n  = help: wrap the expression in a definition like `let name = <expression>`".to_string(),
+            ClexErrorType::ImportError(_, _, path, reason) => format!("failed to import '{}'\n  = note: {}", path, reason),
+            ClexErrorType::UnknownGroupName(_, _, name) => format!("reference to undefined group '{}'\n  = help: you can only reference groups that have been defined earlier\n  = note: named groups are defined with (?<{}>N[...]) syntax", name, name),
 
             ClexErrorType::InvalidRangeValues(_, _, min, max) => format!("invalid range: minimum ({}) is greater than maximum ({})\n  = help: the first number in a range must be less than or equal to the second\n  = example: use [1,10] not [10,1]", min, max),
-            ClexErrorType::UnknownGroupNumber(_, _, group_number) => format!("reference to undefined group {}\n  = help: you can only reference groups that have been defined earlier\n  = note: groups are defined with (N[...]) syntax", group_number),
+            ClexErrorType::NotEnoughDistinctValues(_, _, count) => format!("can't generate {} distinct values within the range\n  = help: unique points and non-overlapping intervals need a range large enough to hold all of them", count),
+            ClexErrorType::UnknownGroupNumber(_, _, group_number) => format!("reference to undefined group {}\n  = help: you can only reference groups that have been defined earlier\n  = note: groups are defined with (N[...]), (S[...]) or ((?:...){{...}}) syntax", group_number),
         }
     }
 
@@ -188,6 +221,17 @@ impl ClexErrorType {
             | ClexErrorType::NegativeValueInPositiveReference(_, span)
             | ClexErrorType::UnexpectedToken(_, span, _)
             | ClexErrorType::UnreachableCodeReached(_, span)
+            | ClexErrorType::DuplicateGroupName(_, span, _)
+            | ClexErrorType::UnknownGroupName(_, span, _)
+            | ClexErrorType::InvalidGroupName(_, span)
+            | ClexErrorType::DistributionNotSupported(_, span)
+            | ClexErrorType::InvalidDistribution(_, span)
+            | ClexErrorType::NotEnoughDistinctValues(_, span, _)
+            | ClexErrorType::InvalidDefinition(_, span)
+            | ClexErrorType::DuplicateDefinition(_, span, _)
+            | ClexErrorType::UnknownDefinition(_, span, _)
+            | ClexErrorType::ExpressionInImportedFile(_, span)
+            | ClexErrorType::ImportError(_, span, _, _)
             | ClexErrorType::InvalidRangeValues(_, span, _, _)
             | ClexErrorType::UnknownGroupNumber(_, span, _)
             | ClexErrorType::UnclosedAtSymbol(_, span)
@@ -212,6 +256,17 @@ impl ClexErrorType {
             | ClexErrorType::NegativeValueInPositiveReference(parent_type, _)
             | ClexErrorType::UnexpectedToken(parent_type, _, _)
             | ClexErrorType::UnreachableCodeReached(parent_type, _)
+            | ClexErrorType::DuplicateGroupName(parent_type, _, _)
+            | ClexErrorType::UnknownGroupName(parent_type, _, _)
+            | ClexErrorType::InvalidGroupName(parent_type, _)
+            | ClexErrorType::DistributionNotSupported(parent_type, _)
+            | ClexErrorType::InvalidDistribution(parent_type, _)
+            | ClexErrorType::NotEnoughDistinctValues(parent_type, _, _)
+            | ClexErrorType::InvalidDefinition(parent_type, _)
+            | ClexErrorType::DuplicateDefinition(parent_type, _, _)
+            | ClexErrorType::UnknownDefinition(parent_type, _, _)
+            | ClexErrorType::ExpressionInImportedFile(parent_type, _)
+            | ClexErrorType::ImportError(parent_type, _, _, _)
             | ClexErrorType::InvalidRangeValues(parent_type, _, _, _)
             | ClexErrorType::UnknownGroupNumber(parent_type, _, _)
             | ClexErrorType::UnclosedAtSymbol(parent_type, _)
@@ -6,16 +6,18 @@
 //!
 //! - `Generator`: The main struct that handles test case generation from an AST
 //! - Random value generation for different data types (Integer, Float, String)
-//! - Support for capturing and non-capturing groups
+//! - Support for capturing (integer, string length and array length) and non-capturing groups
 //! - Reference resolution for group values
 //!
 //! # Features
 //!
-//! - Random number generation within specified ranges
+//! - Random number generation within specified ranges, following uniform, log-uniform, biased-to-max
+//!   or normal distributions
 //! - String generation with customizable character sets
 //! - Support for nested expressions and repetitions
 //! - Group value tracking and reference resolution
 //! - Float, Integer and String data type generation
+//! - Ordered pairs, unique points and non-overlapping intervals, generated together across repetitions
 //!
 //! # Example
 //!
@@ -40,13 +42,15 @@
 //! and other potential generation-time issues.
 
 use crate::clex_language::ast::{
-    CharacterSet, ClexLanguageAST, DataType, PositiveReferenceType, ReferenceType, UnitExpression,
+    CharacterSet, ClexLanguageAST, DataType, Distribution, PositiveReferenceType, ReferenceType,
+    UnitExpression,
 };
 use crate::clex_language::parser::Parser;
 use rand::RngExt;
+use rand::seq::SliceRandom;
 
 use crate::clex_language::clex_error_type::{ClexErrorType, ParentErrorType};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug)]
 pub struct Generator {
@@ -60,7 +64,8 @@ impl Generator {
         }
     }
 
-    fn new_from_program(program: ClexLanguageAST) -> Self {
+    /// Creates a generator directly from an AST, e.g. one constructed using the `builder` module.
+    pub fn from_ast(program: ClexLanguageAST) -> Self {
         Self {
             syntax_tree: program,
         }
@@ -83,16 +88,69 @@ impl Generator {
                     let repetition_count =
                         self.get_positive_value_from_reference(repetition, groups)?;
 
+                    // Unique points and intervals depend on each other, so are generated together
+                    let generated_batch = match data_type {
+                        DataType::UniquePoint(min_reference, max_reference) => {
+                            Some(self.generate_unique_points(
+                                min_reference,
+                                max_reference,
+                                repetition_count,
+                                groups,
+                            )?)
+                        }
+                        DataType::Interval(min_reference, max_reference) => {
+                            Some(self.generate_intervals(
+                                min_reference,
+                                max_reference,
+                                repetition_count,
+                                groups,
+                            )?)
+                        }
+                        _ => None,
+                    };
+
+                    if let Some(generated_batch) = generated_batch {
+                        for generated_text in generated_batch {
+                            output_text.push_str(&generated_text);
+                            output_text.push(' ');
+                        }
+                        continue;
+                    }
+
                     for _ in 1..=repetition_count {
                         let generated_text = match data_type {
                             DataType::String(min_length, max_length, charset) => self
                                 .generate_random_string(min_length, max_length, charset, groups)?,
-                            DataType::Float(min_reference, max_reference) => self
-                                .generate_random_float(min_reference, max_reference, groups)?
+                            DataType::Float(min_reference, max_reference, distribution) => self
+                                .generate_random_float(
+                                    min_reference,
+                                    max_reference,
+                                    *distribution,
+                                    groups,
+                                )?
                                 .to_string(),
-                            DataType::Integer(min_reference, max_reference) => self
-                                .generate_random_number(min_reference, max_reference, groups)?
+                            DataType::Integer(min_reference, max_reference, distribution) => self
+                                .generate_random_number(
+                                    min_reference,
+                                    max_reference,
+                                    *distribution,
+                                    groups,
+                                )?
                                 .to_string(),
+                            DataType::OrderedPair(min_reference, max_reference) => {
+                                let (left, right) = self.generate_ordered_pair(
+                                    min_reference,
+                                    max_reference,
+                                    groups,
+                                )?;
+                                format!("{left} {right}")
+                            }
+                            DataType::UniquePoint(..) | DataType::Interval(..) => {
+                                return Err(ClexErrorType::UnreachableCodeReached(
+                                    ParentErrorType::GeneratorError,
+                                    crate::clex_language::lexer::Span { start: 0, end: 0 },
+                                ));
+                            }
                         };
                         output_text.push_str(&generated_text);
                         output_text.push(' ');
@@ -101,14 +159,45 @@ impl Generator {
                 UnitExpression::CapturingGroup {
                     group_number,
                     range: (min_reference, max_reference),
+                    distribution,
                 } => {
-                    let random_number =
-                        self.generate_positive_random_number(min_reference, max_reference, groups)?;
+                    let random_number = self.generate_positive_random_number(
+                        min_reference,
+                        max_reference,
+                        *distribution,
+                        groups,
+                    )?;
                     groups.insert(*group_number, random_number);
 
                     output_text.push_str(&random_number.to_string());
                     output_text.push(' ');
                 }
+                UnitExpression::StringCapturingGroup {
+                    group_number,
+                    length: (min_length, max_length),
+                    charset,
+                } => {
+                    let random_string =
+                        self.generate_random_string(min_length, max_length, charset, groups)?;
+                    groups.insert(*group_number, random_string.chars().count() as u64);
+
+                    output_text.push_str(&random_string);
+                    output_text.push(' ');
+                }
+                UnitExpression::ArrayCapturingGroup {
+                    group_number,
+                    nest_exp,
+                    repetition,
+                } => {
+                    let repetition_count =
+                        self.get_positive_value_from_reference(repetition, groups)?;
+                    // Captured upfront, so the array length is visible to the nested expressions too
+                    groups.insert(*group_number, repetition_count);
+
+                    let nested_output =
+                        self.generate_repeated(nest_exp, repetition_count, groups)?;
+                    output_text.push_str(&nested_output);
+                }
                 UnitExpression::NonCapturingGroup {
                     nest_exp,
                     repetition,
@@ -116,13 +205,9 @@ impl Generator {
                     let repetition_count =
                         self.get_positive_value_from_reference(repetition, groups)?;
 
-                    for _ in 1..=repetition_count {
-                        let nest_gen = Self::new_from_program(ClexLanguageAST {
-                            expression: nest_exp.clone(),
-                        });
-                        let nested_output = nest_gen.traverse_ast(groups)?;
-                        output_text.push_str(&nested_output);
-                    }
+                    let nested_output =
+                        self.generate_repeated(nest_exp, repetition_count, groups)?;
+                    output_text.push_str(&nested_output);
                 }
                 UnitExpression::Eof => {
                     // Removes the last character introduced by the last iteration before Eof
@@ -135,8 +220,31 @@ impl Generator {
         Ok(output_text)
     }
 
+    // Helper method for generating nested expressions repeatedly
+    fn generate_repeated(
+        &self,
+        nest_exp: &[UnitExpression],
+        repetition_count: u64,
+        groups: &mut HashMap<u64, u64>,
+    ) -> Result<String, ClexErrorType> {
+        let mut output_text = String::new();
+        for _ in 1..=repetition_count {
+            let nest_gen = Self::from_ast(ClexLanguageAST {
+                expression: nest_exp.to_vec(),
+            });
+            let nested_output = nest_gen.traverse_ast(groups)?;
+            output_text.push_str(&nested_output);
+        }
+        Ok(output_text)
+    }
+
     // Helper method for generating random integers
-    fn generate_random_integer(&self, min: i64, max: i64) -> Result<i64, ClexErrorType> {
+    fn generate_random_integer(
+        &self,
+        min: i64,
+        max: i64,
+        distribution: Distribution,
+    ) -> Result<i64, ClexErrorType> {
         if min > max {
             return Err(ClexErrorType::InvalidRangeValues(
                 ParentErrorType::GeneratorError,
@@ -145,11 +253,17 @@ impl Generator {
                 max,
             ));
         }
-        Ok(rand::random_range(min..=max))
+        let offset = Self::generate_random_offset(max.abs_diff(min), distribution);
+        Ok(min.saturating_add_unsigned(offset))
     }
 
     // Helper method for generating random positive integers
-    fn generate_positive_random_integer(&self, min: u64, max: u64) -> Result<u64, ClexErrorType> {
+    fn generate_positive_random_integer(
+        &self,
+        min: u64,
+        max: u64,
+        distribution: Distribution,
+    ) -> Result<u64, ClexErrorType> {
         if min > max {
             return Err(ClexErrorType::InvalidRangeValues(
                 ParentErrorType::GeneratorError,
@@ -158,7 +272,44 @@ impl Generator {
                 max as i64,
             ));
         }
-        Ok(rand::random_range(min..=max))
+        Ok(min + Self::generate_random_offset(max - min, distribution))
+    }
+
+    // Helper method for generating a random offset in [0, span] from the minimum of a range
+    fn generate_random_offset(span: u64, distribution: Distribution) -> u64 {
+        if distribution == Distribution::Uniform {
+            return rand::random_range(0..=span);
+        }
+
+        // Other distributions are sampled as a float offset, which can be off by one at the
+        // edges for spans beyond 2^53, but those can't be told apart in practice
+        let offset = Self::generate_random_float_offset(span as f64 + 1.0, distribution);
+        (offset.floor() as u64).min(span)
+    }
+
+    // Helper method for generating a random float offset in [0, span] from the minimum of a range
+    fn generate_random_float_offset(span: f64, distribution: Distribution) -> f64 {
+        let offset = match distribution {
+            Distribution::Uniform => rand::random_range(0.0..=span),
+            // Uniform over the logarithm of (offset + 1), so each order of magnitude is equally likely
+            Distribution::LogUniform => {
+                ((span + 1.0).ln() * rand::random_range(0.0..=1.0)).exp() - 1.0
+            }
+            Distribution::BiasedMax => {
+                let fraction = (0..3)
+                    .map(|_| rand::random_range(0.0..=1.0))
+                    .fold(0.0, f64::max);
+                span * fraction
+            }
+            // Box-Muller transform, with a standard deviation of a sixth of the span
+            Distribution::Normal => {
+                let uniform: f64 = rand::random_range(f64::EPSILON..=1.0);
+                let angle: f64 = rand::random_range(0.0..std::f64::consts::TAU);
+                let standard_normal = (-2.0 * uniform.ln()).sqrt() * angle.cos();
+                span * (0.5 + standard_normal / 6.0)
+            }
+        };
+        offset.clamp(0.0, span)
     }
 
     fn generate_random_string(
@@ -170,7 +321,11 @@ impl Generator {
     ) -> Result<String, ClexErrorType> {
         let min_length = self.get_positive_value_from_reference(min_length, groups)? as usize;
         let max_length = self.get_positive_value_from_reference(max_length, groups)? as usize;
-        let length = self.generate_positive_random_integer(min_length as u64, max_length as u64)?;
+        let length = self.generate_positive_random_integer(
+            min_length as u64,
+            max_length as u64,
+            Distribution::Uniform,
+        )?;
         let charset = character_set.get_character_domain();
         Ok(Self::generate_random_string_from_charset(&charset, length))
     }
@@ -190,30 +345,33 @@ impl Generator {
         &self,
         min_reference: &ReferenceType,
         max_reference: &ReferenceType,
+        distribution: Distribution,
         groups: &HashMap<u64, u64>,
     ) -> Result<i64, ClexErrorType> {
         let min = self.get_value_from_reference(min_reference, groups)?;
         let max = self.get_value_from_reference(max_reference, groups)?;
 
-        self.generate_random_integer(min, max)
+        self.generate_random_integer(min, max, distribution)
     }
 
     fn generate_positive_random_number(
         &self,
         min_reference: &PositiveReferenceType,
         max_reference: &PositiveReferenceType,
+        distribution: Distribution,
         groups: &HashMap<u64, u64>,
     ) -> Result<u64, ClexErrorType> {
         let min = self.get_positive_value_from_reference(min_reference, groups)?;
         let max = self.get_positive_value_from_reference(max_reference, groups)?;
 
-        self.generate_positive_random_integer(min, max)
+        self.generate_positive_random_integer(min, max, distribution)
     }
 
     fn generate_random_float(
         &self,
         min_reference: &ReferenceType,
         max_reference: &ReferenceType,
+        distribution: Distribution,
         groups: &HashMap<u64, u64>,
     ) -> Result<f64, ClexErrorType> {
         let min = self.get_value_from_reference(min_reference, groups)? as f64;
@@ -228,7 +386,141 @@ impl Generator {
             ));
         }
 
-        Ok(rand::random_range(min..=max))
+        if distribution == Distribution::Uniform {
+            return Ok(rand::random_range(min..=max));
+        }
+        Ok(min + Self::generate_random_float_offset(max - min, distribution))
+    }
+
+    fn generate_ordered_pair(
+        &self,
+        min_reference: &ReferenceType,
+        max_reference: &ReferenceType,
+        groups: &HashMap<u64, u64>,
+    ) -> Result<(i64, i64), ClexErrorType> {
+        let first = self.generate_random_number(
+            min_reference,
+            max_reference,
+            Distribution::Uniform,
+            groups,
+        )?;
+        let second = self.generate_random_number(
+            min_reference,
+            max_reference,
+            Distribution::Uniform,
+            groups,
+        )?;
+
+        Ok((first.min(second), first.max(second)))
+    }
+
+    fn generate_unique_points(
+        &self,
+        min_reference: &ReferenceType,
+        max_reference: &ReferenceType,
+        count: u64,
+        groups: &HashMap<u64, u64>,
+    ) -> Result<Vec<String>, ClexErrorType> {
+        let (min, max) = self.get_range_from_references(min_reference, max_reference, groups)?;
+
+        // Each point is a cell of the side x side grid, numbered row by row
+        let side = max.abs_diff(min) as u128 + 1;
+        let cells = side.saturating_mul(side);
+        if count as u128 > cells {
+            return Err(ClexErrorType::NotEnoughDistinctValues(
+                ParentErrorType::GeneratorError,
+                crate::clex_language::lexer::Span { start: 0, end: 0 },
+                count,
+            ));
+        }
+
+        let mut points = Self::generate_distinct_values(count as u128, cells);
+        points.shuffle(&mut rand::rng());
+
+        Ok(points
+            .into_iter()
+            .map(|cell| {
+                let x = min.saturating_add_unsigned((cell / side) as u64);
+                let y = min.saturating_add_unsigned((cell % side) as u64);
+                format!("{x} {y}")
+            })
+            .collect())
+    }
+
+    fn generate_intervals(
+        &self,
+        min_reference: &ReferenceType,
+        max_reference: &ReferenceType,
+        count: u64,
+        groups: &HashMap<u64, u64>,
+    ) -> Result<Vec<String>, ClexErrorType> {
+        let (min, max) = self.get_range_from_references(min_reference, max_reference, groups)?;
+
+        let span = max.abs_diff(min) as u128;
+        if count as u128 > span + 1 {
+            return Err(ClexErrorType::NotEnoughDistinctValues(
+                ParentErrorType::GeneratorError,
+                crate::clex_language::lexer::Span { start: 0, end: 0 },
+                count,
+            ));
+        }
+
+        // Endpoints l1 <= r1 < l2 <= r2 < ... are shifted to be strictly increasing, i.e. the i-th
+        // interval (from 0) becomes (l + i, r + i + 1), which are distinct values in [0, span + count]
+        let mut endpoints =
+            Self::generate_distinct_values(2 * count as u128, span + count as u128 + 1);
+        endpoints.sort_unstable();
+
+        Ok(endpoints
+            .chunks_exact(2)
+            .zip(0u128..)
+            .map(|(endpoint, index)| {
+                let left = min.saturating_add_unsigned((endpoint[0] - index) as u64);
+                let right = min.saturating_add_unsigned((endpoint[1] - index - 1) as u64);
+                format!("{left} {right}")
+            })
+            .collect())
+    }
+
+    // Helper method for picking count distinct values from [0, population) using Floyd's algorithm
+    fn generate_distinct_values(count: u128, population: u128) -> Vec<u128> {
+        let mut rng = rand::rng();
+        let mut picked = HashSet::new();
+        let mut values = Vec::new();
+
+        for upper in (population - count)..population {
+            let value = rng.random_range(0..=upper);
+            let value = if picked.insert(value) {
+                value
+            } else {
+                picked.insert(upper);
+                upper
+            };
+            values.push(value);
+        }
+
+        values
+    }
+
+    fn get_range_from_references(
+        &self,
+        min_reference: &ReferenceType,
+        max_reference: &ReferenceType,
+        groups: &HashMap<u64, u64>,
+    ) -> Result<(i64, i64), ClexErrorType> {
+        let min = self.get_value_from_reference(min_reference, groups)?;
+        let max = self.get_value_from_reference(max_reference, groups)?;
+
+        if min > max {
+            return Err(ClexErrorType::InvalidRangeValues(
+                ParentErrorType::GeneratorError,
+                crate::clex_language::lexer::Span { start: 0, end: 0 },
+                min,
+                max,
+            ));
+        }
+
+        Ok((min, max))
     }
 
     fn get_value_from_reference(
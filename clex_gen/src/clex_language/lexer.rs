@@ -65,6 +65,13 @@ pub enum TokenType {
     LeftCurlyBrackets,
     RightCurlyBrackets,
     Comma,
+    Equal,
+
+    // Keywords
+    /// `let` keyword, starting a definition like `let name = <expression>`.
+    Let,
+    /// `import` keyword, loading definitions from another clex file.
+    Import,
 
     // Character sets
     /// Integer token type
@@ -73,6 +80,12 @@ pub enum TokenType {
     Float,
     /// String token type.
     String,
+    /// Ordered pair token type.
+    OrderedPair,
+    /// Unique point token type.
+    Point,
+    /// Non-overlapping interval token type.
+    Interval,
 
     // Character Sets
     CharacterSetAlpha,
@@ -83,11 +96,23 @@ pub enum TokenType {
     CharacterSetLower,
     CharacterSetAll,
 
+    // Distributions
+    DistributionUniform,
+    DistributionLogUniform,
+    DistributionBiasedMax,
+    DistributionNormal,
+
     // Literals
     /// Literal number token type with a specified value.
     LiteralNumber(i64),
     LiteralString(String),
 
+    // Names
+    /// Named capturing group opener `?<name>`, holding the group name.
+    GroupName(String),
+    /// Bare name, used by back-references like `\name` and for using definitions.
+    Identifier(String),
+
     // End of file
     Eof,
 }
@@ -127,6 +152,11 @@ impl Tokens {
         self.tokens.clone()
     }
 
+    /// Replaces the scanned tokens, used by the parser once definitions are expanded.
+    pub(crate) fn replace_tokens(&mut self, tokens: Vec<Token>) {
+        self.tokens = tokens;
+    }
+
     /// Scans tokens from the source language and adds them to the tokens vector.
     pub fn scan_tokens(&mut self) -> Result<(), ClexErrorType> {
         while !self.at_end() {
@@ -162,10 +192,20 @@ impl Tokens {
             "{" => self.add_token(TokenType::LeftCurlyBrackets),
             "}" => self.add_token(TokenType::RightCurlyBrackets),
             "," => self.add_token(TokenType::Comma),
+            "=" => self.add_token(TokenType::Equal),
+            "#" => {
+                // Comments run till the end of the line and are ignored like whitespaces
+                while self.peek() != "\n" && !self.at_end() {
+                    self.advance();
+                }
+            }
             "\\" => self.add_token(TokenType::Backslash),
             "N" => self.add_token(TokenType::Integer),
             "F" => self.add_token(TokenType::Float),
             "S" => self.add_token(TokenType::String),
+            "R" => self.add_token(TokenType::OrderedPair),
+            "P" => self.add_token(TokenType::Point),
+            "I" => self.add_token(TokenType::Interval),
             "@" => {
                 let start_pos = self.start; // Store the starting position
                 self.start += 1; // Skip first character in lexeme
@@ -194,6 +234,10 @@ impl Tokens {
                     "CH_UPPER" => TokenType::CharacterSetUpper,
                     "CH_LOWER" => TokenType::CharacterSetLower,
                     "CH_ALL" => TokenType::CharacterSetAll,
+                    "DIST_UNIFORM" => TokenType::DistributionUniform,
+                    "DIST_LOG_UNIFORM" => TokenType::DistributionLogUniform,
+                    "DIST_BIASED_MAX" => TokenType::DistributionBiasedMax,
+                    "DIST_NORMAL" => TokenType::DistributionNormal,
                     _ => {
                         return Err(ClexErrorType::InvalidCharacterSet(
                             ParentErrorType::LexerError,
@@ -273,6 +317,23 @@ impl Tokens {
                 let start_pos = self.start;
                 if self.match_str(":") {
                     self.add_token(TokenType::QuestionColon);
+                } else if self.match_str("<") {
+                    let mut name = String::new();
+                    while Self::is_identifier_char(self.peek()) {
+                        name.push_str(self.advance());
+                    }
+
+                    if !self.match_str(">") || !Self::is_valid_group_name(&name) {
+                        return Err(ClexErrorType::InvalidGroupName(
+                            ParentErrorType::LexerError,
+                            Span {
+                                start: start_pos,
+                                end: self.current,
+                            },
+                        ));
+                    }
+
+                    self.add_token(TokenType::GroupName(name));
                 } else {
                     return Err(ClexErrorType::MissingColonAfterQuestionMark(
                         ParentErrorType::LexerError,
@@ -315,6 +376,18 @@ impl Tokens {
                     };
 
                     self.add_token(TokenType::LiteralNumber(number));
+                } else if Self::is_identifier_start(c.as_str()) {
+                    let mut name = c;
+                    while Self::is_identifier_char(self.peek()) {
+                        name.push_str(self.advance());
+                    }
+
+                    let token_type = match name.as_str() {
+                        "let" => TokenType::Let,
+                        "import" => TokenType::Import,
+                        _ => TokenType::Identifier(name),
+                    };
+                    self.add_token(token_type);
                 } else {
                     let character: &'static str = Box::leak(c.into());
                     return Err(ClexErrorType::UnknownCharacter(
@@ -369,6 +442,25 @@ impl Tokens {
         ("0"..="9").contains(&ch)
     }
 
+    /// Checks if the character can start a group name, i.e. a lowercase letter or underscore.
+    /// Uppercase letters are excluded so names never clash with `N`, `F` and `S`.
+    fn is_identifier_start(ch: &str) -> bool {
+        ch.len() == 1 && ch.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+    }
+
+    /// Checks if the character can continue a group name.
+    fn is_identifier_char(ch: &str) -> bool {
+        ch.len() == 1 && ch.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+
+    /// Checks if the whole string is a valid group name, i.e. an identifier which isn't a keyword.
+    fn is_valid_group_name(name: &str) -> bool {
+        name.graphemes(true)
+            .next()
+            .is_some_and(Self::is_identifier_start)
+            && !matches!(name, "let" | "import")
+    }
+
     /// Peeks at the character at the current index.
     fn peek(&self) -> &str {
         if self.at_end() {
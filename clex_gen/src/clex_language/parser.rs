@@ -4,11 +4,12 @@
 //! The Parser takes a sequence of tokens produced by the lexer and constructs a structured AST that represents
 //! the hierarchical relationships between different language constructs. It handles various expressions including:
 //!
-//! - Primitive expressions (Integer, Float, String)
-//! - Capturing groups with ranges
+//! - Primitive expressions (Integer, Float, String, OrderedPair, UniquePoint, Interval)
+//! - Capturing groups with ranges, string lengths and array lengths, optionally named
 //! - Non-capturing groups with nested expressions
 //! - Quantifiers and modifiers
-//! - Character sets for strings
+//! - Character sets for strings and distributions for numbers
+//! - `let` definitions and `import`s of other clex files, expanded before parsing
 //!
 //! # Examples
 //!
@@ -23,7 +24,7 @@
 //!
 //! The parser performs recursive descent parsing and maintains state about:
 //! - Current token position
-//! - Number of capturing groups
+//! - Number of capturing groups and the group number of each named group
 //! - The AST being constructed
 //!
 //! It provides detailed error reporting for syntax violations and invalid constructs.
@@ -31,10 +32,14 @@
 use super::lexer::{TokenType, Tokens};
 use crate::clex_language;
 use crate::clex_language::ast::{
-    CharacterSet, ClexLanguageAST, DataType, PositiveReferenceType, ReferenceType, UnitExpression,
+    CharacterSet, ClexLanguageAST, DataType, Distribution, PositiveReferenceType, ReferenceType,
+    UnitExpression,
 };
 use crate::clex_language::clex_error_type::{ClexErrorType, ParentErrorType};
-use crate::clex_language::lexer::Token;
+use crate::clex_language::lexer::{Span, Token};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
 pub struct Parser {
@@ -43,6 +48,11 @@ pub struct Parser {
     current: usize,
     language: ClexLanguageAST,
     current_group: u64, // for capturing groupCount, starts from 1.....
+    group_names: HashMap<String, u64>, // named group => positional group number
+    definitions: HashMap<String, Vec<Token>>, // `let` definitions => their expanded tokens
+    base_dir: PathBuf,  // directory relative to which imports are resolved
+    import_stack: Vec<PathBuf>, // files being imported, for detecting circular imports
+    imported: HashSet<PathBuf>, // files already imported, each is only loaded once
 }
 
 impl Parser {
@@ -50,13 +60,17 @@ impl Parser {
         let mut tokens = Tokens::new(source_language);
         tokens.scan_tokens()?;
 
-        Ok(Self {
-            tokens,
-            start: 0,
-            current: 0,
-            language: ClexLanguageAST { expression: vec![] },
-            current_group: 0,
-        })
+        Ok(Self::new_from_tokens(tokens))
+    }
+
+    /// Creates a parser for a `.clex` file, resolving its imports relative to the file's directory.
+    pub fn new_from_file(path: impl AsRef<Path>) -> Result<Self, ClexErrorType> {
+        let (path, source) = Self::read_clex_file(path.as_ref(), Span { start: 0, end: 0 })?;
+
+        let mut parser = Self::new(source)?;
+        parser.base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        parser.import_stack.push(path);
+        Ok(parser)
     }
 
     pub fn get_language(&self) -> &ClexLanguageAST {
@@ -70,10 +84,17 @@ impl Parser {
             current: 0,
             language: ClexLanguageAST { expression: vec![] },
             current_group: 0,
+            group_names: HashMap::new(),
+            definitions: HashMap::new(),
+            base_dir: PathBuf::new(),
+            import_stack: Vec::new(),
+            imported: HashSet::new(),
         }
     }
 
     pub fn parser(&mut self) -> Result<(), ClexErrorType> {
+        self.expand_definitions()?;
+
         while !self.at_end() {
             self.start = self.current;
             let expr = self.parse_expr()?;
@@ -83,13 +104,304 @@ impl Parser {
         Ok(())
     }
 
+    /// Expands top level `let` definitions and `import`s, so only plain clex tokens are left to be
+    /// parsed. Definitions are expanded at every use, and must be defined before they are used.
+    fn expand_definitions(&mut self) -> Result<(), ClexErrorType> {
+        let mut expanded = Vec::new();
+        let mut segment = Vec::new();
+        let mut depth = 0usize;
+
+        while !self.at_end() {
+            let token = self.advance();
+            match token.token_type {
+                TokenType::Let | TokenType::Import if depth == 0 => {
+                    expanded.extend(self.expand_tokens(&segment)?);
+                    segment.clear();
+
+                    if token.token_type == TokenType::Let {
+                        self.parse_definition(token.span)?;
+                    } else {
+                        self.parse_import()?;
+                    }
+                }
+                _ => {
+                    match token.token_type {
+                        TokenType::LeftParens => depth += 1,
+                        TokenType::RightParens => depth = depth.saturating_sub(1),
+                        _ => {}
+                    }
+                    segment.push(token);
+                }
+            }
+        }
+
+        expanded.extend(self.expand_tokens(&segment)?);
+        self.tokens.replace_tokens(expanded);
+        self.current = 0;
+
+        Ok(())
+    }
+
+    fn parse_definition(&mut self, let_span: Span) -> Result<(), ClexErrorType> {
+        let name_token = self.peek();
+        let TokenType::Identifier(name) = name_token.token_type else {
+            return Err(ClexErrorType::InvalidDefinition(
+                ParentErrorType::ParserError,
+                let_span,
+            ));
+        };
+        self.advance();
+
+        if self.definitions.contains_key(&name) {
+            return Err(ClexErrorType::DuplicateDefinition(
+                ParentErrorType::ParserError,
+                name_token.span,
+                name,
+            ));
+        }
+
+        self.expect(&TokenType::Equal)?;
+
+        let body_start = self.current;
+        self.skip_unit_expression(let_span)?;
+        let body = self.expand_tokens(&self.tokens.get_tokens()[body_start..self.current])?;
+
+        self.definitions.insert(name, body);
+        Ok(())
+    }
+
+    fn parse_import(&mut self) -> Result<(), ClexErrorType> {
+        let path_token = self.peek();
+        let TokenType::LiteralString(relative_path) = path_token.token_type else {
+            return Err(ClexErrorType::UnexpectedToken(
+                ParentErrorType::ParserError,
+                path_token.span,
+                TokenType::LiteralString(String::new()),
+            ));
+        };
+        self.advance();
+
+        let import_error = |reason: String| {
+            ClexErrorType::ImportError(
+                ParentErrorType::ParserError,
+                path_token.span,
+                relative_path.clone(),
+                reason,
+            )
+        };
+
+        let (path, source) =
+            Self::read_clex_file(&self.base_dir.join(&relative_path), path_token.span)?;
+        if self.import_stack.contains(&path) {
+            return Err(import_error(
+                "circular import, the file is already being imported".to_string(),
+            ));
+        }
+        if self.imported.contains(&path) {
+            return Ok(());
+        }
+
+        let mut imported = Self::new(source).map_err(|err| import_error(err.to_string()))?;
+        imported.base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        imported.import_stack = self.import_stack.clone();
+        imported.import_stack.push(path.clone());
+        imported.imported = std::mem::take(&mut self.imported);
+        imported.definitions = std::mem::take(&mut self.definitions);
+
+        imported
+            .expand_definitions()
+            .map_err(|err| import_error(err.to_string()))?;
+        if let Some(token) = imported
+            .tokens
+            .get_tokens()
+            .into_iter()
+            .find(|token| token.token_type != TokenType::Eof)
+        {
+            return Err(import_error(
+                ClexErrorType::ExpressionInImportedFile(ParentErrorType::ParserError, token.span)
+                    .to_string(),
+            ));
+        }
+
+        self.definitions = imported.definitions;
+        self.imported = imported.imported;
+        self.imported.insert(path);
+        Ok(())
+    }
+
+    fn read_clex_file(path: &Path, span: Span) -> Result<(PathBuf, String), ClexErrorType> {
+        let read_error = |err: std::io::Error| {
+            ClexErrorType::ImportError(
+                ParentErrorType::ParserError,
+                span,
+                path.display().to_string(),
+                err.to_string(),
+            )
+        };
+
+        let canonical_path = fs::canonicalize(path).map_err(read_error)?;
+        let source = fs::read_to_string(&canonical_path).map_err(read_error)?;
+        Ok((canonical_path, source))
+    }
+
+    /// Moves past the tokens of a single unit expression without parsing it, as group names and
+    /// numbers inside a definition are only resolved where it's used.
+    fn skip_unit_expression(&mut self, let_span: Span) -> Result<(), ClexErrorType> {
+        let token = self.peek();
+        match token.token_type {
+            TokenType::LeftParens => {
+                self.advance();
+                let last_index = self
+                    .peek_from_current(TokenType::RightParens, TokenType::LeftParens)
+                    .ok_or(ClexErrorType::MissingClosingParensNonCapturingGroup(
+                        ParentErrorType::ParserError,
+                        token.span,
+                    ))?;
+                self.current = last_index + 1;
+            }
+            TokenType::Integer
+            | TokenType::Float
+            | TokenType::String
+            | TokenType::OrderedPair
+            | TokenType::Point
+            | TokenType::Interval => {
+                self.advance();
+                if self.match_token(&TokenType::LeftSquareBracket) {
+                    let last_index = self
+                        .peek_from_current(
+                            TokenType::RightSquareBracket,
+                            TokenType::LeftSquareBracket,
+                        )
+                        .ok_or(ClexErrorType::MissingSquareBracketsRangeExpression(
+                            ParentErrorType::ParserError,
+                            token.span,
+                        ))?;
+                    self.current = last_index + 1;
+                }
+            }
+            TokenType::Identifier(_) => {
+                self.advance();
+            }
+            _ => {
+                return Err(ClexErrorType::InvalidDefinition(
+                    ParentErrorType::ParserError,
+                    let_span,
+                ));
+            }
+        }
+
+        if self.match_token(&TokenType::LeftCurlyBrackets) {
+            let last_index = self
+                .peek_from_current(TokenType::RightCurlyBrackets, TokenType::LeftCurlyBrackets)
+                .ok_or(ClexErrorType::UnexpectedToken(
+                    ParentErrorType::ParserError,
+                    self.tokens.get_tokens()[self.current - 1].span,
+                    TokenType::RightCurlyBrackets,
+                ))?;
+            self.current = last_index + 1;
+        }
+
+        Ok(())
+    }
+
+    /// Replaces uses of definitions within the tokens by their bodies. A use followed by a
+    /// quantifier is wrapped in a non-capturing group, so that the whole body gets repeated.
+    fn expand_tokens(&self, tokens: &[Token]) -> Result<Vec<Token>, ClexErrorType> {
+        let mut expanded = Vec::with_capacity(tokens.len());
+
+        for (index, token) in tokens.iter().enumerate() {
+            match &token.token_type {
+                TokenType::Let | TokenType::Import => {
+                    return Err(ClexErrorType::InvalidDefinition(
+                        ParentErrorType::ParserError,
+                        token.span,
+                    ));
+                }
+                TokenType::Identifier(name)
+                    if !Self::is_group_reference(&tokens[..index])
+                        && !Self::is_distribution_field(&tokens[..index]) =>
+                {
+                    let body = self.definitions.get(name).ok_or_else(|| {
+                        ClexErrorType::UnknownDefinition(
+                            ParentErrorType::ParserError,
+                            token.span,
+                            name.clone(),
+                        )
+                    })?;
+                    let is_quantified = tokens
+                        .get(index + 1)
+                        .is_some_and(|next| next.token_type == TokenType::LeftCurlyBrackets);
+                    let synthetic_token = |token_type: TokenType, lexeme: &str| Token {
+                        token_type,
+                        lexeme: lexeme.to_string(),
+                        span: token.span,
+                    };
+
+                    if is_quantified {
+                        expanded.push(synthetic_token(TokenType::LeftParens, "("));
+                        expanded.push(synthetic_token(TokenType::QuestionColon, "?:"));
+                    }
+                    // Expanded tokens take the span of the use, so errors point to where it's used
+                    expanded.extend(body.iter().map(|body_token| Token {
+                        span: token.span,
+                        ..body_token.clone()
+                    }));
+                    if is_quantified {
+                        expanded.push(synthetic_token(TokenType::RightParens, ")"));
+                    }
+                }
+                _ => expanded.push(token.clone()),
+            }
+        }
+
+        Ok(expanded)
+    }
+
+    /// Checks if an identifier after the given tokens names a group, like in `\name` or `\{name}`.
+    fn is_group_reference(preceding: &[Token]) -> bool {
+        match preceding {
+            [.., last] if last.token_type == TokenType::Backslash => true,
+            [.., second_last, last] => {
+                last.token_type == TokenType::LeftCurlyBrackets
+                    && second_last.token_type == TokenType::Backslash
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks if a token after the given tokens is the third field of a range, like in
+    /// `N[1,10,name]`. It is left for `parse_distribution` to reject, as no definition fits there.
+    fn is_distribution_field(preceding: &[Token]) -> bool {
+        let Some(bracket) = preceding
+            .iter()
+            .rposition(|token| token.token_type == TokenType::LeftSquareBracket)
+        else {
+            return false;
+        };
+        let range = &preceding[bracket + 1..];
+        range
+            .last()
+            .is_some_and(|last| last.token_type == TokenType::Comma)
+            && range
+                .iter()
+                .filter(|token| token.token_type == TokenType::Comma)
+                .count()
+                == 2
+            && range
+                .iter()
+                .all(|token| token.token_type != TokenType::RightSquareBracket)
+    }
+
     fn parse_expr(&mut self) -> Result<UnitExpression, ClexErrorType> {
         let token = self.advance();
 
         match token.token_type {
-            TokenType::Integer | TokenType::String | TokenType::Float => {
-                self.parse_primitive_expr(token.token_type)
-            }
+            TokenType::Integer
+            | TokenType::String
+            | TokenType::Float
+            | TokenType::OrderedPair
+            | TokenType::Point
+            | TokenType::Interval => self.parse_primitive_expr(token.token_type),
             TokenType::LeftParens => self.parse_group_expr(),
             TokenType::Eof => Ok(UnitExpression::Eof),
             _ => Err(ClexErrorType::InvalidTokenFound(
@@ -106,20 +418,20 @@ impl Parser {
     ) -> Result<UnitExpression, ClexErrorType> {
         match data_type {
             TokenType::Integer => {
-                let (lower_bound, upper_bound) = self.parse_range()?;
+                let (lower_bound, upper_bound, distribution) = self.parse_range()?;
                 let repetition_type = self.parse_quantifier()?;
 
                 Ok(UnitExpression::Primitives {
-                    data_type: DataType::Integer(lower_bound, upper_bound),
+                    data_type: DataType::Integer(lower_bound, upper_bound, distribution),
                     repetition: repetition_type,
                 })
             }
             TokenType::Float => {
-                let (lower_reference, upper_reference) = self.parse_range()?;
+                let (lower_reference, upper_reference, distribution) = self.parse_range()?;
                 let repetition_type = self.parse_quantifier()?;
 
                 Ok(UnitExpression::Primitives {
-                    data_type: DataType::Float(lower_reference, upper_reference),
+                    data_type: DataType::Float(lower_reference, upper_reference, distribution),
                     repetition: repetition_type,
                 })
             }
@@ -132,6 +444,30 @@ impl Parser {
                     repetition: repetition_type,
                 })
             }
+            TokenType::OrderedPair | TokenType::Point | TokenType::Interval => {
+                let primitive_span = self.tokens.get_tokens()[self.current - 1].span;
+                let (lower_reference, upper_reference, distribution) = self.parse_range()?;
+                if distribution != Distribution::Uniform {
+                    return Err(ClexErrorType::DistributionNotSupported(
+                        ParentErrorType::ParserError,
+                        primitive_span,
+                    ));
+                }
+                let repetition_type = self.parse_quantifier()?;
+
+                let data_type = match data_type {
+                    TokenType::OrderedPair => {
+                        DataType::OrderedPair(lower_reference, upper_reference)
+                    }
+                    TokenType::Point => DataType::UniquePoint(lower_reference, upper_reference),
+                    _ => DataType::Interval(lower_reference, upper_reference),
+                };
+
+                Ok(UnitExpression::Primitives {
+                    data_type,
+                    repetition: repetition_type,
+                })
+            }
             _ => {
                 let current_token = if self.current > 0 {
                     self.tokens.get_tokens()[self.current - 1].span
@@ -153,49 +489,113 @@ impl Parser {
             crate::clex_language::lexer::Span { start: 0, end: 0 }
         };
 
-        if self.match_token(&TokenType::Integer) {
-            let (lower_reference, upper_reference) = self.parse_positive_range()?;
-            self.expect(&TokenType::RightParens)?;
-
-            self.current_group += 1;
+        if self.match_token(&TokenType::QuestionColon) {
+            let (nest_exp, repetition_type) = self.parse_non_capturing_group(start_span)?;
+            return Ok(UnitExpression::NonCapturingGroup {
+                nest_exp,
+                repetition: repetition_type,
+            });
+        }
 
-            Ok(UnitExpression::CapturingGroup {
-                group_number: self.current_group,
-                range: (lower_reference, upper_reference),
-            })
-        } else if self.match_token(&TokenType::QuestionColon) {
-            let last_index = self
-                .peek_from_current(TokenType::RightParens, TokenType::LeftParens)
-                .ok_or(ClexErrorType::MissingClosingParensNonCapturingGroup(
+        let group_name = if let TokenType::GroupName(name) = self.peek().token_type {
+            let name_span = self.peek().span;
+            self.advance();
+            if self.group_names.contains_key(&name) {
+                return Err(ClexErrorType::DuplicateGroupName(
                     ParentErrorType::ParserError,
-                    start_span,
-                ))?;
+                    name_span,
+                    name,
+                ));
+            }
+            Some(name)
+        } else {
+            None
+        };
 
-            let mut nest_exp = Vec::new();
+        // Groups are numbered by their opening parenthesis, so an array capture is numbered
+        // before any capturing group nested inside it.
+        self.current_group += 1;
+        let group_number = self.current_group;
 
-            while self.current < last_index {
-                let expr = self.parse_expr()?;
-                match expr {
-                    UnitExpression::Primitives { .. }
-                    | UnitExpression::NonCapturingGroup { .. }
-                    | UnitExpression::CapturingGroup { .. } => nest_exp.push(expr),
-                    UnitExpression::Eof => break,
-                }
+        let expr = if self.match_token(&TokenType::Integer) {
+            let (lower_reference, upper_reference, distribution) = self.parse_positive_range()?;
+            self.expect(&TokenType::RightParens)?;
+
+            UnitExpression::CapturingGroup {
+                group_number,
+                range: (lower_reference, upper_reference),
+                distribution,
+            }
+        } else if self.match_token(&TokenType::String) {
+            let (min_length, max_length, charset) = self.parse_string_modifiers()?;
+            self.expect(&TokenType::RightParens)?;
+
+            UnitExpression::StringCapturingGroup {
+                group_number,
+                length: (min_length, max_length),
+                charset,
+            }
+        } else if self.match_token(&TokenType::LeftParens) {
+            let inner_span = self.tokens.get_tokens()[self.current - 1].span;
+            if !self.match_token(&TokenType::QuestionColon) {
+                return Err(ClexErrorType::UnclosedParens(
+                    ParentErrorType::ParserError,
+                    inner_span,
+                ));
             }
 
+            let (nest_exp, repetition_type) = self.parse_non_capturing_group(inner_span)?;
             self.expect(&TokenType::RightParens)?;
 
-            let repetition_type = self.parse_quantifier()?;
-            Ok(UnitExpression::NonCapturingGroup {
+            UnitExpression::ArrayCapturingGroup {
+                group_number,
                 nest_exp,
                 repetition: repetition_type,
-            })
+            }
         } else {
-            Err(ClexErrorType::UnclosedParens(
+            return Err(ClexErrorType::UnclosedParens(
                 ParentErrorType::ParserError,
                 start_span,
-            ))
+            ));
+        };
+
+        // Registered only once the group is complete, so a group can't reference itself by name.
+        if let Some(name) = group_name {
+            self.group_names.insert(name, group_number);
         }
+
+        Ok(expr)
+    }
+
+    fn parse_non_capturing_group(
+        &mut self,
+        start_span: crate::clex_language::lexer::Span,
+    ) -> Result<(Vec<UnitExpression>, PositiveReferenceType), ClexErrorType> {
+        let last_index = self
+            .peek_from_current(TokenType::RightParens, TokenType::LeftParens)
+            .ok_or(ClexErrorType::MissingClosingParensNonCapturingGroup(
+                ParentErrorType::ParserError,
+                start_span,
+            ))?;
+
+        let mut nest_exp = Vec::new();
+
+        while self.current < last_index {
+            let expr = self.parse_expr()?;
+            match expr {
+                UnitExpression::Primitives { .. }
+                | UnitExpression::NonCapturingGroup { .. }
+                | UnitExpression::CapturingGroup { .. }
+                | UnitExpression::StringCapturingGroup { .. }
+                | UnitExpression::ArrayCapturingGroup { .. } => nest_exp.push(expr),
+                UnitExpression::Eof => break,
+            }
+        }
+
+        self.expect(&TokenType::RightParens)?;
+
+        let repetition_type = self.parse_quantifier()?;
+        Ok((nest_exp, repetition_type))
     }
 
     fn parse_quantifier(&mut self) -> Result<PositiveReferenceType, ClexErrorType> {
@@ -273,11 +673,14 @@ impl Parser {
         Ok((min_length_reference, max_length_reference, char_set))
     }
 
-    fn parse_range(&mut self) -> Result<(ReferenceType, ReferenceType), ClexErrorType> {
+    fn parse_range(
+        &mut self,
+    ) -> Result<(ReferenceType, ReferenceType, Distribution), ClexErrorType> {
         let lower_bound = clex_language::ast::DEFAULT_RANGE_MIN_VALUE;
         let upper_bound = clex_language::ast::DEFAULT_RANGE_MAX_VALUE;
         let mut lower_reference = ReferenceType::ByLiteral(lower_bound);
         let mut upper_reference = ReferenceType::ByLiteral(upper_bound);
+        let mut distribution = Distribution::get_default_distribution();
 
         if self.match_token(&TokenType::LeftSquareBracket) {
             let bracket_span = if self.current > 0 {
@@ -302,6 +705,10 @@ impl Parser {
 
             upper_reference = self.parse_reference(upper_bound)?;
 
+            if self.match_token(&TokenType::Comma) {
+                distribution = self.parse_distribution()?;
+            }
+
             if !self.match_token(&TokenType::RightSquareBracket) {
                 let current_span = if self.current < self.tokens.get_tokens().len() {
                     self.tokens.get_tokens()[self.current].span
@@ -315,16 +722,17 @@ impl Parser {
             }
         }
 
-        Ok((lower_reference, upper_reference))
+        Ok((lower_reference, upper_reference, distribution))
     }
 
     fn parse_positive_range(
         &mut self,
-    ) -> Result<(PositiveReferenceType, PositiveReferenceType), ClexErrorType> {
+    ) -> Result<(PositiveReferenceType, PositiveReferenceType, Distribution), ClexErrorType> {
         let lower_bound = clex_language::ast::DEFAULT_POSITIVE_RANGE_MIN_VALUE;
         let upper_bound = clex_language::ast::DEFAULT_POSITIVE_RANGE_MAX_VALUE;
         let mut lower_reference = PositiveReferenceType::ByLiteral(lower_bound);
         let mut upper_reference = PositiveReferenceType::ByLiteral(upper_bound);
+        let mut distribution = Distribution::get_default_distribution();
 
         if self.match_token(&TokenType::LeftSquareBracket) {
             let bracket_span = if self.current > 0 {
@@ -349,6 +757,10 @@ impl Parser {
 
             upper_reference = self.parse_positive_reference(upper_bound)?;
 
+            if self.match_token(&TokenType::Comma) {
+                distribution = self.parse_distribution()?;
+            }
+
             if !self.match_token(&TokenType::RightSquareBracket) {
                 let current_span = if self.current < self.tokens.get_tokens().len() {
                     self.tokens.get_tokens()[self.current].span
@@ -362,7 +774,32 @@ impl Parser {
             }
         }
 
-        Ok((lower_reference, upper_reference))
+        Ok((lower_reference, upper_reference, distribution))
+    }
+
+    /// Parses the distribution after the comma following the upper bound of a range
+    fn parse_distribution(&mut self) -> Result<Distribution, ClexErrorType> {
+        let token = self.peek();
+        let distribution = match token.token_type {
+            TokenType::DistributionUniform => Distribution::Uniform,
+            TokenType::DistributionLogUniform => Distribution::LogUniform,
+            TokenType::DistributionBiasedMax => Distribution::BiasedMax,
+            TokenType::DistributionNormal => Distribution::Normal,
+            // A trailing comma or a typo would otherwise silently give uniform values
+            _ => {
+                let span = if self.at_end() {
+                    self.tokens.get_tokens()[self.current - 1].span
+                } else {
+                    token.span
+                };
+                return Err(ClexErrorType::InvalidDistribution(
+                    ParentErrorType::ParserError,
+                    span,
+                ));
+            }
+        };
+        self.advance();
+        Ok(distribution)
     }
 
     fn parse_positive_reference(
@@ -370,31 +807,9 @@ impl Parser {
         default_value: u64,
     ) -> Result<PositiveReferenceType, ClexErrorType> {
         if self.match_token(&TokenType::Backslash) {
-            let backslash_span = if self.current > 0 {
-                self.tokens.get_tokens()[self.current - 1].span
-            } else {
-                crate::clex_language::lexer::Span { start: 0, end: 0 }
-            };
-
-            if let TokenType::LiteralNumber(value) = self.peek().token_type {
-                let num_span = self.peek().span;
-                self.advance();
-                if value <= 0 {
-                    Err(ClexErrorType::NegativeGroupNumber(
-                        ParentErrorType::ParserError,
-                        num_span,
-                    ))
-                } else {
-                    Ok(PositiveReferenceType::ByGroup {
-                        group_number: value as u64,
-                    })
-                }
-            } else {
-                Err(ClexErrorType::MissingGroupNumber(
-                    ParentErrorType::ParserError,
-                    backslash_span,
-                ))
-            }
+            Ok(PositiveReferenceType::ByGroup {
+                group_number: self.parse_group_reference()?,
+            })
         } else if let TokenType::LiteralNumber(value) = self.peek().token_type {
             let num_span = self.peek().span;
             self.advance();
@@ -413,65 +828,85 @@ impl Parser {
 
     fn parse_reference(&mut self, default_value: i64) -> Result<ReferenceType, ClexErrorType> {
         if self.match_token(&TokenType::Backslash) {
-            let backslash_span = if self.current > 0 {
-                self.tokens.get_tokens()[self.current - 1].span
-            } else {
-                crate::clex_language::lexer::Span { start: 0, end: 0 }
-            };
+            Ok(ReferenceType::ByGroup {
+                group_number: self.parse_group_reference()?,
+            })
+        } else if let TokenType::LiteralNumber(value) = self.peek().token_type {
+            self.advance();
+            Ok(ReferenceType::ByLiteral(value))
+        } else {
+            Ok(ReferenceType::ByLiteral(default_value))
+        }
+    }
+
+    /// Parses the group after a `\`, given either as a number or a name, optionally wrapped in
+    /// curly brackets like `\{name}`. Names are resolved to their positional group number.
+    fn parse_group_reference(&mut self) -> Result<u64, ClexErrorType> {
+        let backslash_span = self.tokens.get_tokens()[self.current - 1].span;
+        let is_braced = self.match_token(&TokenType::LeftCurlyBrackets);
 
-            if let TokenType::LiteralNumber(value) = self.peek().token_type {
-                let num_span = self.peek().span;
+        let token = self.peek();
+        let group_number = match token.token_type {
+            TokenType::LiteralNumber(value) => {
                 self.advance();
                 if value <= 0 {
-                    Err(ClexErrorType::NegativeGroupNumber(
+                    return Err(ClexErrorType::NegativeGroupNumber(
                         ParentErrorType::ParserError,
-                        num_span,
-                    ))
-                } else {
-                    Ok(ReferenceType::ByGroup {
-                        group_number: value as u64,
-                    })
+                        token.span,
+                    ));
                 }
-            } else {
-                Err(ClexErrorType::MissingGroupNumber(
+                value as u64
+            }
+            TokenType::Identifier(name) => {
+                self.advance();
+                match self.group_names.get(&name) {
+                    Some(group_number) => *group_number,
+                    None => {
+                        return Err(ClexErrorType::UnknownGroupName(
+                            ParentErrorType::ParserError,
+                            token.span,
+                            name,
+                        ));
+                    }
+                }
+            }
+            _ => {
+                return Err(ClexErrorType::MissingGroupNumber(
                     ParentErrorType::ParserError,
                     backslash_span,
-                ))
+                ));
             }
-        } else if let TokenType::LiteralNumber(value) = self.peek().token_type {
-            self.advance();
-            Ok(ReferenceType::ByLiteral(value))
-        } else {
-            Ok(ReferenceType::ByLiteral(default_value))
-        }
-    }
+        };
 
-    fn peek_from_current(&mut self, expected: TokenType, not_expected: TokenType) -> Option<usize> {
-        // Finds index of occurrence of expected Token from current position
-        let mut stack = Vec::new();
-        let current_reset_duplicate = self.current;
+        if is_braced {
+            self.expect(&TokenType::RightCurlyBrackets)?;
+        }
 
-        while !self.at_end() {
-            let tk = self.advance();
+        Ok(group_number)
+    }
 
+    fn peek_from_current(&self, expected: TokenType, not_expected: TokenType) -> Option<usize> {
+        // Finds index of the matching occurrence of expected Token from current position, skipping
+        // over nested pairs of not_expected and expected Tokens
+        let mut depth = 0usize;
+
+        for (index, tk) in self
+            .tokens
+            .get_tokens()
+            .iter()
+            .enumerate()
+            .skip(self.current)
+        {
             if tk.token_type == not_expected {
-                stack.push(&not_expected);
+                depth += 1;
             } else if tk.token_type == expected {
-                if let Some(not_expect) = stack.pop() {
-                    if not_expect == &not_expected {
-                        stack.pop();
-                    } else {
-                        stack.push(&expected);
-                    }
-                } else {
-                    let expected_index = self.current - 1;
-                    self.current = current_reset_duplicate;
-                    return Some(expected_index);
+                if depth == 0 {
+                    return Some(index);
                 }
+                depth -= 1;
             }
         }
 
-        self.current = current_reset_duplicate;
         None
     }
 
@@ -51,6 +51,7 @@ pub mod clex_language;
 use crate::clex_language::clex_error_type::ClexErrorType;
 use crate::clex_language::lexer::Token;
 use crate::clex_language::{ast::ClexLanguageAST, code_generator, lexer, parser};
+use std::path::Path;
 
 /// Error type that includes source context for better error messages
 #[derive(Debug)]
@@ -170,3 +171,60 @@ pub fn generator(language: String) -> Result<String, ClexError> {
         .generate_testcases()
         .map_err(|e| ClexError::new(e, source))
 }
+
+/// Get the Abstract Syntax Tree (AST) from a `.clex` file.
+///
+/// Imports within the file are resolved relative to the directory of the file.
+///
+/// # Arguments
+///
+/// * `path` - Path to the file containing the custom language generator code.
+///
+/// # Returns
+///
+/// Result enum, if Ok contains the `ClexLanguageAST` AST representing the parsed program.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// let ast = clex_gen::get_ast_from_file("graph.clex").unwrap();
+/// ```
+pub fn get_ast_from_file(path: impl AsRef<Path>) -> Result<ClexLanguageAST, ClexError> {
+    // Only used for error context, reading errors are reported by the parser itself
+    let source = std::fs::read_to_string(path.as_ref()).unwrap_or_default();
+    let mut parser =
+        parser::Parser::new_from_file(path).map_err(|e| ClexError::new(e, source.clone()))?;
+    parser.parser().map_err(|e| ClexError::new(e, source))?;
+    Ok(parser.get_language().clone())
+}
+
+/// Generate code based on the custom language specification in a `.clex` file.
+///
+/// Imports within the file are resolved relative to the directory of the file.
+///
+/// # Arguments
+///
+/// * `path` - Path to the file containing the custom language generator code.
+///
+/// # Returns
+///
+/// Result enum, if Ok contains a string representing the generated test pattern.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// let generated_code = clex_gen::generator_from_file("graph.clex").unwrap();
+/// ```
+pub fn generator_from_file(path: impl AsRef<Path>) -> Result<String, ClexError> {
+    // Only used for error context, reading errors are reported by the parser itself
+    let source = std::fs::read_to_string(path.as_ref()).unwrap_or_default();
+    let mut parser =
+        parser::Parser::new_from_file(path).map_err(|e| ClexError::new(e, source.clone()))?;
+    parser
+        .parser()
+        .map_err(|e| ClexError::new(e, source.clone()))?;
+    let generator = code_generator::Generator::new(&parser);
+    generator
+        .generate_testcases()
+        .map_err(|e| ClexError::new(e, source))
+}
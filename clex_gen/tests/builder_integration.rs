@@ -0,0 +1,44 @@
+use clex_gen::clex_language::ast::{CharacterSet, Distribution};
+use clex_gen::clex_language::builder::ClexBuilder;
+use clex_gen::clex_language::code_generator::Generator;
+use clex_gen::get_ast;
+
+#[test]
+fn test_builder_matches_parsed_ast() {
+    let mut builder = ClexBuilder::new();
+    let n = builder.capture_integer(5u64, 7u64);
+    builder.repeat(n, |element| {
+        let m = element.capture_integer(1u64, 5u64);
+        element.integer(-10000, 10000).times(m);
+    });
+
+    let ast = builder.build();
+    assert_eq!(
+        ast,
+        get_ast("(N[5,7]) (?: (N[1,5]) N[-10000,10000]{\\2}){\\1}".to_string()).unwrap()
+    );
+}
+
+#[test]
+fn test_builder_display_round_trips_through_parser() {
+    let mut builder = ClexBuilder::new();
+    let length = builder.capture_string_length(1u64, 3u64, CharacterSet::Custom("a'\\\n".into()));
+    let count = builder.capture_repeat(2u64, |element| {
+        element.float(-5, length).distribution(Distribution::Normal);
+        element.ordered_pair(1, 100);
+    });
+    builder.interval(1, 1000).times(count);
+
+    let ast = builder.build();
+    assert_eq!(ast, get_ast(ast.to_string()).unwrap());
+}
+
+#[test]
+fn test_generator_from_built_ast() {
+    let mut builder = ClexBuilder::new();
+    let n = builder.capture_integer(3u64, 3u64);
+    builder.integer(3, 3).times(n);
+
+    let generator = Generator::from_ast(builder.build());
+    assert_eq!(generator.generate_testcases().unwrap(), "3 3 3 3");
+}
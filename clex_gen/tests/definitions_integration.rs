@@ -0,0 +1,94 @@
+use std::path::Path;
+
+use clex_gen::{generator, generator_from_file, get_ast};
+
+#[test]
+fn test_comments_are_ignored() {
+    let language = "# number of elements\n(N[2,2]) # count\n(?:N[7,7]){\\1}";
+
+    assert_eq!(generator(language.to_string()).unwrap(), "2 7 7");
+}
+
+#[test]
+fn test_definition_expands_at_every_use() {
+    let language = "let seven = N[7,7]\nseven seven{2}";
+
+    assert_eq!(generator(language.to_string()).unwrap(), "7 7 7");
+}
+
+#[test]
+fn test_definition_resolves_groups_where_used() {
+    let language = "let edge = (?:N[1,\\n] N[1,\\n])\n(?<n>N[1,1]) edge{2}";
+
+    assert_eq!(generator(language.to_string()).unwrap(), "1 1 1 1 1");
+}
+
+#[test]
+fn test_definition_expands_into_ast() {
+    let with_definition = get_ast("let pair = (?:N[1,5] F)\n(N) pair{\\1}".to_string()).unwrap();
+    let without_definition = get_ast("(N) (?:(?:N[1,5] F)){\\1}".to_string()).unwrap();
+
+    assert_eq!(with_definition.expression, without_definition.expression);
+}
+
+#[test]
+fn test_unknown_definition_error() {
+    let error_message = generator("pair let pair = N".to_string())
+        .unwrap_err()
+        .to_string();
+
+    assert!(error_message.contains("use of undefined definition 'pair'"));
+}
+
+#[test]
+fn test_duplicate_definition_error() {
+    let error_message = generator("let a = N let a = F".to_string())
+        .unwrap_err()
+        .to_string();
+
+    assert!(error_message.contains("'a' is already defined"));
+}
+
+#[test]
+fn test_nested_definition_error() {
+    assert!(generator("(?:let a = N)".to_string()).is_err());
+}
+
+#[test]
+fn test_generator_from_file_with_import() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let file_path = Path::new(&manifest_dir).join("tests/clex/graph.clex");
+
+    let generated = generator_from_file(file_path).unwrap();
+    let values: Vec<u64> = generated
+        .split_whitespace()
+        .map(|value| value.parse().unwrap())
+        .collect();
+
+    assert_eq!(values.len(), 2 + 3 * 2);
+    assert_eq!(&values[..2], &[5, 3]);
+    assert!(values[2..].iter().all(|vertex| (1..=5).contains(vertex)));
+}
+
+#[test]
+fn test_circular_import_error() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let file_path = Path::new(&manifest_dir).join("tests/clex/circular_a.clex");
+
+    let error_message = generator_from_file(file_path).unwrap_err().to_string();
+    assert!(error_message.contains("circular import"));
+}
+
+#[test]
+fn test_import_with_expression_error() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let language = format!(
+        "import '{}'",
+        Path::new(&manifest_dir)
+            .join("tests/clex/with_expression.clex")
+            .display()
+    );
+
+    let error_message = generator(language).unwrap_err().to_string();
+    assert!(error_message.contains("imported files may only contain definitions"));
+}
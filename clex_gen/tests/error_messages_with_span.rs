@@ -119,3 +119,60 @@ fn test_error_format_has_visual_pointer() {
     assert!(error_message.contains("|")); // Line separator
     assert!(error_message.contains("error:")); // Error prefix
 }
+
+#[test]
+fn test_unknown_group_name_error() {
+    let input = "(?<n>N) N{\\m}";
+    let result = generator(input.to_string());
+    assert!(result.is_err());
+    let error_message = result.unwrap_err().to_string();
+
+    // Should include position information
+    assert!(error_message.contains("input:"));
+
+    // Should name the undefined group
+    assert!(error_message.contains("reference to undefined group 'm'"));
+}
+
+#[test]
+fn test_duplicate_group_name_error() {
+    let input = "(?<n>N) (?<n>N)";
+    let result = generator(input.to_string());
+    assert!(result.is_err());
+    let error_message = result.unwrap_err().to_string();
+
+    assert!(error_message.contains("input:"));
+    assert!(error_message.contains("group name 'n' is already defined"));
+}
+
+#[test]
+fn test_missing_distribution_after_comma_error() {
+    let input = "N[1,10,]";
+    let result = generator(input.to_string());
+    assert!(result.is_err());
+    let error_message = result.unwrap_err().to_string();
+
+    // Points at the closing bracket where the distribution was expected
+    assert!(error_message.contains("input:7..8"));
+    assert!(error_message.contains("expected a distribution after ','"));
+}
+
+#[test]
+fn test_unknown_distribution_error() {
+    let input = "N[1,10,garbage]";
+    let result = generator(input.to_string());
+    assert!(result.is_err());
+    let error_message = result.unwrap_err().to_string();
+
+    assert!(error_message.contains("input:7..14"));
+    assert!(error_message.contains("expected a distribution after ','"));
+
+    // Even when it names a definition
+    let result = generator("let garbage = N\nN[1,10,garbage]".to_string());
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("expected a distribution after ','")
+    );
+}
@@ -1,4 +1,5 @@
-use clex_gen::generator;
+use clex_gen::clex_language::code_generator::Generator;
+use clex_gen::{generator, get_ast};
 
 #[test]
 fn test_generator_with_integer_expression() {
@@ -56,3 +57,153 @@ fn test_generator_with_numeral_charset_string_expression() {
     let gen_language = generator(language.to_string()).unwrap();
     assert!(!gen_language.is_empty() && gen_language.chars().all(|c| c.is_ascii_digit()));
 }
+
+#[test]
+fn test_generator_with_named_groups() {
+    let language = "(?<n>N[3,3]) (?:N[1,1]){\\n} N[2,2]{\\{n}}";
+
+    assert_eq!(generator(language.to_string()).unwrap(), "3 1 1 1 2 2 2");
+}
+
+#[test]
+fn test_generator_with_string_length_capture() {
+    let language = "(?<s>S[1,10,@CH_LOWER@]) N[\\s,\\s]";
+
+    let generated = generator(language.to_string()).unwrap();
+    let (string, length) = generated.split_once(' ').unwrap();
+    assert_eq!(string.len().to_string(), length);
+}
+
+#[test]
+fn test_generator_with_array_length_capture() {
+    let language = "(N[2,2]) (?<len>(?:N[7,7]){\\1}) N[\\len,\\len]";
+
+    assert_eq!(generator(language.to_string()).unwrap(), "2 7 7 2");
+}
+
+#[test]
+fn test_generator_with_deeply_nested_groups() {
+    let language = "(?:(?:(N[1,1]) N[2,2]) N[3,3]) N[4,4]";
+
+    assert_eq!(generator(language.to_string()).unwrap(), "1 2 3 4");
+}
+
+#[test]
+fn test_generator_with_distributions_within_range() {
+    for distribution in [
+        "@DIST_UNIFORM@",
+        "@DIST_LOG_UNIFORM@",
+        "@DIST_BIASED_MAX@",
+        "@DIST_NORMAL@",
+    ] {
+        let language = format!("N[-50,50,{distribution}]{{100}} F[-1,1,{distribution}]{{100}}");
+
+        let generated = generator(language).unwrap();
+        assert!(generated.split_whitespace().all(|value| {
+            let value: f64 = value.parse().unwrap();
+            (-50.0..=50.0).contains(&value)
+        }));
+
+        let language = format!("(N[7,7,{distribution}]) N[-3,-3,{distribution}]");
+        assert_eq!(generator(language).unwrap(), "7 -3");
+    }
+}
+
+#[test]
+fn test_generator_with_log_uniform_distribution() {
+    let language = "N[1,1000000000,@DIST_LOG_UNIFORM@]{200}";
+
+    // With uniform distribution, practically none of the values would be below 10^6
+    let generated = generator(language.to_string()).unwrap();
+    let small_values = generated
+        .split_whitespace()
+        .filter(|value| value.parse::<u64>().unwrap() < 1_000_000)
+        .count();
+    assert!(small_values > 50);
+}
+
+#[test]
+fn test_generator_with_ordered_pairs() {
+    let language = "(N[20,20]) R[1,\\1]{100}";
+
+    let generated = generator(language.to_string()).unwrap();
+    let values: Vec<i64> = generated
+        .split_whitespace()
+        .skip(1)
+        .map(|value| value.parse().unwrap())
+        .collect();
+
+    assert_eq!(values.len(), 200);
+    assert!(
+        values
+            .chunks(2)
+            .all(|pair| 1 <= pair[0] && pair[0] <= pair[1] && pair[1] <= 20)
+    );
+}
+
+#[test]
+fn test_generator_with_unique_points() {
+    // Every point of the 3x3 grid has to be generated exactly once
+    let language = "P[-1,1]{9}";
+
+    let generated = generator(language.to_string()).unwrap();
+    let values: Vec<i64> = generated
+        .split_whitespace()
+        .map(|value| value.parse().unwrap())
+        .collect();
+    let points: std::collections::HashSet<_> =
+        values.chunks(2).map(|point| (point[0], point[1])).collect();
+
+    assert_eq!(points.len(), 9);
+    assert!(points.iter().all(|&(x, y)| x.abs() <= 1 && y.abs() <= 1));
+}
+
+#[test]
+fn test_generator_with_non_overlapping_intervals() {
+    let language = "(N[5,5]) I[1,10]{\\1} I[3,3]{1}";
+
+    let generated = generator(language.to_string()).unwrap();
+    let values: Vec<i64> = generated
+        .split_whitespace()
+        .skip(1)
+        .map(|value| value.parse().unwrap())
+        .collect();
+
+    assert_eq!(&values[10..], &[3, 3]);
+    let intervals: Vec<_> = values[..10].chunks(2).collect();
+    assert!(intervals.iter().all(|interval| interval[0] <= interval[1]));
+    assert!(intervals.windows(2).all(|pair| pair[0][1] < pair[1][0]));
+    assert!(intervals[0][0] >= 1 && intervals[4][1] <= 10);
+}
+
+#[test]
+fn test_generator_with_not_enough_distinct_values() {
+    assert!(generator("P[1,2]{5}".to_string()).is_err());
+    assert!(generator("I[1,3]{4}".to_string()).is_err());
+}
+
+#[test]
+fn test_generator_with_scaled_capturing_groups() {
+    let ast = get_ast("(N[1,101]) (?:(N[0,10]) N[5,5]{\\2}){\\1}".to_string()).unwrap();
+
+    let generate = |scale| {
+        Generator::from_ast(ast.scale_capturing_groups(scale))
+            .generate_testcases()
+            .unwrap()
+    };
+    assert_eq!(generate(0.0), "1 0");
+    assert_eq!(generate(0.02), "3 0 0 0");
+    assert_eq!(
+        generate(1.0).split_whitespace().count(),
+        // 101 groups of a count and 10 numbers
+        1 + 101 * 11
+    );
+}
+
+#[test]
+fn test_scaled_string_capturing_group() {
+    let ast = get_ast("(S[1,9,@CH_NUM@]) N[1,1]{\\1}".to_string()).unwrap();
+
+    let scaled = ast.scale_capturing_groups(0.5);
+    assert_eq!(scaled.to_string(), "(S[5,5,@CH_NUM@]) N[1,1]{\\1}");
+}
@@ -305,3 +305,56 @@ fn test_character_invalid() {
 
     assert!(get_tokens(src.to_string()).is_err());
 }
+
+#[test]
+fn test_named_group_and_identifier() {
+    let src = "(?<max_n>N)\\max_n";
+
+    assert_eq!(
+        get_tokens(src.to_string()).unwrap(),
+        vec![
+            Token {
+                token_type: TokenType::LeftParens,
+                lexeme: "(".to_string(),
+                span: Span { start: 0, end: 1 },
+            },
+            Token {
+                token_type: TokenType::GroupName("max_n".to_string()),
+                lexeme: "?<max_n>".to_string(),
+                span: Span { start: 1, end: 9 },
+            },
+            Token {
+                token_type: TokenType::Integer,
+                lexeme: "N".to_string(),
+                span: Span { start: 9, end: 10 },
+            },
+            Token {
+                token_type: TokenType::RightParens,
+                lexeme: ")".to_string(),
+                span: Span { start: 10, end: 11 },
+            },
+            Token {
+                token_type: TokenType::Backslash,
+                lexeme: "\\".to_string(),
+                span: Span { start: 11, end: 12 },
+            },
+            Token {
+                token_type: TokenType::Identifier("max_n".to_string()),
+                lexeme: "max_n".to_string(),
+                span: Span { start: 12, end: 17 },
+            },
+            Token {
+                token_type: TokenType::Eof,
+                lexeme: "".to_string(),
+                span: Span { start: 17, end: 17 },
+            }
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_group_name() {
+    for src in ["(?<>N)", "(?<Max>N)", "(?<n N)"] {
+        assert!(get_tokens(src.to_string()).is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use clex_gen::clex_language::ast::{
-    CharacterSet, DataType, PositiveReferenceType, ReferenceType, UnitExpression,
+    CharacterSet, DataType, Distribution, PositiveReferenceType, ReferenceType, UnitExpression,
 };
 use clex_gen::{clex_language, get_ast};
 
@@ -17,6 +17,7 @@ fn test_get_ast_with_complex_pattern() {
                     PositiveReferenceType::ByLiteral(5),
                     PositiveReferenceType::ByLiteral(7)
                 ),
+                distribution: Distribution::Uniform,
             },
             UnitExpression::NonCapturingGroup {
                 nest_exp: vec![
@@ -26,11 +27,13 @@ fn test_get_ast_with_complex_pattern() {
                             PositiveReferenceType::ByLiteral(1),
                             PositiveReferenceType::ByLiteral(5)
                         ),
+                        distribution: Distribution::Uniform,
                     },
                     UnitExpression::Primitives {
                         data_type: DataType::Integer(
                             ReferenceType::ByLiteral(-10000),
-                            ReferenceType::ByLiteral(10000)
+                            ReferenceType::ByLiteral(10000),
+                            Distribution::Uniform
                         ),
                         repetition: PositiveReferenceType::ByGroup { group_number: 2 },
                     },
@@ -108,3 +111,120 @@ fn test_charset_with_string() {
         ]
     );
 }
+
+#[test]
+fn test_get_ast_with_named_groups() {
+    let language = "(?<n>N[1,5]) (?<m>N[1,\\n]) N{\\{m}}";
+    let ast = get_ast(language.to_string()).unwrap();
+
+    assert_eq!(
+        ast.expression,
+        vec![
+            UnitExpression::CapturingGroup {
+                group_number: 1,
+                range: (
+                    PositiveReferenceType::ByLiteral(1),
+                    PositiveReferenceType::ByLiteral(5)
+                ),
+                distribution: Distribution::Uniform,
+            },
+            UnitExpression::CapturingGroup {
+                group_number: 2,
+                range: (
+                    PositiveReferenceType::ByLiteral(1),
+                    PositiveReferenceType::ByGroup { group_number: 1 }
+                ),
+                distribution: Distribution::Uniform,
+            },
+            UnitExpression::Primitives {
+                data_type: DataType::Integer(
+                    ReferenceType::ByLiteral(clex_language::ast::DEFAULT_RANGE_MIN_VALUE),
+                    ReferenceType::ByLiteral(clex_language::ast::DEFAULT_RANGE_MAX_VALUE),
+                    Distribution::Uniform
+                ),
+                repetition: PositiveReferenceType::ByGroup { group_number: 2 },
+            },
+            UnitExpression::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_get_ast_with_string_and_array_captures() {
+    let language = "(S[1,3,@CH_UPPER@]) ((?:(N[2,2]) N{\\2}){\\1})";
+    let ast = get_ast(language.to_string()).unwrap();
+
+    assert_eq!(
+        ast.expression,
+        vec![
+            UnitExpression::StringCapturingGroup {
+                group_number: 1,
+                length: (
+                    PositiveReferenceType::ByLiteral(1),
+                    PositiveReferenceType::ByLiteral(3)
+                ),
+                charset: CharacterSet::Uppercase,
+            },
+            UnitExpression::ArrayCapturingGroup {
+                group_number: 2,
+                nest_exp: vec![
+                    UnitExpression::CapturingGroup {
+                        group_number: 3,
+                        range: (
+                            PositiveReferenceType::ByLiteral(2),
+                            PositiveReferenceType::ByLiteral(2)
+                        ),
+                        distribution: Distribution::Uniform,
+                    },
+                    UnitExpression::Primitives {
+                        data_type: DataType::Integer(
+                            ReferenceType::ByLiteral(clex_language::ast::DEFAULT_RANGE_MIN_VALUE),
+                            ReferenceType::ByLiteral(clex_language::ast::DEFAULT_RANGE_MAX_VALUE),
+                            Distribution::Uniform
+                        ),
+                        repetition: PositiveReferenceType::ByGroup { group_number: 2 },
+                    },
+                ],
+                repetition: PositiveReferenceType::ByGroup { group_number: 1 },
+            },
+            UnitExpression::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_get_ast_with_distributions() {
+    let language = "(N[1,1000,@DIST_LOG_UNIFORM@]) F[-5,5,@DIST_NORMAL@] N[1,9]";
+    let ast = get_ast(language.to_string()).unwrap();
+
+    assert_eq!(
+        ast.expression,
+        vec![
+            UnitExpression::CapturingGroup {
+                group_number: 1,
+                range: (
+                    PositiveReferenceType::ByLiteral(1),
+                    PositiveReferenceType::ByLiteral(1000)
+                ),
+                distribution: Distribution::LogUniform,
+            },
+            UnitExpression::Primitives {
+                data_type: DataType::Float(
+                    ReferenceType::ByLiteral(-5),
+                    ReferenceType::ByLiteral(5),
+                    Distribution::Normal
+                ),
+                repetition: PositiveReferenceType::ByLiteral(1),
+            },
+            UnitExpression::Primitives {
+                data_type: DataType::Integer(
+                    ReferenceType::ByLiteral(1),
+                    ReferenceType::ByLiteral(9),
+                    Distribution::Uniform
+                ),
+                repetition: PositiveReferenceType::ByLiteral(1),
+            },
+            UnitExpression::Eof,
+        ]
+    );
+}
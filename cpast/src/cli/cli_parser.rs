@@ -1,11 +1,15 @@
 use std::io;
 
-use clap::{Command, CommandFactory, Parser, Subcommand, ValueHint};
+use ccode_runner::lang_runner::checker::FloatChecker;
+use ccode_runner::{DEFAULT_OUTPUT_LIMIT_BYTES, DEFAULT_TIME_LIMIT_MS};
+use clap::{Command, CommandFactory, Parser, Subcommand, ValueEnum, ValueHint};
 use clap_complete::{Generator, Shell, generate};
 use colored::Colorize;
 
 const DEFAULT_ITERATIONS_COUNT: usize = 5;
 
+pub(crate) const BYTES_PER_MEGABYTE: u64 = 1024 * 1024;
+
 #[derive(Parser)] // requires `derive` feature
 #[command(name = "cpast", version, author, about, long_about = None)]
 #[command(bin_name = "cpast")]
@@ -29,6 +33,10 @@ pub(crate) enum CpastSubcommands {
 
     /// Generate clex using AI from input format and constraints
     Ai(AiArgs),
+
+    /// Profile two files over inputs of increasing size to find where the test file is too slow
+    #[command(author)]
+    Perf(PerfArgs),
 }
 
 fn print_completions<G: Generator>(generator: G, cmd: &mut Command) {
@@ -46,11 +54,15 @@ pub(crate) struct TestArgs {
     #[arg(short, long, value_hint = ValueHint::FilePath)]
     pub(crate) correct_file: Option<String>,
 
-    /// The test file
+    /// The test file, repeat to compare several programs at once
     #[arg(short, long, required = true, value_hint = ValueHint::FilePath)]
-    pub(crate) test_file: Option<String>,
+    pub(crate) test_file: Vec<String>,
+
+    /// Expect the output most programs agree on, instead of the correct file's output
+    #[arg(long)]
+    pub(crate) majority: bool,
 
-    /// Clex for generating Tests
+    /// Clex for generating Tests, or path to a .clex file
     #[arg(short, long, value_hint = ValueHint::Other)]
     pub(crate) generator: Option<String>,
 
@@ -73,11 +85,118 @@ pub(crate) struct TestArgs {
     /// Debug mode for verbose output
     #[arg(short, long)]
     pub(crate) debug: bool,
+
+    /// How the output of the test file is checked against the correct file's output
+    #[arg(long, value_enum, default_value_t = CheckerArg::Text)]
+    pub(crate) checker: CheckerArg,
+
+    /// Absolute and relative tolerance of the float checker
+    #[arg(long, default_value_t = FloatChecker::DEFAULT_TOLERANCE, value_hint = ValueHint::Other)]
+    pub(crate) float_tolerance: f64,
+
+    /// Special judge, run as `<checker_file> <input> <output> <answer>` (testlib-style)
+    #[arg(long, required_if_eq("checker", "special-judge"), value_hint = ValueHint::FilePath)]
+    pub(crate) checker_file: Option<String>,
+
+    /// TOML file overriding how languages are compiled and run, e.g. to match a judge's flags
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    pub(crate) toolchain: Option<String>,
+
+    /// Directory of the compilation cache, shared with other runs [default: ~/.cache/cpast/compile]
+    #[arg(long, value_hint = ValueHint::DirPath)]
+    pub(crate) cache_dir: Option<String>,
+
+    /// Compile into a temporary directory instead of the compilation cache
+    #[arg(long, conflicts_with = "cache_dir")]
+    pub(crate) no_cache: bool,
+
+    /// Don't re-run failing testcases under the sanitizer (debug) build of the test files
+    #[arg(long)]
+    pub(crate) no_sanitize: bool,
+
+    /// Run the programs this many times on failing testcases, flagging those whose output changes
+    /// between runs (0 turns it off)
+    #[arg(long, default_value_t = 5, value_hint = ValueHint::Other)]
+    pub(crate) flaky_runs: usize,
+
+    /// Run Python, Java and JavaScript programs in this many warm workers each, instead of a fresh
+    /// process per testcase
+    #[arg(long, default_value_t = 0, value_hint = ValueHint::Other)]
+    pub(crate) workers: usize,
+
+    /// Wall clock time limit of each run in milliseconds
+    #[arg(long, default_value_t = DEFAULT_TIME_LIMIT_MS, value_hint = ValueHint::Other)]
+    pub(crate) time_limit: u64,
+
+    /// CPU time limit of each run in milliseconds, unlike the time limit not counting the time
+    /// spent waiting (Unix only)
+    #[arg(long, value_hint = ValueHint::Other)]
+    pub(crate) cpu_time_limit: Option<u64>,
+
+    /// Output limit of each run in megabytes, stopping programs which print forever
+    #[arg(long, default_value_t = DEFAULT_OUTPUT_LIMIT_BYTES / BYTES_PER_MEGABYTE, value_hint = ValueHint::Other)]
+    pub(crate) output_limit: u64,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub(crate) enum CheckerArg {
+    /// Line by line comparison, ignoring trailing whitespace
+    Text,
+    /// Whitespace-insensitive token comparison
+    Token,
+    /// Token comparison, accepting numbers within the float tolerance
+    Float,
+    /// Case-insensitive token comparison, for YES/NO answers
+    YesNo,
+    /// Line comparison, regardless of the order of lines
+    UnorderedLines,
+    /// External checker program given by --checker-file
+    SpecialJudge,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct PerfArgs {
+    /// The correct file
+    #[arg(short, long, value_hint = ValueHint::FilePath)]
+    pub(crate) correct_file: String,
+
+    /// The test file
+    #[arg(short, long, value_hint = ValueHint::FilePath)]
+    pub(crate) test_file: String,
+
+    /// Clex for generating Tests, or path to a .clex file. Its capturing groups are scaled from a
+    /// small fraction of their range up to their maximum
+    #[arg(short, long, value_hint = ValueHint::Other)]
+    pub(crate) generator: String,
+
+    /// Number of input sizes, each doubling the capturing groups
+    #[arg(long, default_value_t = 8, value_hint = ValueHint::Other)]
+    pub(crate) steps: usize,
+
+    /// Inputs generated at each size
+    #[arg(long, default_value_t = 3, value_hint = ValueHint::Other)]
+    pub(crate) inputs_per_step: usize,
+
+    /// Report inputs on which the test file is this many times slower than the correct file
+    #[arg(long, default_value_t = 3.0, value_hint = ValueHint::Other)]
+    pub(crate) slowdown: f64,
+
+    /// Time limit of each run in milliseconds, profiling stops at the first size exceeding it
+    #[arg(long, default_value_t = 5000, value_hint = ValueHint::Other)]
+    pub(crate) time_limit: u64,
+
+    /// Force recompile code even if binaries is up to date
+    #[arg(short, long)]
+    pub(crate) force_recompile: bool,
+
+    /// TOML file overriding how languages are compiled and run, e.g. to match a judge's flags
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    pub(crate) toolchain: Option<String>,
 }
 
 #[derive(clap::Args)]
 pub(crate) struct GenerateArgs {
-    /// Write Clex for generating Tests
+    /// Write Clex for generating Tests, or path to a .clex file
     pub(crate) generator: Option<String>,
 
     /// Copy testcases to clipboard
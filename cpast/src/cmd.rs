@@ -1,3 +1,4 @@
 pub(crate) mod ai;
 pub(crate) mod generate;
+pub(crate) mod perf;
 pub(crate) mod test;
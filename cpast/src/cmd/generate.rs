@@ -6,14 +6,20 @@ use crate::cli::cli_parser::GenerateArgs;
     windows,
 ))]
 use arboard::Clipboard;
-use clex_gen::generator;
+use clex_gen::{generator, generator_from_file};
 use colored::Colorize;
-use cpast::DEFAULT_FAIL_EXIT_CODE;
+use cpast::{DEFAULT_FAIL_EXIT_CODE, is_clex_file};
 
 pub(crate) fn generate_call(args: GenerateArgs) {
     match args.generator {
         Some(language) => {
-            match generator(language) {
+            let generated = if is_clex_file(&language) {
+                generator_from_file(&language)
+            } else {
+                generator(language)
+            };
+
+            match generated {
                 Ok(testcase) => {
                     let generated_testcases = testcase;
                     eprintln!("=====================================");
@@ -0,0 +1,38 @@
+use std::path::Path;
+use std::process::exit;
+use std::time::Duration;
+
+use crate::cli::cli_parser::PerfArgs;
+use ccode_runner::lang_runner::compile_cache::CompileCache;
+use ccode_runner::lang_runner::toolchain::ToolchainConfig;
+use cpast::perf::compile_and_profile;
+use cpast::{CodeOrPath, DEFAULT_FAIL_EXIT_CODE};
+
+pub(crate) async fn perf_call(args: PerfArgs) {
+    let toolchains = match args.toolchain {
+        Some(toolchain_file) => ToolchainConfig::from_file(Path::new(&toolchain_file)),
+        None => Ok(ToolchainConfig::default()),
+    }
+    .unwrap_or_else(|err| {
+        eprintln!("{err}");
+        exit(DEFAULT_FAIL_EXIT_CODE);
+    })
+    .with_compile_cache(CompileCache::in_default_dir());
+
+    compile_and_profile(
+        CodeOrPath::Path(args.correct_file),
+        args.test_file,
+        args.generator,
+        args.steps,
+        args.inputs_per_step,
+        args.slowdown,
+        Duration::from_millis(args.time_limit),
+        args.force_recompile,
+        &toolchains,
+    )
+    .await
+    .unwrap_or_else(|err| {
+        eprintln!("{err}");
+        exit(DEFAULT_FAIL_EXIT_CODE);
+    });
+}
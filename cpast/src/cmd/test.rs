@@ -1,24 +1,83 @@
 use std::process::exit;
 
-use crate::{cli::cli_parser::TestArgs, error_types::cli_error::CliErrorType};
-use ccode_runner::lang_runner::language_name::LanguageName;
+use std::path::Path;
+
+use crate::{
+    cli::cli_parser::{BYTES_PER_MEGABYTE, CheckerArg, TestArgs},
+    error_types::cli_error::CliErrorType,
+};
+use ccode_runner::ExecutionLimits;
+use ccode_runner::lang_runner::{
+    checker::{
+        Checker, FloatChecker, SpecialJudgeChecker, TextChecker, TokenChecker,
+        UnorderedLinesChecker, YesNoChecker,
+    },
+    compile_cache::CompileCache,
+    diagnostics::DiagnosticSeverity,
+    language_name::LanguageName,
+    multi_program_store::ReferenceStrategy,
+    runner::Language,
+    runner_error_types::RunnerErrorType,
+    toolchain::ToolchainConfig,
+};
 use colored::Colorize;
-use cpast::{CodeOrPath, DEFAULT_FAIL_EXIT_CODE, compile_and_test};
+use cpast::{
+    CodeOrPath, DEFAULT_FAIL_EXIT_CODE, GenericCpastError, compile_and_test_programs,
+    compile_and_test_with_checker,
+};
 use cscrapper::qscrapper::ScraperError;
 
 pub(crate) async fn test_call(args: TestArgs) {
-    let test_binding = args.test_file.unwrap_or_default();
+    let mut test_bindings = args.test_file;
     let iterations = args.iterations;
     let no_stop = args.no_stop;
     let do_force_compile = args.force_recompile;
     let debug = args.debug;
 
+    let toolchains = match args.toolchain {
+        Some(toolchain_file) => ToolchainConfig::from_file(Path::new(&toolchain_file)),
+        None => Ok(ToolchainConfig::default()),
+    }
+    .unwrap_or_else(|err| {
+        eprintln!("{err}");
+        exit(DEFAULT_FAIL_EXIT_CODE);
+    });
+    let toolchains = match (args.no_cache, args.cache_dir) {
+        (true, _) => toolchains,
+        (false, Some(cache_dir)) => toolchains.with_compile_cache(CompileCache::new(cache_dir)),
+        (false, None) => toolchains.with_compile_cache(CompileCache::in_default_dir()),
+    };
+    let toolchains = toolchains.with_workers(args.workers);
+
+    let mut limits = ExecutionLimits::new()
+        .with_time_limit(args.time_limit)
+        .with_output_limit(args.output_limit.saturating_mul(BYTES_PER_MEGABYTE));
+    if let Some(cpu_time_limit) = args.cpu_time_limit {
+        limits = limits.with_cpu_time_limit(cpu_time_limit);
+    }
+
+    let checker = build_checker(
+        args.checker,
+        args.float_tolerance,
+        args.checker_file.as_deref(),
+        do_force_compile,
+        &toolchains,
+        limits,
+    )
+    .unwrap_or_else(|err| {
+        report_runner_error(&err);
+        exit(DEFAULT_FAIL_EXIT_CODE);
+    });
+
+    // With a majority vote, the test files are enough to get the expected output
     if !((args.problem_url.is_some() && args.correct_file.is_none() && args.generator.is_none())
-        || (args.problem_url.is_none() && args.correct_file.is_some() && args.generator.is_some()))
+        || (args.problem_url.is_none()
+            && (args.correct_file.is_some() || args.majority)
+            && args.generator.is_some()))
     {
         eprintln!(
             "{}",
-            "[TEST] Either problem URL or test file & generator is required!".red()
+            "[TEST] Either problem URL or correct file (optional with --majority) & generator is required!".red()
         );
         exit(DEFAULT_FAIL_EXIT_CODE);
     }
@@ -48,34 +107,133 @@ pub(crate) async fn test_call(args: TestArgs) {
             }
 
             (
-                CodeOrPath::Code(generated_code, generated_language),
+                Some(CodeOrPath::Code(generated_code, generated_language)),
                 generated_clex,
             )
         }
         None => {
             let language = args.generator.unwrap_or_default();
 
-            let correct_binding = args.correct_file.unwrap_or_default();
-            (CodeOrPath::Path(correct_binding), language)
+            (args.correct_file.map(CodeOrPath::Path), language)
         }
     };
 
-    compile_and_test(
-        correct_binding,
-        test_binding,
-        clex,
-        iterations,
-        no_stop,
-        do_force_compile,
-        debug,
-    )
-    .await
-    .unwrap_or_else(|err| {
-        eprintln!("{err}");
+    let result = match correct_binding {
+        Some(correct_binding) if test_bindings.len() == 1 && !args.majority => {
+            compile_and_test_with_checker(
+                correct_binding,
+                test_bindings.remove(0),
+                clex,
+                iterations,
+                no_stop,
+                do_force_compile,
+                debug,
+                checker,
+                &toolchains,
+                limits,
+                !args.no_sanitize,
+                args.flaky_runs,
+            )
+            .await
+        }
+        correct_binding => {
+            // The correct file comes first, being the oracle unless voting
+            let reference = match args.majority {
+                true => ReferenceStrategy::Majority,
+                false => ReferenceStrategy::Oracle(0),
+            };
+            let programs = correct_binding
+                .into_iter()
+                .chain(test_bindings.into_iter().map(CodeOrPath::Path))
+                .collect();
+            compile_and_test_programs(
+                programs,
+                clex,
+                iterations,
+                no_stop,
+                do_force_compile,
+                debug,
+                reference,
+                checker,
+                &toolchains,
+                limits,
+                !args.no_sanitize,
+                args.flaky_runs,
+            )
+            .await
+        }
+    };
+
+    result.unwrap_or_else(|err| {
+        match err {
+            GenericCpastError::RunnerError(err) => report_runner_error(&err),
+            err => eprintln!("{err}"),
+        }
         exit(DEFAULT_FAIL_EXIT_CODE);
     });
 }
 
+/// Prints compilation errors as the compiler diagnostics, and other errors as is
+fn report_runner_error(err: &RunnerErrorType) {
+    let RunnerErrorType::CompilationError(source_path, attempts) = err else {
+        eprintln!("{err}");
+        return;
+    };
+
+    eprintln!(
+        "{}",
+        format!("[TEST] Failed to compile {}", source_path.display()).red()
+    );
+    for attempt in attempts {
+        if !attempt.launched {
+            eprintln!("{} {}", attempt.compiler.bold(), attempt.stderr.dimmed());
+        } else if attempt.diagnostics.is_empty() {
+            eprintln!("{}\n{}", attempt.compiler.bold(), attempt.stderr);
+        } else {
+            eprintln!("{}", attempt.compiler.bold());
+            for diagnostic in &attempt.diagnostics {
+                let severity = match diagnostic.severity {
+                    DiagnosticSeverity::Error => diagnostic.severity.to_string().red(),
+                    DiagnosticSeverity::Warning => diagnostic.severity.to_string().yellow(),
+                    DiagnosticSeverity::Note => diagnostic.severity.to_string().blue(),
+                };
+                let location = match diagnostic.column {
+                    Some(column) => format!("{}:{}:{column}", diagnostic.file, diagnostic.line),
+                    None => format!("{}:{}", diagnostic.file, diagnostic.line),
+                };
+                eprintln!("  {location}: {severity}: {}", diagnostic.message);
+            }
+        }
+    }
+}
+
+fn build_checker(
+    checker: CheckerArg,
+    float_tolerance: f64,
+    checker_file: Option<&str>,
+    do_force_compile: bool,
+    toolchains: &ToolchainConfig,
+    limits: ExecutionLimits,
+) -> Result<Box<dyn Checker>, Box<RunnerErrorType>> {
+    Ok(match checker {
+        CheckerArg::Text => Box::new(TextChecker),
+        CheckerArg::Token => Box::new(TokenChecker),
+        CheckerArg::Float => Box::new(FloatChecker::new(float_tolerance, float_tolerance)),
+        CheckerArg::YesNo => Box::new(YesNoChecker),
+        CheckerArg::UnorderedLines => Box::new(UnorderedLinesChecker),
+        CheckerArg::SpecialJudge => {
+            // Enforced by clap
+            let checker_file = checker_file.unwrap_or_default();
+            Box::new(SpecialJudgeChecker::new(Language::new_with_toolchain(
+                Path::new(checker_file),
+                do_force_compile,
+                limits,
+                toolchains,
+            )?))
+        }
+    })
+}
+
 /// Generate Clex, Code from problem URL
 async fn get_clex_code_input_format_constraints_from_problem_url(
     problem_url: &str,
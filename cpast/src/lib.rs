@@ -1,6 +1,6 @@
 //! # cpast - Code Testing and Analysis Tool
 //!
-//! `cpast` is a versatile code testing and analysis tool that empowers users in competitive programming and coding practice. It allows testing correct and incorrect code files against a custom language generator called `clex_gen`. This crate supports various programming languages, such as Python, C++, C, Rust, Ruby, JavaScript, and Java, and enables users to specify the number of iterations for testing code against random input values.
+//! `cpast` is a versatile code testing and analysis tool that empowers users in competitive programming and coding practice. It allows testing correct and incorrect code files against a custom language generator called `clex_gen`. This crate supports various programming languages, such as Python, C++, C, Rust, Ruby, JavaScript, Java, Go, Kotlin, C#, Haskell, and PHP, and enables users to specify the number of iterations for testing code against random input values.
 //!
 //! ## Main Modules
 //!
@@ -16,6 +16,8 @@
 //! To get started with `cpast`, users can use the provided functions:
 //!
 //! - `compile_and_test`: Compiles and tests code against a custom language generator.
+//! - `compile_and_test_programs`: Same, comparing any number of programs against an oracle or by majority vote.
+//! - `perf::compile_and_profile`: Profiles two programs over inputs of increasing size, fitting their empirical complexity.
 //!
 //! ## Example
 //!
@@ -30,8 +32,18 @@
 //! For more details on usage and advanced features, refer to the README.
 //!
 
+use ccode_runner::lang_runner::checker::{Checker, CheckerVerdict, TextChecker};
+use ccode_runner::lang_runner::determinism::check_determinism_async;
 use ccode_runner::lang_runner::language_name::LanguageName;
+use ccode_runner::lang_runner::multi_program_store::{
+    MultiProgramStore, ProgramVerdict, ReferenceStrategy,
+};
 use ccode_runner::lang_runner::runner::Language;
+use ccode_runner::lang_runner::toolchain::ToolchainConfig;
+use ccode_runner::{
+    DEFAULT_CONTEXT_LINES, DEFAULT_OUTPUT_LIMIT_BYTES, DEFAULT_TIME_LIMIT_MS, ExecutionLimits,
+    ExecutionStatus, Side, diff_outputs,
+};
 use colored::Colorize;
 use error_types::cli_error::CliErrorType;
 use futures::future::join_all;
@@ -43,9 +55,12 @@ use ccode_runner::lang_runner::program_store::ProgramStore;
 use ccode_runner::lang_runner::runner_error_types::RunnerErrorType;
 use clex_gen::clex_language::clex_error_type::ClexErrorType;
 use clex_gen::clex_language::{code_generator, lexer, parser};
+use sanitizer_recheck::SanitizerRecheck;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 pub(crate) mod error_types;
+pub mod perf;
+mod sanitizer_recheck;
 
 pub const DEFAULT_FAIL_EXIT_CODE: i32 = 1;
 
@@ -68,13 +83,21 @@ pub enum CodeOrPath {
     Path(String),
 }
 
+/// Checks if the generator is a path to a `.clex` file, rather than the clex expression itself.
+pub fn is_clex_file(generator: &str) -> bool {
+    let path = Path::new(generator);
+    path.extension()
+        .is_some_and(|extension| extension == "clex")
+        && path.is_file()
+}
+
 /// Compile and test code against custom language generator.
 ///
 /// # Arguments
 ///
 /// * `correct_binding` - The source code file path containing correct code.
 /// * `test_binding` - The source code file path containing incorrect code for testing.
-/// * `language` - The custom language generator code for test generation.
+/// * `language` - The custom language generator code for test generation, or a path to a `.clex` file containing it.
 /// * `iterations` - The number of test iterations to run.
 /// * `no_stop` - Whether to stop after a failing testcase is found or not.
 /// * `do_force_compile` - Whether to forcefully recompile files, even though it is updated
@@ -96,25 +119,216 @@ pub async fn compile_and_test(
     do_force_compile: bool,
     debug: bool,
 ) -> Result<(), GenericCpastError> {
+    compile_and_test_with_checker(
+        correct_binding,
+        test_binding,
+        language,
+        iterations,
+        no_stop,
+        do_force_compile,
+        debug,
+        Box::new(TextChecker),
+        ToolchainConfig::builtin(),
+        ExecutionLimits::new()
+            .with_time_limit(DEFAULT_TIME_LIMIT_MS)
+            .with_output_limit(DEFAULT_OUTPUT_LIMIT_BYTES),
+        false,
+        0,
+    )
+    .await
+}
+
+/// Compile and test code against custom language generator, checking outputs with the checker.
+///
+/// Same as `compile_and_test`, except the output of the test code is accepted or rejected by the
+/// `checker` instead of being compared line by line, e.g. for problems with multiple valid answers,
+/// and the code is compiled and run with the given `toolchains`, every run enforcing the `limits`.
+///
+/// With `sanitize_failures`, inputs on which the test code gives a wrong answer are run again
+/// under its debug build, reporting out of bounds accesses and undefined behavior caught by the
+/// sanitizers, and panics of Rust's overflow checks. With `flaky_runs` of 2 or more, both programs are run that many times on failing
+/// inputs, reporting the ones whose output changes between runs, as a flaky correct code makes
+/// the mismatch meaningless.
+#[allow(clippy::too_many_arguments)]
+pub async fn compile_and_test_with_checker(
+    correct_binding: CodeOrPath,
+    test_binding: String,
+    language: String,
+    iterations: usize,
+    no_stop: bool,
+    do_force_compile: bool,
+    debug: bool,
+    checker: Box<dyn Checker>,
+    toolchains: &ToolchainConfig,
+    limits: ExecutionLimits,
+    sanitize_failures: bool,
+    flaky_runs: usize,
+) -> Result<(), GenericCpastError> {
+    let checks = FailureChecks {
+        sanitizer: sanitize_failures.then(|| {
+            SanitizerRecheck::new(
+                vec![test_binding.clone()],
+                toolchains,
+                limits,
+                do_force_compile,
+            )
+        }),
+        flaky_runs,
+    };
     let store = match correct_binding {
         CodeOrPath::Code(correct_code, correct_lang) => {
             // CURRENTLY UNSTABLE
-            let correct_lang_instance =
-                Language::new_from_text(&correct_code, correct_lang, do_force_compile)?;
-            let test_lang_instance = Language::new(Path::new(&test_binding), do_force_compile)?;
+            let correct_lang_instance = Language::new_from_text_with_toolchain(
+                &correct_code,
+                correct_lang,
+                do_force_compile,
+                limits,
+                toolchains,
+            )?;
+            let test_lang_instance = Language::new_with_toolchain(
+                Path::new(&test_binding),
+                do_force_compile,
+                limits,
+                toolchains,
+            )?;
             ProgramStore::new_from_language(correct_lang_instance, test_lang_instance)?
         }
-        CodeOrPath::Path(correct_path) => ProgramStore::new(
+        CodeOrPath::Path(correct_path) => ProgramStore::new_with_toolchain(
             Path::new(&correct_path),
             Path::new(&test_binding),
             do_force_compile,
+            limits,
+            toolchains,
         )?,
     };
-    let store = Arc::new(store);
 
-    let mut token = lexer::Tokens::new(language);
-    token.scan_tokens()?;
-    let mut parser = parser::Parser::new_from_tokens(token);
+    run_test_cases(
+        Comparison::Pair(Box::new(store.with_checker(checker))),
+        checks,
+        language,
+        iterations,
+        no_stop,
+        debug,
+    )
+    .await
+}
+
+/// Compile and test any number of programs against custom language generator.
+///
+/// Every program runs on each testcase, and the testcases on which any of them disagree with the
+/// expected output are reported, along with the programs which disagree. The expected output is
+/// the output of the program at the index given by `ReferenceStrategy::Oracle`, or the output most
+/// programs agree on with `ReferenceStrategy::Majority`. Every run enforces the `limits`. With `sanitize_failures`, programs read
+/// from files are rechecked as described in `compile_and_test_with_checker`, both on wrong answers
+/// and runtime errors. With `flaky_runs`, the reference and the disagreeing programs are checked
+/// for flakiness on failing inputs, as in `compile_and_test_with_checker`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ccode_runner::ExecutionLimits;
+/// use ccode_runner::lang_runner::{checker::TextChecker, multi_program_store::ReferenceStrategy, toolchain::ToolchainConfig};
+/// use cpast::CodeOrPath;
+///
+/// async fn compile() {
+///     let programs = ["brute.py", "intended.cpp", "candidate.rs"]
+///         .map(|path| CodeOrPath::Path(path.to_string()))
+///         .into();
+///     cpast::compile_and_test_programs(programs, "(N[1,10]) (?:N){\\1}".to_string(), 100, false, false, false, ReferenceStrategy::Majority, Box::new(TextChecker), ToolchainConfig::builtin(), ExecutionLimits::new().with_time_limit(2000), true, 5).await.unwrap();
+/// }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub async fn compile_and_test_programs(
+    programs: Vec<CodeOrPath>,
+    language: String,
+    iterations: usize,
+    no_stop: bool,
+    do_force_compile: bool,
+    debug: bool,
+    reference: ReferenceStrategy,
+    checker: Box<dyn Checker>,
+    toolchains: &ToolchainConfig,
+    limits: ExecutionLimits,
+    sanitize_failures: bool,
+    flaky_runs: usize,
+) -> Result<(), GenericCpastError> {
+    let sanitizer = sanitize_failures.then(|| {
+        let paths = programs
+            .iter()
+            .filter_map(|program| match program {
+                CodeOrPath::Path(path) => Some(path.clone()),
+                CodeOrPath::Code(..) => None,
+            })
+            .collect();
+        SanitizerRecheck::new(paths, toolchains, limits, do_force_compile)
+    });
+    let programs = programs
+        .into_iter()
+        .map(|program| match program {
+            CodeOrPath::Code(code, lang) => Language::new_from_text_with_toolchain(
+                &code,
+                lang.clone(),
+                do_force_compile,
+                limits,
+                toolchains,
+            )
+            .map(|program| (format!("generated {lang} code"), program)),
+            CodeOrPath::Path(path) => {
+                Language::new_with_toolchain(Path::new(&path), do_force_compile, limits, toolchains)
+                    .map(|program| (path, program))
+            }
+        })
+        .collect::<Result<_, _>>()?;
+    let store = MultiProgramStore::new_from_languages(programs, reference)?;
+
+    run_test_cases(
+        Comparison::Many(store.with_checker(checker)),
+        FailureChecks {
+            sanitizer,
+            flaky_runs,
+        },
+        language,
+        iterations,
+        no_stop,
+        debug,
+    )
+    .await
+}
+
+/// Programs compared on each testcase
+enum Comparison {
+    /// The correct and the test program
+    Pair(Box<ProgramStore>),
+    /// Any number of programs, against an oracle or by majority vote
+    Many(MultiProgramStore),
+}
+
+/// Checks run on failing testcases, telling why they fail
+struct FailureChecks {
+    /// Reruns the failing programs under their debug build
+    sanitizer: Option<SanitizerRecheck>,
+    /// Runs the programs this many times, flagging those whose output changes (off below 2)
+    flaky_runs: usize,
+}
+
+async fn run_test_cases(
+    comparison: Comparison,
+    checks: FailureChecks,
+    language: String,
+    iterations: usize,
+    no_stop: bool,
+    debug: bool,
+) -> Result<(), GenericCpastError> {
+    let comparison = Arc::new(comparison);
+    let checks = Arc::new(checks);
+
+    let mut parser = if is_clex_file(&language) {
+        parser::Parser::new_from_file(&language)?
+    } else {
+        let mut token = lexer::Tokens::new(language);
+        token.scan_tokens()?;
+        parser::Parser::new_from_tokens(token)
+    };
     parser.parser()?;
     let generator = code_generator::Generator::new(&parser);
     let generator = Arc::new(generator);
@@ -134,7 +348,8 @@ pub async fn compile_and_test(
     let tasks = (1..=iterations)
         .map(|iter| {
             let has_failed_clone = Arc::clone(&has_failed);
-            let store_clone = Arc::clone(&store);
+            let comparison_clone = Arc::clone(&comparison);
+            let checks_clone = Arc::clone(&checks);
             let generator_clone = Arc::clone(&generator);
             // let semaphore_clone = Arc::clone(&semaphore);
 
@@ -145,7 +360,8 @@ pub async fn compile_and_test(
                     debug,
                     iter,
                     has_failed_clone,
-                    store_clone,
+                    comparison_clone,
+                    checks_clone,
                     generator_clone,
                 )
                 .await;
@@ -170,57 +386,348 @@ pub async fn compile_and_test(
     Ok(())
 }
 
+/// Short verdict for errors caused by how a program ran, rather than by the runner itself
+fn get_verdict(err: &RunnerErrorType) -> Option<String> {
+    match err.program_error() {
+        RunnerErrorType::TimeLimitExceeded(_) => Some(String::from("Time Limit Exceeded")),
+        RunnerErrorType::CpuTimeLimitExceeded(_) => {
+            Some(String::from("Time Limit Exceeded (CPU time)"))
+        }
+        RunnerErrorType::MemoryLimitExceeded(_) => Some(String::from("Memory Limit Exceeded")),
+        RunnerErrorType::OutputLimitExceeded(_) => Some(String::from("Output Limit Exceeded")),
+        RunnerErrorType::RuntimeError(result) => Some(match result.status {
+            ExecutionStatus::Signaled(signal) => format!("Runtime Error (signal {signal})"),
+            ExecutionStatus::NonZeroExit(exit_code) => {
+                format!("Runtime Error (exit code {exit_code})")
+            }
+            _ => String::from("Runtime Error"),
+        }),
+        RunnerErrorType::SanitizerError(_, report) => Some(format!("Runtime Error ({report})")),
+        _ => None,
+    }
+}
+
 async fn process_test_case(
     no_stop: bool,
     debug: bool,
     iter: usize,
     has_failed_clone: Arc<AtomicBool>,
-    store_clone: Arc<ProgramStore>,
+    comparison_clone: Arc<Comparison>,
+    checks_clone: Arc<FailureChecks>,
     generator_clone: Arc<code_generator::Generator>,
 ) {
     if !no_stop && has_failed_clone.load(Ordering::Relaxed) {
         return;
     }
 
-    match generator_clone.generate_testcases() {
+    let output_text = match generator_clone.generate_testcases() {
         Err(err) => {
             eprintln!("{err}");
             has_failed_clone.store(true, Ordering::Relaxed);
+            return;
         }
-        Ok(output_text) => match store_clone.run_codes_and_compare_output(&output_text) {
-            Ok((true, _, _)) => {
-                if !no_stop && debug {
-                    eprintln!("{}", format!("Testcase {iter} ran successfully!").green());
+        Ok(output_text) => output_text,
+    };
+
+    let passed = match comparison_clone.as_ref() {
+        Comparison::Pair(store) => match store.run_codes_and_check_output_async(&output_text).await
+        {
+            Ok((CheckerVerdict::Accepted, _, _)) => Ok(()),
+            Ok((CheckerVerdict::WrongAnswer(message), expected, actual)) => {
+                println!(
+                    "{}\n{}\n{}",
+                    format!("Testcase {iter} failed! {message}").red(),
+                    "INPUT".underline(),
+                    &output_text.cyan(),
+                );
+                print_output_diff("EXPECTED OUTPUT", &expected, "ACTUAL OUTPUT", &actual);
+                if let Some(recheck) = &checks_clone.sanitizer {
+                    report_sanitizer_findings(recheck, recheck.paths(), &output_text, debug).await;
+                }
+                report_flaky_programs(
+                    &[
+                        ("correct file", store.correct_program()),
+                        ("test file", store.test_program()),
+                    ],
+                    Some("correct file"),
+                    store.checker(),
+                    &output_text,
+                    checks_clone.flaky_runs,
+                )
+                .await;
+                Err(())
+            }
+            Err(err) => {
+                // Either program may have failed only this time
+                let program_failed = get_verdict(&err).is_some();
+                report_run_error(iter, &output_text, err);
+                if program_failed {
+                    report_flaky_programs(
+                        &[
+                            ("correct file", store.correct_program()),
+                            ("test file", store.test_program()),
+                        ],
+                        Some("correct file"),
+                        store.checker(),
+                        &output_text,
+                        checks_clone.flaky_runs,
+                    )
+                    .await;
                 }
+                Err(())
             }
-            Ok((false, expected, actual)) => {
+        },
+        Comparison::Many(store) => match store
+            .run_codes_and_compare_outputs_async(&output_text)
+            .await
+        {
+            Ok(report) if report.all_agree() => Ok(()),
+            Ok(report) => {
                 println!(
-                    "{}\n{}\n{}\n==============================\n{}\n{}\n==============================\n{}\n{}",
-                    format!("Testcase {iter} failed!").red(),
+                    "{}\n{}\n{}",
+                    format!(
+                        "Testcase {iter} failed! {} of {} programs disagree",
+                        report.disagreeing().count(),
+                        report.results.len()
+                    )
+                    .red(),
                     "INPUT".underline(),
                     &output_text.cyan(),
-                    "EXPECTED OUTPUT".underline(),
-                    expected.green(),
-                    "ACTUAL OUTPUT".underline(),
-                    actual.red()
                 );
-                has_failed_clone.store(true, Ordering::Relaxed);
+                let expected = report.reference.zip(report.reference_output());
+                for result in report.disagreeing() {
+                    match (&result.verdict, &result.output) {
+                        (ProgramVerdict::WrongAnswer(message), Some(output)) => match expected {
+                            Some((reference, expected)) => print_output_diff(
+                                &format!("EXPECTED OUTPUT ({})", report.results[reference].name),
+                                expected,
+                                &format!("{}: {message}", result.name),
+                                output,
+                            ),
+                            None => println!(
+                                "==============================\n{}\n{}",
+                                format!("{}: {message}", result.name).underline(),
+                                output.red()
+                            ),
+                        },
+                        (ProgramVerdict::Failed(err), _) => println!(
+                            "==============================\n{}",
+                            format!(
+                                "{}: {}",
+                                result.name,
+                                get_verdict(err).unwrap_or_else(|| err.to_string())
+                            )
+                            .red()
+                        ),
+                        _ => {}
+                    }
+                }
+                if let Some(recheck) = &checks_clone.sanitizer {
+                    let failing = report
+                        .disagreeing()
+                        .filter_map(|result| match &result.verdict {
+                            ProgramVerdict::WrongAnswer(_) => Some(result.name.clone()),
+                            ProgramVerdict::Failed(err)
+                                if matches!(**err, RunnerErrorType::RuntimeError(_)) =>
+                            {
+                                Some(result.name.clone())
+                            }
+                            _ => None,
+                        });
+                    report_sanitizer_findings(
+                        recheck,
+                        &failing.collect::<Vec<_>>(),
+                        &output_text,
+                        debug,
+                    )
+                    .await;
+                }
+                let rechecked: Vec<(&str, &Language)> = report
+                    .results
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, result)| {
+                        Some(*index) == report.reference || !result.verdict.is_accepted()
+                    })
+                    .filter_map(|(index, result)| {
+                        Some((result.name.as_str(), store.program(index)?))
+                    })
+                    .collect();
+                report_flaky_programs(
+                    &rechecked,
+                    report
+                        .reference
+                        .map(|index| report.results[index].name.as_str()),
+                    store.checker(),
+                    &output_text,
+                    checks_clone.flaky_runs,
+                )
+                .await;
+                Err(())
             }
             Err(err) => {
-                eprintln!("{}", format!("Error matching the file! {err}").red());
-                if let RunnerErrorType::ProgramRunError(run_err) = *err
-                    && let Some(io_err) = run_err.downcast_ref::<io::Error>()
-                    && io_err.kind() == io::ErrorKind::BrokenPipe
+                report_run_error(iter, &output_text, err);
+                // Only a failing oracle stops the comparison
+                if let ReferenceStrategy::Oracle(index) = store.reference()
+                    && let (Some(name), Some(oracle)) =
+                        (store.program_names().nth(index), store.program(index))
                 {
-                    eprintln!("Broken pipe detected!");
-                    eprintln!(
-                        "This usually happens when your clex is incorrect and it doesn't generate what your codes are expecting!"
-                    );
-                    eprintln!("Please check your clex and try again!");
+                    report_flaky_programs(
+                        &[(name, oracle)],
+                        Some(name),
+                        store.checker(),
+                        &output_text,
+                        checks_clone.flaky_runs,
+                    )
+                    .await;
                 }
-
-                has_failed_clone.store(true, Ordering::Relaxed);
+                Err(())
             }
         },
+    };
+
+    match passed {
+        Ok(()) => {
+            if !no_stop && debug {
+                eprintln!("{}", format!("Testcase {iter} ran successfully!").green());
+            }
+        }
+        Err(()) => has_failed_clone.store(true, Ordering::Relaxed),
+    }
+}
+
+/// Runs the programs at the paths again under their debug build, printing what the sanitizers
+/// found
+async fn report_sanitizer_findings(
+    recheck: &SanitizerRecheck,
+    paths: &[String],
+    output_text: &str,
+    debug: bool,
+) {
+    for path in paths {
+        if let Some(report) = recheck.recheck(path, output_text, debug).await {
+            println!(
+                "==============================\n{}\n{}",
+                format!("SANITIZER ({path}, debug build)").underline(),
+                report.to_string().magenta()
+            );
+            if debug {
+                eprintln!("{}", report.report.dimmed());
+            }
+        }
+    }
+}
+
+/// Prints where the actual output first differs from the expected one, or both outputs in full if
+/// they only differ for the checker (e.g. beyond a float tolerance)
+fn print_output_diff(expected_title: &str, expected: &str, actual_title: &str, actual: &str) {
+    let Some(diff) = diff_outputs(expected, actual, DEFAULT_CONTEXT_LINES) else {
+        println!(
+            "==============================\n{}\n{}\n==============================\n{}\n{}",
+            expected_title.underline(),
+            expected.green(),
+            actual_title.underline(),
+            actual.red()
+        );
+        return;
+    };
+
+    println!(
+        "==============================\n{}",
+        format!("DIFF (- {expected_title}, + {actual_title})").underline()
+    );
+    print!(
+        "{}",
+        diff.render(|text, side, changed| match (side, changed) {
+            (Side::Summary, _) => text.yellow(),
+            (Side::Context, _) => text.dimmed(),
+            (Side::Expected, false) => text.green(),
+            (Side::Expected, true) => text.black().on_green(),
+            (Side::Actual, false) => text.red(),
+            (Side::Actual, true) => text.white().on_red(),
+        }
+        .to_string())
+    );
+}
+
+/// Runs the programs again on the testcase, printing those whose outcome changes between runs.
+///
+/// `reference` names the program the expected output comes from, whose flakiness puts the whole
+/// verdict in doubt.
+async fn report_flaky_programs(
+    programs: &[(&str, &Language)],
+    reference: Option<&str>,
+    checker: &dyn Checker,
+    output_text: &str,
+    runs: usize,
+) {
+    if runs < 2 {
+        return;
+    }
+
+    for (name, program) in programs {
+        let report = match check_determinism_async(program, output_text, runs, checker).await {
+            Ok(report) if !report.is_deterministic() => report,
+            _ => continue,
+        };
+
+        println!(
+            "==============================\n{}",
+            format!(
+                "FLAKY ({name}, {} distinct outcomes in {runs} runs)",
+                report.outcomes.len()
+            )
+            .underline()
+        );
+        if reference == Some(name) {
+            println!(
+                "{}",
+                "The expected output changes between runs, so the other programs may not be at fault"
+                    .yellow()
+            );
+        }
+        for outcome in &report.outcomes {
+            match &outcome.outcome {
+                Ok(output) => println!(
+                    "{}\n{}",
+                    format!("{} run(s):", outcome.runs).bold(),
+                    output.magenta()
+                ),
+                Err(err) => println!(
+                    "{} {}",
+                    format!("{} run(s):", outcome.runs).bold(),
+                    get_verdict(err)
+                        .unwrap_or_else(|| err.to_string())
+                        .magenta()
+                ),
+            }
+        }
+    }
+}
+
+/// Reports an error which stopped the programs from being compared on the testcase
+fn report_run_error(iter: usize, output_text: &str, err: Box<RunnerErrorType>) {
+    if let Some(verdict) = get_verdict(&err) {
+        let verdict = match err.program_role() {
+            Some(role) => format!("{verdict} ({role} file)"),
+            None => verdict,
+        };
+        println!(
+            "{}\n{}\n{}",
+            format!("Testcase {iter} failed! {verdict}").red(),
+            "INPUT".underline(),
+            &output_text.cyan(),
+        );
+    }
+    eprintln!("{}", format!("Error matching the file! {err}").red());
+    if let RunnerErrorType::ProgramRunError(run_err) = err.program_error()
+        && let Some(io_err) = run_err.downcast_ref::<io::Error>()
+        && io_err.kind() == io::ErrorKind::BrokenPipe
+    {
+        eprintln!("Broken pipe detected!");
+        eprintln!(
+            "This usually happens when your clex is incorrect and it doesn't generate what your codes are expecting!"
+        );
+        eprintln!("Please check your clex and try again!");
     }
 }
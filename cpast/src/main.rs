@@ -17,6 +17,9 @@ async fn main() {
                 CpastSubcommands::Generate(args) => {
                     cmd::generate::generate_call(args);
                 }
+                CpastSubcommands::Perf(args) => {
+                    cmd::perf::perf_call(args).await;
+                }
                 CpastSubcommands::Ai(args) => {
                     cmd::ai::generate_clex_from_input_format_and_constraints(args)
                         .await
@@ -0,0 +1,465 @@
+//! Profiles two programs over inputs of increasing size, to hunt the inputs on which the test code
+//! is too slow rather than wrong.
+//!
+//! Inputs are generated with every capturing group of the clex scaled through its range (see
+//! `ClexLanguageAST::scale_capturing_groups`), doubling from a small fraction of it up to its
+//! maximum. Each program's CPU time and peak memory are recorded per input, and fitted against the
+//! input size to find their empirical complexity.
+
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+
+use ccode_runner::lang_runner::runner::Language;
+use ccode_runner::lang_runner::toolchain::ToolchainConfig;
+use ccode_runner::{DEFAULT_OUTPUT_LIMIT_BYTES, ExecutionLimits, ExecutionResult, ExecutionStatus};
+use clex_gen::clex_language::code_generator::Generator;
+use clex_gen::clex_language::{lexer, parser};
+use colored::Colorize;
+
+use crate::{CodeOrPath, GenericCpastError, is_clex_file};
+
+/// Differences in time below this are noise, whatever the slowdown
+const MIN_SLOWDOWN_DIFFERENCE: Duration = Duration::from_millis(5);
+
+/// Longest input printed for a slow testcase, longer ones are cut
+const MAX_PRINTED_INPUT_LENGTH: usize = 500;
+
+/// Candidate complexities, from the slowest growing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Complexity {
+    Constant,
+    Logarithmic,
+    Linear,
+    Linearithmic,
+    Quadratic,
+    Cubic,
+}
+
+impl Complexity {
+    pub const ALL: [Complexity; 6] = [
+        Complexity::Constant,
+        Complexity::Logarithmic,
+        Complexity::Linear,
+        Complexity::Linearithmic,
+        Complexity::Quadratic,
+        Complexity::Cubic,
+    ];
+
+    /// Growth of the complexity at size `n`
+    pub fn growth(self, n: f64) -> f64 {
+        match self {
+            Complexity::Constant => 1.0,
+            Complexity::Logarithmic => n.max(1.0).log2(),
+            Complexity::Linear => n,
+            Complexity::Linearithmic => n * n.max(1.0).log2(),
+            Complexity::Quadratic => n * n,
+            Complexity::Cubic => n * n * n,
+        }
+    }
+}
+
+impl fmt::Display for Complexity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Complexity::Constant => write!(f, "O(1)"),
+            Complexity::Logarithmic => write!(f, "O(log n)"),
+            Complexity::Linear => write!(f, "O(n)"),
+            Complexity::Linearithmic => write!(f, "O(n log n)"),
+            Complexity::Quadratic => write!(f, "O(n^2)"),
+            Complexity::Cubic => write!(f, "O(n^3)"),
+        }
+    }
+}
+
+/// A complexity fitted to measurements, as `intercept + coefficient * growth(n)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComplexityFit {
+    pub complexity: Complexity,
+    /// Cost independent of the size, e.g. starting the process
+    pub intercept: f64,
+    pub coefficient: f64,
+    /// Root mean square of the residuals, relative to the mean measurement
+    pub relative_error: f64,
+}
+
+/// Fits each candidate complexity to the `(size, measurement)` samples by least squares, and picks
+/// the slowest growing one fitting about as well as the best one, as noise lets faster growing
+/// curves fit slightly better. Over the usual range of sizes, `O(n log n)` and `O(n)` are barely
+/// apart, so they are easily mistaken for each other.
+///
+/// # Returns
+///
+/// `None` if there are fewer than 3 distinct sizes, too few to tell complexities apart
+pub fn fit_complexity(samples: &[(f64, f64)]) -> Option<ComplexityFit> {
+    let mut sizes: Vec<f64> = samples.iter().map(|(n, _)| *n).collect();
+    sizes.sort_by(f64::total_cmp);
+    sizes.dedup();
+    if sizes.len() < 3 {
+        return None;
+    }
+
+    let mean = samples.iter().map(|(_, y)| y).sum::<f64>() / samples.len() as f64;
+    let fits: Vec<ComplexityFit> = Complexity::ALL
+        .iter()
+        .map(|&complexity| fit(samples, complexity, mean))
+        .collect();
+    let best = fits
+        .iter()
+        .map(|fit| fit.relative_error)
+        .fold(f64::INFINITY, f64::min);
+    fits.into_iter()
+        .find(|fit| fit.relative_error <= best * 1.2 + 0.02)
+}
+
+/// Least squares fit of `y = intercept + coefficient * growth(n)`, with a coefficient of at least 0
+fn fit(samples: &[(f64, f64)], complexity: Complexity, mean: f64) -> ComplexityFit {
+    let count = samples.len() as f64;
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|&(n, y)| (complexity.growth(n), y))
+        .collect();
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean)).sum();
+    let coefficient = match variance > 0.0 {
+        true => (covariance / variance).max(0.0),
+        false => 0.0,
+    };
+    let intercept = mean - coefficient * mean_x;
+
+    let squared_error: f64 = points
+        .iter()
+        .map(|(x, y)| (y - intercept - coefficient * x).powi(2))
+        .sum();
+    let relative_error = match mean > 0.0 {
+        true => (squared_error / count).sqrt() / mean,
+        false => 0.0,
+    };
+    ComplexityFit {
+        complexity,
+        intercept,
+        coefficient,
+        relative_error,
+    }
+}
+
+/// How a program ran on an input
+#[derive(Debug, Clone)]
+pub struct RunProfile {
+    /// CPU time, or the wall clock time where it isn't measured
+    pub time: Duration,
+    pub peak_memory_bytes: Option<u64>,
+    pub status: ExecutionStatus,
+}
+
+impl From<ExecutionResult> for RunProfile {
+    fn from(result: ExecutionResult) -> Self {
+        RunProfile {
+            time: result.cpu_time.unwrap_or(result.wall_time),
+            peak_memory_bytes: result.peak_memory_bytes,
+            status: result.status,
+        }
+    }
+}
+
+/// Both programs run on one input
+#[derive(Debug, Clone)]
+pub struct PerfSample {
+    pub input: String,
+    pub correct: RunProfile,
+    pub test: RunProfile,
+}
+
+impl PerfSample {
+    /// Size of the input in bytes, which the complexities are fitted against
+    pub fn size(&self) -> usize {
+        self.input.len()
+    }
+
+    /// How many times slower the test code was than the correct code
+    pub fn slowdown(&self) -> f64 {
+        self.test.time.as_secs_f64() / self.correct.time.as_secs_f64().max(1e-6)
+    }
+
+    /// Whether the test code was `slowdown` times slower than the correct code, or only the test
+    /// code exceeded the time limit
+    pub fn is_slow(&self, slowdown: f64) -> bool {
+        let timed_out = |profile: &RunProfile| {
+            matches!(
+                profile.status,
+                ExecutionStatus::TimeLimitExceeded | ExecutionStatus::CpuTimeLimitExceeded
+            )
+        };
+        (timed_out(&self.test) && !timed_out(&self.correct))
+            || (self.slowdown() >= slowdown
+                && self.test.time.saturating_sub(self.correct.time) >= MIN_SLOWDOWN_DIFFERENCE)
+    }
+}
+
+/// Measurements of both programs, with their fitted complexities
+#[derive(Debug, Clone)]
+pub struct PerfReport {
+    /// Samples in the order they ran, by increasing size
+    pub samples: Vec<PerfSample>,
+    pub correct_time: Option<ComplexityFit>,
+    pub test_time: Option<ComplexityFit>,
+    pub correct_memory: Option<ComplexityFit>,
+    pub test_memory: Option<ComplexityFit>,
+}
+
+impl PerfReport {
+    fn new(samples: Vec<PerfSample>) -> Self {
+        let fit_by = |profile: fn(&PerfSample) -> &RunProfile, memory: bool| {
+            let points: Vec<(f64, f64)> = samples
+                .iter()
+                .filter(|sample| profile(sample).status == ExecutionStatus::Success)
+                .filter_map(|sample| {
+                    let profile = profile(sample);
+                    let y = match memory {
+                        true => profile.peak_memory_bytes? as f64,
+                        false => profile.time.as_secs_f64() * 1000.0,
+                    };
+                    Some((sample.size() as f64, y))
+                })
+                .collect();
+            fit_complexity(&points)
+        };
+
+        PerfReport {
+            correct_time: fit_by(|sample| &sample.correct, false),
+            test_time: fit_by(|sample| &sample.test, false),
+            correct_memory: fit_by(|sample| &sample.correct, true),
+            test_memory: fit_by(|sample| &sample.test, true),
+            samples,
+        }
+    }
+
+    /// Samples on which the test code was `slowdown` times slower than the correct code
+    pub fn slow_samples(&self, slowdown: f64) -> impl Iterator<Item = &PerfSample> {
+        self.samples
+            .iter()
+            .filter(move |sample| sample.is_slow(slowdown))
+    }
+}
+
+/// Compile and profile code over inputs of increasing size generated from the clex.
+///
+/// Inputs are generated at `steps` sizes, each capturing group doubling from `1 / 2^(steps - 1)`
+/// of its range up to its maximum, with `inputs_per_step` inputs of each size. The programs run
+/// one at a time, so they don't compete for the CPU, under the `time_limit`; profiling stops at the
+/// first size on which either of them exceeds it.
+///
+/// The measurements and fitted complexities are printed, along with the inputs on which the test
+/// code is `slowdown` times slower than the correct code, and returned.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ccode_runner::lang_runner::toolchain::ToolchainConfig;
+/// use std::time::Duration;
+///
+/// async fn profile() {
+///     cpast::perf::compile_and_profile(cpast::CodeOrPath::Path("correct.cpp".to_string()), "slow.cpp".to_string(), "(N[1,100000]) (?:N[1,1000000000]){\\1}".to_string(), 8, 3, 3.0, Duration::from_secs(5), false, ToolchainConfig::builtin()).await.unwrap();
+/// }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub async fn compile_and_profile(
+    correct_binding: CodeOrPath,
+    test_binding: String,
+    language: String,
+    steps: usize,
+    inputs_per_step: usize,
+    slowdown: f64,
+    time_limit: Duration,
+    do_force_compile: bool,
+    toolchains: &ToolchainConfig,
+) -> Result<PerfReport, GenericCpastError> {
+    let limits = ExecutionLimits::new()
+        .with_time_limit(time_limit.as_millis() as u64)
+        .with_output_limit(DEFAULT_OUTPUT_LIMIT_BYTES);
+    let correct = match correct_binding {
+        CodeOrPath::Code(code, lang) => Language::new_from_text_with_toolchain(
+            &code,
+            lang,
+            do_force_compile,
+            limits,
+            toolchains,
+        )?,
+        CodeOrPath::Path(path) => {
+            Language::new_with_toolchain(Path::new(&path), do_force_compile, limits, toolchains)?
+        }
+    };
+    let test = Language::new_with_toolchain(
+        Path::new(&test_binding),
+        do_force_compile,
+        limits,
+        toolchains,
+    )?;
+
+    let mut parser = if is_clex_file(&language) {
+        parser::Parser::new_from_file(&language)?
+    } else {
+        let mut token = lexer::Tokens::new(language);
+        token.scan_tokens()?;
+        parser::Parser::new_from_tokens(token)
+    };
+    parser.parser()?;
+    let ast = parser.get_language();
+
+    let mut samples = Vec::new();
+    'steps: for step in 0..steps {
+        let scale = 0.5_f64.powi((steps - 1 - step) as i32);
+        let generator = Generator::from_ast(ast.scale_capturing_groups(scale));
+        for _ in 0..inputs_per_step {
+            let input = generator.generate_testcases()?;
+            let correct_run = RunProfile::from(correct.run_program_code_async(&input).await?);
+            let test_run = RunProfile::from(test.run_program_code_async(&input).await?);
+            let timed_out = [&correct_run, &test_run]
+                .iter()
+                .any(|run| run.status == ExecutionStatus::TimeLimitExceeded);
+            print_sample_row(samples.is_empty(), input.len(), &correct_run, &test_run);
+            samples.push(PerfSample {
+                input,
+                correct: correct_run,
+                test: test_run,
+            });
+            if timed_out {
+                println!(
+                    "{}",
+                    "Stopping at this size, larger inputs would exceed the time limit too".yellow()
+                );
+                break 'steps;
+            }
+        }
+    }
+
+    let report = PerfReport::new(samples);
+    print_report(&report, slowdown);
+    Ok(report)
+}
+
+fn format_run(run: &RunProfile) -> String {
+    let memory = run.peak_memory_bytes.map_or(String::from("-"), |bytes| {
+        format!("{:.1}", bytes as f64 / (1024.0 * 1024.0))
+    });
+    let time = format!("{:.1}", run.time.as_secs_f64() * 1000.0);
+    match run.status {
+        ExecutionStatus::Success => format!("{time:>10} {memory:>10}"),
+        ExecutionStatus::TimeLimitExceeded | ExecutionStatus::CpuTimeLimitExceeded => {
+            format!("{:>10} {memory:>10}", "TLE")
+        }
+        _ => format!("{:>10} {memory:>10}", "RE"),
+    }
+}
+
+fn print_sample_row(header: bool, size: usize, correct: &RunProfile, test: &RunProfile) {
+    if header {
+        println!(
+            "{}",
+            format!(
+                "{:>12} | {:>10} {:>10} | {:>10} {:>10}",
+                "SIZE (bytes)", "CORRECT ms", "MiB", "TEST ms", "MiB"
+            )
+            .underline()
+        );
+    }
+    println!(
+        "{size:>12} | {} | {}",
+        format_run(correct).green(),
+        format_run(test).cyan()
+    );
+}
+
+fn describe_fit(fit: Option<ComplexityFit>, measure: &str) -> String {
+    match fit {
+        Some(fit) => format!(
+            "{} {measure} (off by {:.0}% on average)",
+            fit.complexity,
+            fit.relative_error * 100.0
+        ),
+        None => format!("unknown {measure}, too few sizes ran"),
+    }
+}
+
+fn print_report(report: &PerfReport, slowdown: f64) {
+    println!(
+        "==============================\n{}\n{} {}, {}\n{} {}, {}",
+        "EMPIRICAL COMPLEXITY (n = input size)".underline(),
+        "Correct code:".bold(),
+        describe_fit(report.correct_time, "time"),
+        describe_fit(report.correct_memory, "memory"),
+        "Test code:".bold(),
+        describe_fit(report.test_time, "time"),
+        describe_fit(report.test_memory, "memory"),
+    );
+    if let (Some(correct), Some(test)) = (report.correct_time, report.test_time)
+        && test.complexity > correct.complexity
+    {
+        println!(
+            "{}",
+            format!(
+                "The test code's time grows as {}, faster than the correct code's {}",
+                test.complexity, correct.complexity
+            )
+            .yellow()
+        );
+    }
+
+    let slow: Vec<&PerfSample> = report.slow_samples(slowdown).collect();
+    let Some(smallest) = slow.iter().min_by_key(|sample| sample.size()) else {
+        println!(
+            "{}",
+            format!("🐣 The test code was never {slowdown}x slower than the correct code!")
+                .bold()
+                .green()
+        );
+        return;
+    };
+
+    println!(
+        "==============================\n{}",
+        format!("SLOW INPUTS ({} of {})", slow.len(), report.samples.len()).underline()
+    );
+    for sample in &slow {
+        let test_time = sample.test.time.as_secs_f64() * 1000.0;
+        let verdict = match sample.test.status {
+            ExecutionStatus::Success => format!(
+                "{:.1}x slower ({test_time:.1} ms vs {:.1} ms)",
+                sample.slowdown(),
+                sample.correct.time.as_secs_f64() * 1000.0
+            ),
+            ExecutionStatus::TimeLimitExceeded | ExecutionStatus::CpuTimeLimitExceeded => {
+                String::from("exceeded the time limit")
+            }
+            // Slow, but also failing, which is worth knowing before looking into its speed
+            ExecutionStatus::MemoryLimitExceeded => {
+                format!("exceeded the memory limit after {test_time:.1} ms")
+            }
+            ExecutionStatus::OutputLimitExceeded => {
+                format!("exceeded the output limit after {test_time:.1} ms")
+            }
+            ExecutionStatus::Signaled(signal) => {
+                format!("was killed by signal {signal} after {test_time:.1} ms")
+            }
+            ExecutionStatus::NonZeroExit(code) => {
+                format!("exited with code {code} after {test_time:.1} ms")
+            }
+        };
+        println!(
+            "{}",
+            format!("Test code {verdict} on {} bytes of input", sample.size()).red()
+        );
+    }
+
+    // The smallest slow input is the easiest to debug with
+    let input = match smallest.input.char_indices().nth(MAX_PRINTED_INPUT_LENGTH) {
+        Some((end, _)) => format!("{}...", &smallest.input[..end]),
+        None => smallest.input.clone(),
+    };
+    println!(
+        "==============================\n{}\n{}",
+        format!("SMALLEST SLOW INPUT ({} bytes)", smallest.size()).underline(),
+        input.cyan()
+    );
+}
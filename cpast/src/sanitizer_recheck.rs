@@ -0,0 +1,129 @@
+//! Re-runs failing testcases under the debug build of the tested programs, where out of bounds
+//! accesses and undefined behavior, which otherwise just look like wrong answers, are stopped by
+//! sanitizers.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use ccode_runner::ExecutionLimits;
+use ccode_runner::lang_runner::runner::Language;
+use ccode_runner::lang_runner::sanitizer::{SanitizerReport, parse_sanitizer_report};
+use ccode_runner::lang_runner::toolchain::{BuildProfile, ToolchainConfig};
+use colored::Colorize;
+use tokio::sync::OnceCell;
+
+/// Debug build of a program, set to `None` if it has none or fails to compile
+type DebugBuild = Arc<OnceCell<Option<Arc<Language>>>>;
+
+/// Debug builds of the tested programs, compiled the first time one of them fails
+pub(crate) struct SanitizerRecheck {
+    /// Source files which may be rechecked, others aren't built
+    paths: Vec<String>,
+    toolchains: Arc<ToolchainConfig>,
+    /// Limits of the tested programs, which their debug builds are run with too
+    limits: ExecutionLimits,
+    do_force_compile: bool,
+    builds: Mutex<HashMap<String, DebugBuild>>,
+}
+
+impl SanitizerRecheck {
+    pub(crate) fn new(
+        paths: Vec<String>,
+        toolchains: &ToolchainConfig,
+        limits: ExecutionLimits,
+        do_force_compile: bool,
+    ) -> Self {
+        SanitizerRecheck {
+            paths,
+            toolchains: Arc::new(toolchains.clone().with_profile(BuildProfile::Debug)),
+            limits,
+            do_force_compile,
+            builds: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Source files which may be rechecked
+    pub(crate) fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    /// What a sanitizer found running the debug build of the program on the input, `None` if it
+    /// ran cleanly, or if the program has no debug build
+    pub(crate) async fn recheck(
+        &self,
+        path: &str,
+        input: &str,
+        debug: bool,
+    ) -> Option<SanitizerReport> {
+        let language = self.build(path, debug).await?;
+        match language.run_program_code_async(input).await {
+            Ok(result) if !result.is_success() => parse_sanitizer_report(&result.stderr_lossy()),
+            Ok(_) => None,
+            Err(err) => {
+                if debug {
+                    eprintln!("[SANITIZER] Failed to run the debug build of {path}: {err}");
+                }
+                None
+            }
+        }
+    }
+
+    /// Debug build of the program, compiled once and shared by every testcase
+    async fn build(&self, path: &str, debug: bool) -> Option<Arc<Language>> {
+        if !self.paths.iter().any(|recheckable| recheckable == path) {
+            return None;
+        }
+        let cell = Arc::clone(
+            self.builds
+                .lock()
+                .unwrap()
+                .entry(path.to_string())
+                .or_default(),
+        );
+
+        cell.get_or_init(|| async {
+            // Only languages with debug compilers have anything to catch
+            let has_debug_build = self
+                .toolchains
+                .language_of(Path::new(path))
+                .and_then(|lang| self.toolchains.get(&lang))
+                .is_ok_and(|toolchain| !toolchain.debug_compile.is_empty());
+            if !has_debug_build {
+                return None;
+            }
+
+            if debug {
+                eprintln!(
+                    "{}",
+                    format!("Compiling the debug build of {path}...").bright_blue()
+                );
+            }
+            let toolchains = Arc::clone(&self.toolchains);
+            let (limits, do_force_compile) = (self.limits, self.do_force_compile);
+            let source_path = path.to_string();
+            let language = tokio::task::spawn_blocking(move || {
+                Language::new_with_toolchain(
+                    Path::new(&source_path),
+                    do_force_compile,
+                    limits,
+                    &toolchains,
+                )
+            })
+            .await;
+
+            match language {
+                Ok(Ok(language)) => Some(Arc::new(language)),
+                Ok(Err(err)) => {
+                    if debug {
+                        eprintln!("[SANITIZER] Failed to build the debug build of {path}: {err}");
+                    }
+                    None
+                }
+                Err(_) => None,
+            }
+        })
+        .await
+        .clone()
+    }
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use ccode_runner::ExecutionLimits;
+use ccode_runner::lang_runner::checker::TextChecker;
+use ccode_runner::lang_runner::toolchain::ToolchainConfig;
+use cpast::{CodeOrPath, compile_and_test_with_checker};
+
+#[tokio::test]
+async fn test_limits_stop_programs_which_never_finish() {
+    let dir = tempfile::tempdir().unwrap();
+    let correct_file = dir.path().join("correct.py");
+    let test_file = dir.path().join("test.py");
+    std::fs::write(&correct_file, "print(input())").unwrap();
+    std::fs::write(&test_file, "while True:\n    pass").unwrap();
+
+    let run = compile_and_test_with_checker(
+        CodeOrPath::Path(correct_file.display().to_string()),
+        test_file.display().to_string(),
+        String::from("N[1,10]"),
+        1,
+        false,
+        false,
+        false,
+        Box::new(TextChecker),
+        ToolchainConfig::builtin(),
+        ExecutionLimits::new()
+            .with_time_limit(500)
+            .with_cpu_time_limit(500),
+        false,
+        0,
+    );
+
+    // Without the limits, the test file would run forever
+    tokio::time::timeout(Duration::from_secs(30), run)
+        .await
+        .expect("the limits should stop the test file")
+        .unwrap();
+}
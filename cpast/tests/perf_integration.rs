@@ -0,0 +1,113 @@
+use std::io::Write;
+use std::time::Duration;
+
+use ccode_runner::ExecutionStatus;
+use ccode_runner::lang_runner::toolchain::ToolchainConfig;
+use cpast::CodeOrPath;
+use cpast::perf::{Complexity, PerfSample, RunProfile, compile_and_profile, fit_complexity};
+
+/// Cost of a run at size `n`, on top of starting the process
+type Cost = fn(f64) -> f64;
+
+/// Samples of `startup + cost(n)` at doubling sizes, with a little deterministic noise
+fn samples(startup: f64, cost: Cost) -> Vec<(f64, f64)> {
+    (0..8)
+        .map(|step| {
+            let n = 1000.0 * 2f64.powi(step);
+            let noise = if step % 2 == 0 { 1.02 } else { 0.98 };
+            (n, (startup + cost(n)) * noise)
+        })
+        .collect()
+}
+
+#[test]
+fn test_fit_complexity_finds_growth() {
+    let cases: [(Complexity, Cost); 4] = [
+        (Complexity::Constant, |_| 0.0),
+        (Complexity::Linear, |n| n / 1000.0),
+        (Complexity::Quadratic, |n| n * n / 1e6),
+        (Complexity::Cubic, |n| n * n * n / 1e9),
+    ];
+    for (complexity, cost) in cases {
+        let fit = fit_complexity(&samples(20.0, cost)).unwrap();
+        assert_eq!(fit.complexity, complexity);
+    }
+}
+
+#[test]
+fn test_fit_complexity_needs_three_sizes() {
+    assert_eq!(fit_complexity(&[(1.0, 1.0), (2.0, 2.0), (2.0, 2.1)]), None);
+    assert!(fit_complexity(&[(1.0, 1.0), (2.0, 2.0), (4.0, 4.0)]).is_some());
+}
+
+fn run(time_ms: u64, status: ExecutionStatus) -> RunProfile {
+    RunProfile {
+        time: Duration::from_millis(time_ms),
+        peak_memory_bytes: None,
+        status,
+    }
+}
+
+#[test]
+fn test_slow_samples_ignore_noise() {
+    let sample = |correct, test| PerfSample {
+        input: String::from("1"),
+        correct: run(correct, ExecutionStatus::Success),
+        test: run(test, ExecutionStatus::Success),
+    };
+    assert!(sample(10, 40).is_slow(3.0));
+    assert!(!sample(10, 20).is_slow(3.0));
+    // 4 times slower, but only by 3 ms
+    assert!(!sample(1, 4).is_slow(3.0));
+
+    let timed_out = PerfSample {
+        input: String::from("1"),
+        correct: run(10, ExecutionStatus::Success),
+        test: run(10, ExecutionStatus::TimeLimitExceeded),
+    };
+    assert!(timed_out.is_slow(3.0));
+}
+
+#[tokio::test]
+async fn test_profile_finds_slow_inputs() {
+    let dir = tempfile::tempdir().unwrap();
+    let write = |name: &str, source: &str| {
+        let path = dir.path().join(name);
+        std::fs::File::create(&path)
+            .unwrap()
+            .write_all(source.as_bytes())
+            .unwrap();
+        path.to_str().unwrap().to_string()
+    };
+    let correct = write(
+        "correct.py",
+        "n, *a = map(int, open(0).read().split())\nprint(sum(a))",
+    );
+    let test = write(
+        "test.py",
+        "n, *a = map(int, open(0).read().split())\nprint(sum(a[i] for i in range(n) for j in range(n) if i == j))",
+    );
+
+    let report = compile_and_profile(
+        CodeOrPath::Path(correct),
+        test,
+        "(N[1,5000]) N[1,9]{\\1}".to_string(),
+        3,
+        1,
+        3.0,
+        Duration::from_secs(20),
+        false,
+        &ToolchainConfig::default(),
+    )
+    .await
+    .unwrap();
+
+    let sizes: Vec<usize> = report.samples.iter().map(PerfSample::size).collect();
+    assert_eq!(sizes, [2506, 5006, 10004]);
+    assert!(report.correct_time.is_some());
+    assert!(
+        report
+            .slow_samples(3.0)
+            .any(|sample| sample.size() == 10004)
+    );
+}
@@ -0,0 +1,2 @@
+-- Languages are stored as TEXT, the enum type only lists the languages supported back then
+DROP TYPE IF EXISTS language_name;
@@ -18,6 +18,25 @@ pub struct ApplicationSettings {
     pub host: String,
     pub base_url: String,
     pub hmac_secret: SecretString,
+    /// TOML file overriding how languages are compiled and run, built-in toolchains if unset
+    #[serde(default)]
+    pub toolchain_path: Option<String>,
+    /// Directory of the compilation cache, shared with the CLI, default cache directory if unset
+    #[serde(default)]
+    pub compile_cache_dir: Option<String>,
+    /// Size limit of the compilation cache in bytes, 512 MiB if unset
+    #[serde(default)]
+    pub compile_cache_max_size: Option<u64>,
+    /// Wall clock time limit of each run of the evaluated code in milliseconds, 10 seconds if unset
+    #[serde(default)]
+    pub time_limit_ms: Option<u64>,
+    /// Output limit of each run of the evaluated code in bytes, 64 MiB if unset
+    #[serde(default)]
+    pub output_limit_bytes: Option<u64>,
+    /// Run the evaluated code and special judges in the sandbox, which needs unprivileged user
+    /// namespaces (in Docker, e.g. `--security-opt seccomp=unconfined`), disabled if unset
+    #[serde(default)]
+    pub sandbox: bool,
 }
 
 #[derive(serde::Deserialize, Clone)]
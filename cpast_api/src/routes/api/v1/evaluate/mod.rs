@@ -1,12 +1,19 @@
-use actix_web::{ResponseError, http::StatusCode};
+use actix_web::{HttpResponse, ResponseError, http::StatusCode, http::header::ContentType};
 use anyhow::Context;
 use ccode_runner::lang_runner::{
-    language_name::LanguageName, program_store::ProgramStore, runner_error_types::RunnerErrorType,
+    checker::CheckerKind,
+    diagnostics::CompilerAttempt,
+    language_name::LanguageName,
+    program_store::{ProgramRole, ProgramStore},
+    runner_error_types::RunnerErrorType,
+    toolchain::ToolchainConfig,
 };
+use ccode_runner::{DEFAULT_CONTEXT_LINES, ExecutionLimits, OutputDiff, diff_outputs};
 use clex_gen::clex_language::{self, code_generator::Generator, lexer};
 use serde::Serialize;
 use sha2::{Digest, Sha256};
 use sqlx::{Executor, PgPool, Postgres, Transaction};
+use std::path::Path;
 use utoipa::{OpenApi, ToSchema};
 
 pub(crate) mod with_code_and_clex;
@@ -35,6 +42,75 @@ struct EvaluateCodeInputDiff {
 
     #[schema(example = "Hello, worldd!")]
     actual_output: String,
+
+    /// Where the actual output first differs from the expected output, None if they only differ
+    /// for the checker (e.g. beyond a float tolerance)
+    diff: Option<OutputDiff>,
+}
+
+/// How a program failed to run on an input
+#[derive(Serialize, ToSchema)]
+enum RunVerdict {
+    TimeLimitExceeded,
+    CpuTimeLimitExceeded,
+    MemoryLimitExceeded,
+    OutputLimitExceeded,
+    RuntimeError,
+}
+
+#[derive(Serialize, ToSchema)]
+struct EvaluateCodeRunFailure {
+    #[schema(example = "world")]
+    input: String,
+
+    #[schema(example = "Test")]
+    program: ProgramRole,
+
+    #[schema(example = "TimeLimitExceeded")]
+    verdict: RunVerdict,
+
+    /// What went wrong, e.g. the stderr of a program which crashed
+    #[schema(example = "Exceeded the time limit of 5000 milliseconds")]
+    message: String,
+}
+
+impl EvaluateCodeRunFailure {
+    /// The failure of a program on the input, None if the error isn't about how a program ran
+    fn new(input: &str, err: &RunnerErrorType) -> Option<Self> {
+        let (verdict, message) = match err.program_error() {
+            RunnerErrorType::TimeLimitExceeded(time_limit_ms) => (
+                RunVerdict::TimeLimitExceeded,
+                format!("Exceeded the time limit of {time_limit_ms} milliseconds"),
+            ),
+            RunnerErrorType::CpuTimeLimitExceeded(cpu_time_limit_ms) => (
+                RunVerdict::CpuTimeLimitExceeded,
+                format!("Exceeded the CPU time limit of {cpu_time_limit_ms} milliseconds"),
+            ),
+            RunnerErrorType::MemoryLimitExceeded(memory_limit_bytes) => (
+                RunVerdict::MemoryLimitExceeded,
+                format!("Exceeded the memory limit of {memory_limit_bytes} bytes"),
+            ),
+            RunnerErrorType::OutputLimitExceeded(output_limit_bytes) => (
+                RunVerdict::OutputLimitExceeded,
+                format!("Exceeded the output limit of {output_limit_bytes} bytes"),
+            ),
+            RunnerErrorType::RuntimeError(result) => {
+                (RunVerdict::RuntimeError, result.stderr_lossy().into_owned())
+            }
+            RunnerErrorType::SanitizerError(_, report) => (
+                RunVerdict::RuntimeError,
+                format!("{report}\n{}", report.report),
+            ),
+            _ => return None,
+        };
+
+        Some(EvaluateCodeRunFailure {
+            input: input.to_string(),
+            program: err.program_role()?,
+            verdict,
+            message,
+        })
+    }
 }
 
 #[derive(Serialize, ToSchema)]
@@ -46,13 +122,27 @@ struct EvaluateCodeResponse {
         input: "world".to_string(),
         expected_output: "Hello, world!".to_string(),
         actual_output: "Hello, worldd!".to_string(),
+        diff: diff_outputs("Hello, world!", "Hello, worldd!", DEFAULT_CONTEXT_LINES),
     }])))]
     input_diffs: Vec<EvaluateCodeInputDiff>,
 
+    /// The program which failed to run on an input, which ends the evaluation
+    run_failure: Option<EvaluateCodeRunFailure>,
+
     #[schema(example = "N[1,1000]")]
     clex: String,
 }
 
+/// Body of the 400 response when the submitted code doesn't compile
+#[derive(Serialize, ToSchema)]
+pub(crate) struct CompilationErrorResponse {
+    #[schema(example = "Failed to compile source.cpp")]
+    error: String,
+
+    /// Each compiler tried, with its output and the diagnostics parsed from it
+    attempts: Vec<CompilerAttempt>,
+}
+
 #[derive(thiserror::Error)]
 pub enum EvaluateAPIError {
     #[error(transparent)]
@@ -100,13 +190,37 @@ impl ResponseError for EvaluateAPIError {
             EvaluateAPIError::DirtyLanguageInDatabase(_) => StatusCode::INTERNAL_SERVER_ERROR,
             EvaluateAPIError::ShareIdNotFound(_) => StatusCode::NOT_FOUND,
             EvaluateAPIError::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
-            EvaluateAPIError::APIRunnerErrorType(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            EvaluateAPIError::APIRunnerErrorType(err) => match **err {
+                RunnerErrorType::CompilationError(..) => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            },
             EvaluateAPIError::ClexLLMError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             EvaluateAPIError::InvalidInputFormatOrConstraints => StatusCode::BAD_REQUEST,
             EvaluateAPIError::InvalidProblemURL => StatusCode::BAD_REQUEST,
             EvaluateAPIError::ScrapperError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    fn error_response(&self) -> HttpResponse {
+        if let EvaluateAPIError::APIRunnerErrorType(err) = self
+            && let RunnerErrorType::CompilationError(source_path, attempts) = err.as_ref()
+        {
+            let file_name = source_path.file_name().unwrap_or_default().display();
+            // The code is compiled in a temporary directory, which is no business of the client
+            let source_dir = source_path.parent().unwrap_or(Path::new(""));
+            return HttpResponse::build(self.status_code()).json(CompilationErrorResponse {
+                error: format!("Failed to compile {file_name}"),
+                attempts: attempts
+                    .iter()
+                    .map(|attempt| attempt.relative_to(source_dir))
+                    .collect(),
+            });
+        }
+
+        HttpResponse::build(self.status_code())
+            .insert_header(ContentType::plaintext())
+            .body(self.to_string())
+    }
 }
 
 pub fn error_chain_fmt(
@@ -127,20 +241,41 @@ fn verify_clex(clex: &str) -> Result<(), EvaluateAPIError> {
     Ok(())
 }
 
-fn run_and_compare(
+#[allow(clippy::too_many_arguments)]
+async fn run_and_compare(
     correct_code: &str,
     test_code: &str,
     correct_code_language: LanguageName,
     test_code_language: LanguageName,
     clex_language: &str,
+    checker: CheckerKind,
+    toolchains: &ToolchainConfig,
+    limits: ExecutionLimits,
 ) -> Result<EvaluateCodeResponse, EvaluateAPIError> {
-    let runner = ProgramStore::new_from_text(
-        correct_code,
-        test_code,
-        correct_code_language.clone(),
-        test_code_language.clone(),
-        false,
-    )
+    let (correct_code, test_code, toolchains) = (
+        correct_code.to_string(),
+        test_code.to_string(),
+        toolchains.clone(),
+    );
+    // Compilation blocks, so it runs on the blocking thread pool
+    let runner = actix_web::web::block(move || {
+        Ok::<_, Box<RunnerErrorType>>(
+            ProgramStore::new_from_text_with_toolchain(
+                &correct_code,
+                &test_code,
+                correct_code_language,
+                test_code_language,
+                false,
+                limits,
+                &toolchains,
+            )?
+            // The special judge is as untrusted as the code it checks, so it runs with the same
+            // limits (and sandbox)
+            .with_checker(checker.build(limits)?),
+        )
+    })
+    .await
+    .map_err(|err| EvaluateAPIError::UnexpectedError(anyhow::anyhow!("{err}")))?
     .map_err(EvaluateAPIError::APIRunnerErrorType)?;
 
     let mut token = lexer::Tokens::new(clex_language.to_string());
@@ -156,6 +291,7 @@ fn run_and_compare(
     let mut response = EvaluateCodeResponse {
         has_output_matched: true,
         input_diffs: Vec::new(),
+        run_failure: None,
         clex: clex_language.to_string(),
     };
 
@@ -163,12 +299,22 @@ fn run_and_compare(
         let testcase = generator
             .generate_testcases()
             .map_err(|e| EvaluateAPIError::APIClexErrorType(e.into()))?;
-        let (matched, expected, actual) = runner
-            .run_codes_and_compare_output(&testcase)
-            .map_err(EvaluateAPIError::APIRunnerErrorType)?;
+        let (matched, expected, actual) =
+            match runner.run_codes_and_compare_output_async(&testcase).await {
+                Ok(outputs) => outputs,
+                Err(err) => match EvaluateCodeRunFailure::new(&testcase, &err) {
+                    Some(failure) => {
+                        response.has_output_matched = false;
+                        response.run_failure = Some(failure);
+                        break;
+                    }
+                    None => return Err(EvaluateAPIError::APIRunnerErrorType(err)),
+                },
+            };
         if !matched {
             response.has_output_matched = false;
             response.input_diffs.push(EvaluateCodeInputDiff {
+                diff: diff_outputs(&expected, &actual, DEFAULT_CONTEXT_LINES),
                 input: testcase,
                 expected_output: expected,
                 actual_output: actual,
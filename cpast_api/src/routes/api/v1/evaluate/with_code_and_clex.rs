@@ -1,14 +1,17 @@
-use actix_web::HttpResponse;
 use actix_web::post;
 use actix_web::web::Json;
+use actix_web::{HttpResponse, web};
+use ccode_runner::ExecutionLimits;
+use ccode_runner::lang_runner::checker::CheckerKind;
 use ccode_runner::lang_runner::language_name::LanguageName;
+use ccode_runner::lang_runner::toolchain::ToolchainConfig;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use crate::routes::api::v1::evaluate::run_and_compare;
 use crate::routes::api::v1::evaluate::verify_clex;
 
-use super::{EvaluateAPIError, EvaluateCodeResponse};
+use super::{CompilationErrorResponse, EvaluateAPIError, EvaluateCodeResponse};
 
 #[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
 struct EvaluateCodeWithClexRequest {
@@ -26,18 +29,24 @@ struct EvaluateCodeWithClexRequest {
 
     #[schema(example = "N[1,50] S[1, 10, @CH_UPPER@]")]
     clex: String,
+
+    #[serde(default)]
+    #[schema(example = "Token")]
+    checker: CheckerKind,
 }
 
 #[utoipa::path(
     responses(
         (status = 200, description = "Successful evaluation", body = EvaluateCodeResponse),
-        (status = 400, description = "Invalid clex", body = String),
+        (status = 400, description = "Invalid clex, Code failing to compile (JSON body)", content((String = "text/plain"), (CompilationErrorResponse = "application/json"))),
         (status = 500, description = "Program Exceution Error/Unexpected server error", body = String)
     )
 )]
 #[post("/with_code_and_clex")]
 pub async fn post_with_code_and_clex(
     code_request: Json<EvaluateCodeWithClexRequest>,
+    toolchains: web::Data<ToolchainConfig>,
+    limits: web::Data<ExecutionLimits>,
 ) -> Result<HttpResponse, EvaluateAPIError> {
     verify_clex(&code_request.clex)?;
 
@@ -47,7 +56,11 @@ pub async fn post_with_code_and_clex(
         code_request.correct_code_language.clone(),
         code_request.test_code_language.clone(),
         &code_request.clex,
-    )?;
+        code_request.checker.clone(),
+        &toolchains,
+        **limits,
+    )
+    .await?;
 
     Ok(HttpResponse::Ok().json(response))
 }
@@ -1,7 +1,10 @@
 use actix_web::post;
 use actix_web::web::Json;
 use actix_web::{HttpResponse, web};
+use ccode_runner::ExecutionLimits;
+use ccode_runner::lang_runner::checker::CheckerKind;
 use ccode_runner::lang_runner::language_name::LanguageName;
+use ccode_runner::lang_runner::toolchain::ToolchainConfig;
 use secrecy::{ExposeSecret, SecretString};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
@@ -11,7 +14,7 @@ use crate::routes::api::v1::evaluate::{
     cache_clex_into_db, get_cached_clex_from_db, run_and_compare, verify_clex,
 };
 
-use super::{EvaluateAPIError, EvaluateCodeResponse};
+use super::{CompilationErrorResponse, EvaluateAPIError, EvaluateCodeResponse};
 
 #[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
 struct EvaluateCodeWithConstraintRequest {
@@ -32,12 +35,16 @@ struct EvaluateCodeWithConstraintRequest {
 
     #[schema(example = "1 <= Integer < 50000")]
     constraints: String,
+
+    #[serde(default)]
+    #[schema(example = "Token")]
+    checker: CheckerKind,
 }
 
 #[utoipa::path(
     responses(
         (status = 200, description = "Successfully evaluated code", body = EvaluateCodeResponse),
-        (status = 400, description = "Bad request - Possible causes: Invalid clex expression format, Invalid input format/constraints", body = String),
+        (status = 400, description = "Bad request - Possible causes: Invalid clex expression format, Invalid input format/constraints, Code failing to compile (JSON body)", content((String = "text/plain"), (CompilationErrorResponse = "application/json"))),
         (status = 404, description = "Not found", body = String),
         (status = 500, description = "Internal server error - Possible causes: Database errors, LLM errors, Code runner errors", body = String),
     )
@@ -47,6 +54,8 @@ pub async fn post_with_code_and_constraint(
     pool: web::Data<PgPool>,
     gemini_api_key: web::Data<SecretString>,
     code_request: Json<EvaluateCodeWithConstraintRequest>,
+    toolchains: web::Data<ToolchainConfig>,
+    limits: web::Data<ExecutionLimits>,
 ) -> Result<HttpResponse, EvaluateAPIError> {
     if code_request.input_format.is_empty() || code_request.constraints.is_empty() {
         return Err(EvaluateAPIError::InvalidInputFormatOrConstraints);
@@ -93,7 +102,11 @@ pub async fn post_with_code_and_constraint(
         code_request.correct_code_language.clone(),
         code_request.test_code_language.clone(),
         &clex,
-    )?;
+        code_request.checker.clone(),
+        &toolchains,
+        **limits,
+    )
+    .await?;
 
     Ok(HttpResponse::Ok().json(response))
 }
@@ -1,7 +1,10 @@
 use actix_web::post;
 use actix_web::web::Json;
 use actix_web::{HttpResponse, web};
+use ccode_runner::ExecutionLimits;
+use ccode_runner::lang_runner::checker::CheckerKind;
 use ccode_runner::lang_runner::language_name::LanguageName;
+use ccode_runner::lang_runner::toolchain::ToolchainConfig;
 use secrecy::{ExposeSecret, SecretString};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
@@ -12,7 +15,7 @@ use crate::routes::api::v1::evaluate::{
     verify_clex,
 };
 
-use super::{EvaluateAPIError, EvaluateCodeResponse};
+use super::{CompilationErrorResponse, EvaluateAPIError, EvaluateCodeResponse};
 
 #[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
 struct EvaluateCodeWithPlatformRequest {
@@ -30,12 +33,16 @@ struct EvaluateCodeWithPlatformRequest {
 
     #[schema(example = "https://www.codechef.com/problems/WAPEN")]
     problem_url: String,
+
+    #[serde(default)]
+    #[schema(example = "Token")]
+    checker: CheckerKind,
 }
 
 #[utoipa::path(
     responses(
         (status = 200, description = "Successful evaluation", body = EvaluateCodeResponse),
-        (status = 400, description = "Bad Request - Possible causes: Invalid clex expression, Invalid problem URL", body = String),
+        (status = 400, description = "Bad Request - Possible causes: Invalid clex expression, Invalid problem URL, Code failing to compile (JSON body)", content((String = "text/plain"), (CompilationErrorResponse = "application/json"))),
         (status = 404, description = "Not Found - Possible cause: Share ID not found", body = String),
         (status = 500, description = "Internal Server Error - Possible causes: Invalid Language Name in database, Runner errors, LLM generation errors, Scraper errors, Unexpected errors", body = String)
     )
@@ -45,6 +52,8 @@ pub async fn post_with_code_and_platform(
     pool: web::Data<PgPool>,
     gemini_api_key: web::Data<SecretString>,
     code_request: Json<EvaluateCodeWithPlatformRequest>,
+    toolchains: web::Data<ToolchainConfig>,
+    limits: web::Data<ExecutionLimits>,
 ) -> Result<HttpResponse, EvaluateAPIError> {
     if code_request.problem_url.is_empty() {
         return Err(EvaluateAPIError::InvalidProblemURL);
@@ -119,7 +128,11 @@ pub async fn post_with_code_and_platform(
         code_request.correct_code_language.clone(),
         code_request.test_code_language.clone(),
         &scrape_clex,
-    )?;
+        code_request.checker.clone(),
+        &toolchains,
+        **limits,
+    )
+    .await?;
 
     Ok(HttpResponse::Ok().json(response))
 }
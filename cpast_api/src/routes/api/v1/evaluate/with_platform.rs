@@ -2,7 +2,10 @@ use actix_web::post;
 use actix_web::web::Json;
 use actix_web::{HttpResponse, web};
 use anyhow::Context;
+use ccode_runner::ExecutionLimits;
+use ccode_runner::lang_runner::checker::CheckerKind;
 use ccode_runner::lang_runner::language_name::LanguageName;
+use ccode_runner::lang_runner::toolchain::ToolchainConfig;
 use secrecy::{ExposeSecret, SecretString};
 use serde::{Deserialize, Serialize};
 use sqlx::{Executor, PgPool, Postgres, Transaction};
@@ -12,7 +15,7 @@ use crate::routes::api::v1::evaluate::{
     cache_clex_into_db, cache_scrape_into_db, run_and_compare, verify_clex,
 };
 
-use super::{EvaluateAPIError, EvaluateCodeResponse};
+use super::{CompilationErrorResponse, EvaluateAPIError, EvaluateCodeResponse};
 
 #[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
 struct EvaluateCodeWithOnlyPlatformRequest {
@@ -24,6 +27,10 @@ struct EvaluateCodeWithOnlyPlatformRequest {
 
     #[schema(example = "https://www.codechef.com/problems/WAPEN")]
     problem_url: String,
+
+    #[serde(default)]
+    #[schema(example = "Token")]
+    checker: CheckerKind,
 }
 
 #[derive(Debug)]
@@ -37,7 +44,7 @@ struct ScrapeCacheStoreResponse {
 #[utoipa::path(
     responses(
         (status = 200, description = "Successful evaluation", body = EvaluateCodeResponse),
-        (status = 400, description = "Bad Request. Possibly invalid CLEX expression", body = String),
+        (status = 400, description = "Bad Request. Possibly invalid CLEX expression, Code failing to compile (JSON body)", content((String = "text/plain"), (CompilationErrorResponse = "application/json"))),
         (status = 404, description = "Problem URL not found or invalid", body = String),
         (status = 500, description = "Internal server error", body = String),
     )
@@ -47,6 +54,8 @@ pub async fn post_with_platform(
     pool: web::Data<PgPool>,
     gemini_api_key: web::Data<SecretString>,
     code_request: Json<EvaluateCodeWithOnlyPlatformRequest>,
+    toolchains: web::Data<ToolchainConfig>,
+    limits: web::Data<ExecutionLimits>,
 ) -> Result<HttpResponse, EvaluateAPIError> {
     if code_request.problem_url.is_empty() {
         return Err(EvaluateAPIError::InvalidProblemURL);
@@ -236,7 +245,11 @@ pub async fn post_with_platform(
         lang_name,
         code_request.test_code_language.clone(),
         &clex,
-    )?;
+        code_request.checker.clone(),
+        &toolchains,
+        **limits,
+    )
+    .await?;
 
     Ok(HttpResponse::Ok().json(response))
 }
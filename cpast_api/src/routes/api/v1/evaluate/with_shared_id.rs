@@ -2,7 +2,10 @@ use actix_web::post;
 use actix_web::web::Json;
 use actix_web::{HttpResponse, web};
 use anyhow::Context;
+use ccode_runner::ExecutionLimits;
+use ccode_runner::lang_runner::checker::CheckerKind;
 use ccode_runner::lang_runner::language_name::LanguageName;
+use ccode_runner::lang_runner::toolchain::ToolchainConfig;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use utoipa::ToSchema;
@@ -10,7 +13,7 @@ use uuid::{Uuid, Version};
 
 use crate::routes::api::v1::evaluate::run_and_compare;
 
-use super::{EvaluateAPIError, EvaluateCodeResponse};
+use super::{CompilationErrorResponse, EvaluateAPIError, EvaluateCodeResponse};
 
 #[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
 struct EvaluateCodeWithOnlySharedIDRequest {
@@ -22,6 +25,10 @@ struct EvaluateCodeWithOnlySharedIDRequest {
 
     #[schema(example = "01959403-d3e4-7752-85bd-a304b561692d")]
     share_id: String,
+
+    #[serde(default)]
+    #[schema(example = "Token")]
+    checker: CheckerKind,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -39,7 +46,7 @@ pub(crate) struct ShareGetResponse {
 #[utoipa::path(
     responses(
         (status = 200, description = "Successful evaluation", body = EvaluateCodeResponse),
-        (status = 400, description = "Invalid share ID or format", body = String),
+        (status = 400, description = "Invalid share ID or format, Code failing to compile (JSON body)", content((String = "text/plain"), (CompilationErrorResponse = "application/json"))),
         (status = 404, description = "Share ID not found", body = String), 
         (status = 500, description = "Server error - includes database errors or language parsing errors", body = String)
     )
@@ -48,6 +55,8 @@ pub(crate) struct ShareGetResponse {
 pub async fn post_with_shared_id(
     pool: web::Data<PgPool>,
     code_request: Json<EvaluateCodeWithOnlySharedIDRequest>,
+    toolchains: web::Data<ToolchainConfig>,
+    limits: web::Data<ExecutionLimits>,
 ) -> Result<HttpResponse, EvaluateAPIError> {
     let parsed_share_id = Uuid::parse_str(&code_request.share_id)
         .map_err(|err| EvaluateAPIError::InvalidShareId(err.to_string()))?;
@@ -69,7 +78,11 @@ pub async fn post_with_shared_id(
         correct_code_in_shared_db.language,
         code_request.test_code_language.clone(),
         &correct_code_in_shared_db.clex,
-    )?;
+        code_request.checker.clone(),
+        &toolchains,
+        **limits,
+    )
+    .await?;
 
     Ok(HttpResponse::Ok().json(response))
 }
@@ -15,10 +15,17 @@ use actix_web::web::Data;
 use actix_web::{App, HttpResponse, HttpServer, Responder, web};
 use actix_web_flash_messages::FlashMessagesFramework;
 use actix_web_flash_messages::storage::CookieMessageStore;
+use anyhow::Context;
+use ccode_runner::lang_runner::compile_cache::{CompileCache, DEFAULT_MAX_CACHE_SIZE};
+use ccode_runner::lang_runner::toolchain::ToolchainConfig;
+use ccode_runner::{
+    DEFAULT_OUTPUT_LIMIT_BYTES, DEFAULT_TIME_LIMIT_MS, ExecutionLimits, SandboxConfig,
+};
 use secrecy::{ExposeSecret, SecretString};
 use sqlx::PgPool;
 use sqlx::postgres::PgPoolOptions;
 use std::net::TcpListener;
+use std::path::Path;
 use tracing_actix_web::TracingLogger;
 use utoipa::OpenApi;
 use utoipa_rapidoc::RapiDoc;
@@ -54,6 +61,43 @@ impl Application {
             "{}:{}",
             configuration.application.host, configuration.application.port
         );
+        let toolchains = match &configuration.application.toolchain_path {
+            Some(toolchain_path) => ToolchainConfig::from_file(Path::new(toolchain_path))?,
+            None => ToolchainConfig::default(),
+        };
+        let compile_cache = match &configuration.application.compile_cache_dir {
+            Some(cache_dir) => CompileCache::new(cache_dir),
+            None => CompileCache::in_default_dir(),
+        }
+        .with_max_size(
+            configuration
+                .application
+                .compile_cache_max_size
+                .unwrap_or(DEFAULT_MAX_CACHE_SIZE),
+        );
+        let toolchains = toolchains.with_compile_cache(compile_cache);
+        let mut limits = ExecutionLimits::new()
+            .with_time_limit(
+                configuration
+                    .application
+                    .time_limit_ms
+                    .unwrap_or(DEFAULT_TIME_LIMIT_MS),
+            )
+            .with_output_limit(
+                configuration
+                    .application
+                    .output_limit_bytes
+                    .unwrap_or(DEFAULT_OUTPUT_LIMIT_BYTES),
+            );
+        if configuration.application.sandbox {
+            let sandbox = SandboxConfig::new();
+            sandbox.check_available().context(
+                "The sandbox is enabled but unavailable, as it needs unprivileged user namespaces \
+                 (which e.g. Docker's default seccomp profile blocks)",
+            )?;
+            limits = limits.with_sandbox(sandbox);
+        }
+
         let listener = TcpListener::bind(address)?;
         let port = listener.local_addr().unwrap().port();
         let server = run(
@@ -63,6 +107,8 @@ impl Application {
             configuration.application.hmac_secret,
             configuration.redis_uri,
             configuration.llm.api_key,
+            toolchains,
+            limits,
         )
         .await?;
 
@@ -90,6 +136,7 @@ async fn redirect_swagger_ui() -> impl Responder {
         .finish()
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn run(
     listener: TcpListener,
     db_pool: PgPool,
@@ -97,6 +144,8 @@ async fn run(
     hmac_secret: SecretString,
     redis_uri: SecretString,
     gemini_api_key: SecretString,
+    toolchains: ToolchainConfig,
+    limits: ExecutionLimits,
 ) -> Result<Server, anyhow::Error> {
     #[derive(OpenApi)]
     #[openapi(
@@ -109,6 +158,8 @@ async fn run(
     let base_url = Data::new(ApplicationBaseUrl(base_url));
     let secret_key = Key::from(hmac_secret.expose_secret().as_bytes());
     let gemini_api_key = Data::new(gemini_api_key);
+    let toolchains = Data::new(toolchains);
+    let limits = Data::new(limits);
     let message_store = CookieMessageStore::builder(secret_key.clone()).build();
     let message_framework = FlashMessagesFramework::builder(message_store).build();
 
@@ -142,6 +193,8 @@ async fn run(
                     .service(post_with_shared_id)
                     .service(post_with_code_and_clex)
                     .app_data(gemini_api_key.clone())
+                    .app_data(toolchains.clone())
+                    .app_data(limits.clone())
                     .service(post_with_code_and_platform)
                     .service(post_with_code_and_constraint)
                     .service(post_with_platform),
@@ -8,12 +8,33 @@ struct EvaluateCodeInputDiff {
     input: String,
     expected_output: String,
     actual_output: String,
+    diff: Option<EvaluateCodeOutputDiff>,
+}
+
+#[derive(Deserialize)]
+struct EvaluateCodeOutputDiff {
+    line: usize,
+    token: Option<EvaluateCodeTokenDiff>,
+}
+
+#[derive(Deserialize)]
+struct EvaluateCodeTokenDiff {
+    expected: Option<String>,
+    actual: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EvaluateCodeRunFailure {
+    program: String,
+    verdict: String,
+    message: String,
 }
 
 #[derive(Deserialize)]
 struct EvaluateCodeResponse {
     has_output_matched: bool,
     input_diffs: Vec<EvaluateCodeInputDiff>,
+    run_failure: Option<EvaluateCodeRunFailure>,
     clex: String,
 }
 
@@ -77,6 +98,11 @@ async fn evaluate_with_different_outputs() {
     assert!(!evaluation.input_diffs.is_empty());
     assert_eq!(evaluation.input_diffs[0].expected_output, "Hello\n");
     assert_eq!(evaluation.input_diffs[0].actual_output, "World\n");
+    let diff = evaluation.input_diffs[0].diff.as_ref().unwrap();
+    assert_eq!(diff.line, 1);
+    let token = diff.token.as_ref().unwrap();
+    assert_eq!(token.expected.as_deref(), Some("Hello"));
+    assert_eq!(token.actual.as_deref(), Some("World"));
     assert_eq!(evaluation.clex, "N[1,10]");
 }
 
@@ -94,5 +120,108 @@ async fn evaluate_code_invalid_syntax() {
 
     let response = app.post_evaluate_with_code_and_clex(&req_body).await;
 
-    assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+    assert_eq!(StatusCode::OK, response.status());
+
+    let evaluation = response.json::<EvaluateCodeResponse>().await.unwrap();
+    assert!(!evaluation.has_output_matched);
+    let failure = evaluation.run_failure.unwrap();
+    assert_eq!(failure.program, "Correct");
+    assert_eq!(failure.verdict, "RuntimeError");
+    assert!(failure.message.contains("SyntaxError"));
+}
+
+#[tokio::test]
+async fn evaluate_code_time_limit_exceeded_is_a_verdict() {
+    let app = spawn_app().await;
+
+    let req_body = serde_json::json!({
+        "correct_code": "print('Hello')",
+        "correct_code_language": "Python",
+        "test_code": "while True:\n    pass",
+        "test_code_language": "Python",
+        "clex": "N[1,10]"
+    });
+
+    let response = app.post_evaluate_with_code_and_clex(&req_body).await;
+
+    assert_eq!(StatusCode::OK, response.status());
+
+    let evaluation = response.json::<EvaluateCodeResponse>().await.unwrap();
+    assert!(!evaluation.has_output_matched);
+    let failure = evaluation.run_failure.unwrap();
+    assert_eq!(failure.program, "Test");
+    assert_eq!(failure.verdict, "TimeLimitExceeded");
+}
+
+#[tokio::test]
+async fn evaluate_code_output_limit_exceeded_is_a_verdict() {
+    let app = spawn_app().await;
+
+    let req_body = serde_json::json!({
+        "correct_code": "print('Hello')",
+        "correct_code_language": "Python",
+        "test_code": "while True:\n    print('spam' * 100000)",
+        "test_code_language": "Python",
+        "clex": "N[1,10]"
+    });
+
+    let response = app.post_evaluate_with_code_and_clex(&req_body).await;
+
+    assert_eq!(StatusCode::OK, response.status());
+
+    let evaluation = response.json::<EvaluateCodeResponse>().await.unwrap();
+    assert!(!evaluation.has_output_matched);
+    let failure = evaluation.run_failure.unwrap();
+    assert_eq!(failure.program, "Test");
+    assert_eq!(failure.verdict, "OutputLimitExceeded");
+}
+
+#[derive(Deserialize)]
+struct CompileDiagnostic {
+    file: String,
+    line: u32,
+    severity: String,
+}
+
+#[derive(Deserialize)]
+struct CompilerAttempt {
+    compiler: String,
+    launched: bool,
+    stderr: String,
+    diagnostics: Vec<CompileDiagnostic>,
+}
+
+#[derive(Deserialize)]
+struct CompilationErrorResponse {
+    attempts: Vec<CompilerAttempt>,
+}
+
+#[tokio::test]
+async fn evaluate_code_compile_error_returns_400_with_diagnostics() {
+    let app = spawn_app().await;
+
+    let req_body = serde_json::json!({
+        "correct_code": "print('Hello')",
+        "correct_code_language": "Python",
+        "test_code": "int main() {\n    return x;\n}\n",
+        "test_code_language": "Cpp",
+        "clex": "N[1,10]"
+    });
+
+    let response = app.post_evaluate_with_code_and_clex(&req_body).await;
+
+    assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+    let compilation_error = response.json::<CompilationErrorResponse>().await.unwrap();
+    let gxx = compilation_error
+        .attempts
+        .iter()
+        .find(|attempt| attempt.compiler == "g++")
+        .unwrap();
+    assert!(gxx.launched);
+    assert_eq!(gxx.diagnostics[0].line, 2);
+    assert_eq!(gxx.diagnostics[0].severity, "error");
+    // The temporary directory the code was compiled in is not leaked to the client
+    assert!(!gxx.diagnostics[0].file.contains("cpast_runner_"));
+    assert!(!gxx.stderr.contains("cpast_runner_"));
 }
@@ -147,6 +147,8 @@ pub async fn spawn_app() -> TestApp {
         c.database.database_name = format!("cpast_api_tests_{}", Uuid::new_v4().as_simple());
         // Use a random OS port
         c.application.port = 0;
+        // Keep programs which never finish from slowing the tests down
+        c.application.time_limit_ms = Some(2000);
         c
     };
 